@@ -9,7 +9,7 @@ use std::{fs, panic};
 use tauri::Emitter;
 
 use noir::{
-    handlers::{connections, queries, task},
+    handlers::{connections, history, monitor, queries, task},
     state::{self},
 };
 
@@ -54,11 +54,38 @@ fn main() {
             connections::init_connection,
             connections::disconnect,
             connections::set_schema,
+            monitor::get_top_queries,
+            monitor::get_sessions,
+            monitor::cancel_session,
+            monitor::terminate_session,
+            monitor::get_lock_tree,
             queries::sql_to_statements,
+            queries::quote_identifier,
+            queries::quote_literal,
             queries::execute_query,
+            queries::explain_query,
+            queries::get_index_advice,
             queries::enqueue_query,
             queries::execute_tx,
+            queries::dry_run,
             queries::get_columns,
+            queries::get_schema_snapshot,
+            queries::refresh_metadata,
+            queries::get_sequences,
+            queries::get_enums,
+            queries::get_domains,
+            queries::get_composite_types,
+            queries::get_materialized_views,
+            queries::refresh_materialized_view,
+            queries::get_extensions,
+            queries::get_inheritance,
+            queries::get_events,
+            queries::get_partitions,
+            queries::get_modules,
+            queries::get_object_ddl,
+            queries::alter_table,
+            queries::apply_row_edits,
+            queries::get_table_history,
             queries::get_foreign_keys,
             queries::get_primary_key,
             queries::get_functions,
@@ -73,6 +100,9 @@ fn main() {
             queries::download_csv,
             queries::invalidate_query,
             task::cancel_task_token,
+            history::get_query_history,
+            history::clear_query_history,
+            history::prune_query_history,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
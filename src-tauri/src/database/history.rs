@@ -0,0 +1,358 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use deadpool_sqlite::rusqlite::{named_params, Connection as AppConnection, Row};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ValueRef};
+use serde::{Deserialize, Serialize};
+
+use super::QueryType;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum HistoryStatus {
+    Running,
+    Completed,
+    Error,
+    Cancelled,
+}
+
+impl Display for HistoryStatus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            HistoryStatus::Running => write!(f, "Running"),
+            HistoryStatus::Completed => write!(f, "Completed"),
+            HistoryStatus::Error => write!(f, "Error"),
+            HistoryStatus::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl FromSql for HistoryStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let s: String = String::column_result(value)?;
+        match s.as_str() {
+            "Running" => Ok(HistoryStatus::Running),
+            "Completed" => Ok(HistoryStatus::Completed),
+            "Error" => Ok(HistoryStatus::Error),
+            "Cancelled" => Ok(HistoryStatus::Cancelled),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// A query run from the console, times are milliseconds since the epoch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub conn_id: String,
+    pub tab_idx: Option<usize>,
+    pub query: String,
+    pub query_type: QueryType,
+    pub status: HistoryStatus,
+    pub error: Option<String>,
+    pub row_count: Option<u64>,
+    pub affected_rows: Option<u64>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// How a query ended, recorded on its history entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryOutcome {
+    pub status: HistoryStatus,
+    pub error: Option<String>,
+    pub row_count: Option<u64>,
+    pub affected_rows: Option<u64>,
+}
+
+impl HistoryOutcome {
+    pub fn completed(row_count: u64, affected_rows: u64) -> Self {
+        HistoryOutcome {
+            status: HistoryStatus::Completed,
+            error: None,
+            row_count: Some(row_count),
+            affected_rows: Some(affected_rows),
+        }
+    }
+
+    pub fn failed(error: String) -> Self {
+        HistoryOutcome {
+            status: HistoryStatus::Error,
+            error: Some(error),
+            row_count: None,
+            affected_rows: None,
+        }
+    }
+
+    pub fn cancelled() -> Self {
+        HistoryOutcome {
+            status: HistoryStatus::Cancelled,
+            error: None,
+            row_count: None,
+            affected_rows: None,
+        }
+    }
+}
+
+/// Every filter is optional, `search` matches words of the query text and
+/// the last one as a prefix, `from` and `to` bound `started_at`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub conn_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<HistoryStatus>,
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+}
+
+/// Entries past either limit are removed, the newest are kept.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HistoryRetention {
+    pub max_entries: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        HistoryRetention {
+            max_entries: 10_000,
+            max_age_days: 90,
+        }
+    }
+}
+
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as i64
+}
+
+pub fn start_query(
+    db: &AppConnection,
+    conn_id: &str,
+    tab_idx: Option<usize>,
+    query: &str,
+    query_type: QueryType,
+) -> Result<i64> {
+    let mut statement = db.prepare(
+        "INSERT INTO query_history (
+            conn_id,
+            tab_idx,
+            query,
+            query_type,
+            status,
+            started_at
+            ) VALUES (
+                :conn_id,
+                :tab_idx,
+                :query,
+                :query_type,
+                :status,
+                :started_at
+                )",
+    )?;
+    statement.execute(named_params! {
+        ":conn_id": conn_id,
+        ":tab_idx": tab_idx,
+        ":query": query,
+        ":query_type": query_type.to_string(),
+        ":status": HistoryStatus::Running.to_string(),
+        ":started_at": now_millis(),
+    })?;
+    Ok(db.last_insert_rowid())
+}
+
+pub fn finish_query(db: &AppConnection, id: i64, outcome: &HistoryOutcome) -> Result<()> {
+    let mut statement = db.prepare(
+        "UPDATE query_history
+        SET
+            status = :status,
+            error = :error,
+            row_count = :row_count,
+            affected_rows = :affected_rows,
+            finished_at = :finished_at
+        WHERE
+            id = :id",
+    )?;
+    statement.execute(named_params! {
+        ":id": id,
+        ":status": outcome.status.to_string(),
+        ":error": outcome.error,
+        ":row_count": outcome.row_count,
+        ":affected_rows": outcome.affected_rows,
+        ":finished_at": now_millis(),
+    })?;
+    Ok(())
+}
+
+pub fn search_history(db: &AppConnection, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>> {
+    let mut statement = db.prepare(
+        "SELECT * FROM query_history
+        WHERE
+            (:search IS NULL OR id IN (
+                SELECT rowid FROM query_history_fts WHERE query_history_fts MATCH :search
+            ))
+            AND (:conn_id IS NULL OR conn_id = :conn_id)
+            AND (:status IS NULL OR status = :status)
+            AND (:from IS NULL OR started_at >= :from)
+            AND (:to IS NULL OR started_at < :to)
+        ORDER BY started_at DESC, id DESC
+        LIMIT :limit OFFSET :offset",
+    )?;
+    let search = filter.search.as_deref().and_then(fts_query);
+    let mut rows = statement.query(named_params! {
+        ":search": search,
+        ":conn_id": filter.conn_id,
+        ":status": filter.status.map(|s| s.to_string()),
+        ":from": filter.from,
+        ":to": filter.to,
+        ":limit": filter.limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+        ":offset": filter.offset.unwrap_or_default(),
+    })?;
+    let mut items = Vec::new();
+    while let Some(row) = rows.next()? {
+        items.push(history_entry(row)?);
+    }
+    Ok(items)
+}
+
+fn history_entry(row: &Row) -> Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        conn_id: row.get("conn_id")?,
+        tab_idx: row.get("tab_idx")?,
+        query: row.get("query")?,
+        query_type: row.get("query_type")?,
+        status: row.get("status")?,
+        error: row.get("error")?,
+        row_count: row.get("row_count")?,
+        affected_rows: row.get("affected_rows")?,
+        started_at: row.get("started_at")?,
+        finished_at: row.get("finished_at")?,
+    })
+}
+
+/// Quotes each word so FTS5 operators and punctuation in the search are taken
+/// literally, the last word matches as a prefix while it's being typed.
+fn fts_query(search: &str) -> Option<String> {
+    let words: Vec<String> = search
+        .split_whitespace()
+        .map(|w| format!("\"{}\"", w.replace('"', "\"\"")))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(words.join(" ") + "*")
+}
+
+/// Deletes the entries of `conn_id`, or all of them.
+pub fn clear_history(db: &AppConnection, conn_id: Option<&str>) -> Result<usize> {
+    let mut statement =
+        db.prepare("DELETE FROM query_history WHERE :conn_id IS NULL OR conn_id = :conn_id")?;
+    Ok(statement.execute(named_params! {":conn_id": conn_id})?)
+}
+
+/// Applies the retention limits and returns how many entries were removed.
+pub fn prune_history(db: &AppConnection, retention: &HistoryRetention) -> Result<usize> {
+    let max_age = Duration::from_secs(retention.max_age_days * 24 * 60 * 60);
+    let cutoff = now_millis() - max_age.as_millis() as i64;
+    let expired = db.execute(
+        "DELETE FROM query_history WHERE started_at < :cutoff",
+        named_params! {":cutoff": cutoff},
+    )?;
+    let excess = db.execute(
+        "DELETE FROM query_history WHERE id NOT IN (
+            SELECT id FROM query_history ORDER BY started_at DESC, id DESC LIMIT :max_entries
+        )",
+        named_params! {":max_entries": retention.max_entries},
+    )?;
+    Ok(expired + excess)
+}
+
+/// Queries still running when the app was closed never finished.
+pub fn cancel_running(db: &AppConnection) -> Result<usize> {
+    Ok(db.execute(
+        "UPDATE query_history SET status = :cancelled WHERE status = :running",
+        named_params! {
+            ":cancelled": HistoryStatus::Cancelled.to_string(),
+            ":running": HistoryStatus::Running.to_string(),
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod test {
+    use deadpool_sqlite::rusqlite::Connection;
+
+    use super::{
+        finish_query, prune_history, search_history, start_query, HistoryFilter, HistoryOutcome,
+        HistoryRetention, HistoryStatus,
+    };
+    use crate::database::QueryType;
+
+    #[test]
+    fn test_query_history() {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(include_str!("migrations/03-query-history/up.sql"))
+            .unwrap();
+        let id = start_query(&db, "a", Some(0), "SELECT * FROM orders", QueryType::Select).unwrap();
+        finish_query(&db, id, &HistoryOutcome::completed(3, 0)).unwrap();
+        start_query(&db, "b", None, "DELETE FROM users", QueryType::Delete).unwrap();
+
+        let search = |filter: HistoryFilter| {
+            search_history(&db, &filter)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.query)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search(HistoryFilter::default()).len(), 2);
+        assert_eq!(
+            search(HistoryFilter {
+                search: Some("from ord".to_string()),
+                ..Default::default()
+            }),
+            vec!["SELECT * FROM orders"]
+        );
+        assert_eq!(
+            search(HistoryFilter {
+                search: Some("users\" OR".to_string()),
+                ..Default::default()
+            }),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            search(HistoryFilter {
+                status: Some(HistoryStatus::Running),
+                conn_id: Some("b".to_string()),
+                ..Default::default()
+            }),
+            vec!["DELETE FROM users"]
+        );
+
+        let retention = HistoryRetention {
+            max_entries: 1,
+            max_age_days: 1,
+        };
+        assert_eq!(prune_history(&db, &retention).unwrap(), 1);
+        assert_eq!(
+            search(HistoryFilter {
+                search: Some("orders".to_string()),
+                ..Default::default()
+            }),
+            Vec::<String>::new()
+        );
+    }
+}
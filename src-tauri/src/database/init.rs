@@ -1,3 +1,4 @@
+use crate::database::history::{self, HistoryRetention};
 use crate::utils::fs::get_db_path;
 use deadpool_sqlite::rusqlite::{Connection as AppConnection, Error};
 use include_dir::{include_dir, Dir};
@@ -24,5 +25,12 @@ pub fn initialize_database(app: AppHandle) -> Result<AppConnection, Error> {
         error!("Error applying migrations: {:?}", e);
     });
 
+    let retention = HistoryRetention::default();
+    if let Err(e) =
+        history::cancel_running(&db).and_then(|_| history::prune_history(&db, &retention))
+    {
+        error!("Error cleaning up query history: {:?}", e);
+    }
+
     Ok(db)
 }
@@ -0,0 +1,31 @@
+CREATE TABLE query_history (
+  id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  conn_id TEXT NOT NULL,
+  tab_idx INTEGER,
+  query TEXT NOT NULL,
+  query_type VARCHAR(255) NOT NULL,
+  status VARCHAR(255) NOT NULL,
+  error TEXT,
+  row_count INTEGER,
+  affected_rows INTEGER,
+  started_at INTEGER NOT NULL,
+  finished_at INTEGER
+);
+
+CREATE INDEX query_history_started_at ON query_history (started_at);
+CREATE INDEX query_history_conn_id ON query_history (conn_id, started_at);
+
+CREATE VIRTUAL TABLE query_history_fts USING fts5 (
+  query,
+  content = 'query_history',
+  content_rowid = 'id'
+);
+
+CREATE TRIGGER query_history_insert AFTER INSERT ON query_history BEGIN
+  INSERT INTO query_history_fts (rowid, query) VALUES (new.id, new.query);
+END;
+
+CREATE TRIGGER query_history_delete AFTER DELETE ON query_history BEGIN
+  INSERT INTO query_history_fts (query_history_fts, rowid, query)
+  VALUES ('delete', old.id, old.query);
+END;
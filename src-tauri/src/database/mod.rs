@@ -1,7 +1,9 @@
 use std::fmt::{self, Display, Formatter};
 
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ValueRef};
 use serde::{Deserialize, Serialize};
 
+pub mod history;
 pub mod init;
 pub mod queries;
 
@@ -35,3 +37,22 @@ impl Display for QueryType {
         }
     }
 }
+
+impl FromSql for QueryType {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let s: String = String::column_result(value)?;
+        match s.as_str() {
+            "Alter" => Ok(QueryType::Alter),
+            "Create" => Ok(QueryType::Create),
+            "Delete" => Ok(QueryType::Delete),
+            "Drop" => Ok(QueryType::Drop),
+            "Insert" => Ok(QueryType::Insert),
+            "Other" => Ok(QueryType::Other),
+            "Select" => Ok(QueryType::Select),
+            "Show" => Ok(QueryType::Show),
+            "Truncate" => Ok(QueryType::Truncate),
+            "Update" => Ok(QueryType::Update),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
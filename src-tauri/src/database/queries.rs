@@ -20,7 +20,8 @@ pub fn add_connection(
             credentials,
             schema,
             name,
-            color
+            color,
+            metadata
             ) VALUES (
                 :id,
                 :dialect,
@@ -28,10 +29,12 @@ pub fn add_connection(
                 :credentials,
                 :schema,
                 :name,
-                :color
+                :color,
+                :metadata
                 )",
     )?;
     let credentials = serde_json::to_string(&conn.credentials)?;
+    let metadata = serde_json::to_string(&conn.metadata)?;
     let credentials = encrypt_data(&credentials, &key);
     statement.execute(named_params! {
         ":id": conn.id.to_string(),
@@ -41,6 +44,7 @@ pub fn add_connection(
         ":schema": conn.schema,
         ":name": conn.name,
         ":color": conn.color,
+        ":metadata": metadata,
     })?;
 
     Ok(())
@@ -0,0 +1,577 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlparser::ast::{
+    BinaryOperator, Expr, FromTable, JoinConstraint, JoinOperator, Query, SetExpr, Statement,
+    TableFactor, TableWithJoins,
+};
+
+use super::quote::{quote_ident, quote_qualified};
+use super::types::advice::{IndexAdvice, IndexReason, IndexSuggestion, IssueKind, PlanIssue};
+use super::types::config::Dialect;
+use super::types::connection::InitiatedConnection;
+use super::types::plan::NodeKind;
+
+/// Full scans below this many rows are cheap enough to not be worth an index.
+const LARGE_TABLE_ROWS: f64 = 10_000.0;
+/// Postgres truncates longer identifiers.
+const MAX_INDEX_NAME_LENGTH: usize = 63;
+
+pub async fn advise_query(
+    conn: &InitiatedConnection,
+    statement: &Statement,
+) -> Result<IndexAdvice> {
+    let plan = conn.explain_query(&statement.to_string(), false).await?;
+    let default_schema = conn.get_schema();
+    let usage = collect_statement(statement);
+
+    let mut issues: Vec<PlanIssue> = vec![];
+    for node in plan.root.walk() {
+        let issue = match node.kind {
+            NodeKind::FullScan => {
+                let table_rows = match &node.relation {
+                    Some(relation) => table_rows(conn, &usage, &default_schema, relation).await,
+                    None => None,
+                };
+                let rows = match (table_rows, node.estimated_rows) {
+                    (Some(table_rows), Some(estimated)) => Some(table_rows.max(estimated)),
+                    (table_rows, estimated) => table_rows.or(estimated),
+                };
+                match rows.is_some_and(|rows| rows >= LARGE_TABLE_ROWS) {
+                    true => PlanIssue {
+                        kind: IssueKind::FullScan,
+                        relation: node.relation.clone(),
+                        estimated_rows: rows,
+                        message: format!(
+                            "Full scan of {} (~{} rows)",
+                            node.relation.as_deref().unwrap_or("table"),
+                            rows.unwrap_or_default().round()
+                        ),
+                    },
+                    false => continue,
+                }
+            }
+            NodeKind::Sort | NodeKind::TempTable => {
+                let rows = match node.estimated_rows {
+                    Some(rows) => Some(rows),
+                    // MySQL and SQLite only estimate the tables below
+                    None => {
+                        let mut rows: Option<f64> = None;
+                        for child in node.walk().into_iter().skip(1) {
+                            let child_rows = match (&child.relation, child.estimated_rows) {
+                                (_, Some(estimated)) => Some(estimated),
+                                (Some(relation), None) => {
+                                    table_rows(conn, &usage, &default_schema, relation).await
+                                }
+                                (None, None) => None,
+                            };
+                            rows = match (rows, child_rows) {
+                                (Some(rows), Some(child_rows)) => Some(rows.max(child_rows)),
+                                (rows, child_rows) => rows.or(child_rows),
+                            };
+                        }
+                        rows
+                    }
+                };
+                if !rows.is_some_and(|rows| rows >= LARGE_TABLE_ROWS) {
+                    continue;
+                }
+                match node.kind {
+                    NodeKind::Sort => PlanIssue {
+                        kind: IssueKind::Filesort,
+                        relation: node.relation.clone(),
+                        estimated_rows: rows,
+                        message:
+                            "Rows are sorted at query time, an index matching the ORDER BY can avoid it"
+                                .to_string(),
+                    },
+                    _ => PlanIssue {
+                        kind: IssueKind::TempTable,
+                        relation: node.relation.clone(),
+                        estimated_rows: rows,
+                        message: format!(
+                            "Intermediate results are materialized ({})",
+                            node.operation
+                        ),
+                    },
+                }
+            }
+            _ => continue,
+        };
+        if !issues.contains(&issue) {
+            issues.push(issue);
+        }
+    }
+
+    let mut tables: Vec<TableInfo> = vec![];
+    for table in &usage.tables {
+        let schema = table.schema.clone().unwrap_or(default_schema.clone());
+        if tables.iter().any(|t| t.is(&schema, &table.name)) {
+            continue;
+        }
+        let columns = conn.get_columns(&schema, Some(&table.name)).await?;
+        // CTE names and unknown relations have no columns in the catalog
+        if columns.is_empty() {
+            continue;
+        }
+        let mut indices: Vec<Vec<String>> = conn
+            .get_indices(&schema, &table.name)
+            .await?
+            .into_iter()
+            .map(|i| i.columns)
+            .collect();
+        // SQLite rowid keys have no backing index
+        let primary_key = conn.get_primary_key(&schema, &table.name).await?;
+        indices.push(primary_key.into_iter().map(|c| c.column_name).collect());
+        tables.push(TableInfo {
+            schema,
+            name: table.name.clone(),
+            columns: columns.into_iter().map(|c| c.column_name).collect(),
+            indices,
+        });
+    }
+    let suggestions = suggest_indices(&usage, &tables, &conn.config.dialect, &default_schema);
+
+    Ok(IndexAdvice {
+        plan,
+        issues,
+        suggestions,
+    })
+}
+
+/// Row estimate of a relation named in the plan, plans don't name the schema
+/// so the statement's own qualifier is used.
+async fn table_rows(
+    conn: &InitiatedConnection,
+    usage: &ColumnUsage,
+    default_schema: &str,
+    relation: &str,
+) -> Option<f64> {
+    let schema = usage
+        .tables
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(relation))
+        .and_then(|t| t.schema.as_deref())
+        .unwrap_or(default_schema);
+    conn.get_row_estimate(schema, relation).await.ok().flatten()
+}
+
+struct TableInfo {
+    schema: String,
+    name: String,
+    columns: Vec<String>,
+    indices: Vec<Vec<String>>,
+}
+
+impl TableInfo {
+    fn is(&self, schema: &str, name: &str) -> bool {
+        self.schema.eq_ignore_ascii_case(schema) && self.name.eq_ignore_ascii_case(name)
+    }
+
+    fn column(&self, name: &str) -> Option<&String> {
+        self.columns.iter().find(|c| c.eq_ignore_ascii_case(name))
+    }
+
+    /// An index helps a predicate on `column` only when the column leads it.
+    fn is_covered(&self, column: &str) -> bool {
+        self.indices
+            .iter()
+            .any(|i| i.first().is_some_and(|c| c.eq_ignore_ascii_case(column)))
+    }
+}
+
+struct TableRef {
+    schema: Option<String>,
+    name: String,
+    alias: Option<String>,
+}
+
+struct ColumnRef {
+    qualifier: Option<String>,
+    column: String,
+    equality: bool,
+}
+
+#[derive(Default)]
+struct ColumnUsage {
+    tables: Vec<TableRef>,
+    filters: Vec<ColumnRef>,
+    joins: Vec<ColumnRef>,
+}
+
+fn collect_statement(statement: &Statement) -> ColumnUsage {
+    let mut usage = ColumnUsage::default();
+    match statement {
+        Statement::Query(query) => usage.query(query),
+        Statement::Update {
+            table, selection, ..
+        } => {
+            usage.table_with_joins(table);
+            if let Some(selection) = selection {
+                usage.predicate(selection);
+            }
+        }
+        Statement::Delete(delete) => {
+            let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = &delete.from;
+            from.iter()
+                .chain(delete.using.iter().flatten())
+                .for_each(|t| usage.table_with_joins(t));
+            if let Some(selection) = &delete.selection {
+                usage.predicate(selection);
+            }
+        }
+        _ => {}
+    }
+    usage
+}
+
+fn column_of(expr: &Expr) -> Option<(Option<String>, String)> {
+    match expr {
+        Expr::Identifier(ident) => Some((None, ident.value.clone())),
+        Expr::CompoundIdentifier(parts) if parts.len() >= 2 => Some((
+            Some(parts[parts.len() - 2].value.clone()),
+            parts[parts.len() - 1].value.clone(),
+        )),
+        Expr::Nested(expr) => column_of(expr),
+        _ => None,
+    }
+}
+
+impl ColumnUsage {
+    fn query(&mut self, query: &Query) {
+        if let Some(with) = &query.with {
+            with.cte_tables
+                .iter()
+                .for_each(|cte| self.query(&cte.query));
+        }
+        self.set_expr(&query.body);
+    }
+
+    fn set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => {
+                select.from.iter().for_each(|t| self.table_with_joins(t));
+                if let Some(selection) = &select.selection {
+                    self.predicate(selection);
+                }
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left);
+                self.set_expr(right);
+            }
+            SetExpr::Query(query) => self.query(query),
+            _ => {}
+        }
+    }
+
+    fn table_with_joins(&mut self, table: &TableWithJoins) {
+        self.factor(&table.relation);
+        for join in &table.joins {
+            self.factor(&join.relation);
+            let constraint = match &join.join_operator {
+                JoinOperator::Inner(c)
+                | JoinOperator::LeftOuter(c)
+                | JoinOperator::RightOuter(c)
+                | JoinOperator::FullOuter(c) => c,
+                _ => continue,
+            };
+            match constraint {
+                JoinConstraint::On(expr) => self.predicate(expr),
+                JoinConstraint::Using(columns) => {
+                    let qualifier = self
+                        .tables
+                        .last()
+                        .map(|t| t.alias.clone().unwrap_or(t.name.clone()));
+                    for column in columns.iter().filter_map(|c| c.0.last()) {
+                        self.joins.push(ColumnRef {
+                            qualifier: qualifier.clone(),
+                            column: column.value.clone(),
+                            equality: true,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn factor(&mut self, factor: &TableFactor) {
+        match factor {
+            TableFactor::Table { name, alias, .. } => {
+                if let Some((table, qualifier)) = name.0.split_last() {
+                    self.tables.push(TableRef {
+                        schema: qualifier.last().map(|s| s.value.clone()),
+                        name: table.value.clone(),
+                        alias: alias.as_ref().map(|a| a.name.value.clone()),
+                    });
+                }
+            }
+            TableFactor::Derived { subquery, .. } => self.query(subquery),
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.table_with_joins(table_with_joins),
+            _ => {}
+        }
+    }
+
+    fn filter(&mut self, expr: &Expr, equality: bool) {
+        if let Some((qualifier, column)) = column_of(expr) {
+            self.filters.push(ColumnRef {
+                qualifier,
+                column,
+                equality,
+            });
+        }
+    }
+
+    fn predicate(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And | BinaryOperator::Or,
+                right,
+            } => {
+                self.predicate(left);
+                self.predicate(right);
+            }
+            Expr::BinaryOp { left, op, right } => {
+                let equality = *op == BinaryOperator::Eq;
+                let range = matches!(
+                    op,
+                    BinaryOperator::Lt
+                        | BinaryOperator::LtEq
+                        | BinaryOperator::Gt
+                        | BinaryOperator::GtEq
+                );
+                if !equality && !range {
+                    return;
+                }
+                match (column_of(left), column_of(right)) {
+                    (Some(l), Some(r)) => {
+                        for (qualifier, column) in [l, r] {
+                            self.joins.push(ColumnRef {
+                                qualifier,
+                                column,
+                                equality,
+                            });
+                        }
+                    }
+                    (Some(_), None) => self.filter(left, equality),
+                    (None, Some(_)) => self.filter(right, equality),
+                    (None, None) => {}
+                }
+            }
+            Expr::InList {
+                expr,
+                negated: false,
+                ..
+            }
+            | Expr::IsNull(expr) => self.filter(expr, true),
+            Expr::Between {
+                expr,
+                negated: false,
+                ..
+            } => self.filter(expr, false),
+            Expr::Like {
+                expr,
+                negated: false,
+                pattern,
+                ..
+            } => {
+                // only prefix matches can use an index
+                if let Expr::Value(sqlparser::ast::Value::SingleQuotedString(pattern)) =
+                    pattern.as_ref()
+                {
+                    if !pattern.starts_with('%') && !pattern.starts_with('_') {
+                        self.filter(expr, false);
+                    }
+                }
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                if !negated {
+                    self.filter(expr, true);
+                }
+                self.query(subquery);
+            }
+            Expr::Exists { subquery, .. } | Expr::Subquery(subquery) => self.query(subquery),
+            Expr::Nested(expr) => self.predicate(expr),
+            _ => {}
+        }
+    }
+
+    /// Table a column reference belongs to, unqualified columns are looked up
+    /// in the catalog columns of the tables in the statement.
+    fn resolve<'a>(
+        &self,
+        column: &ColumnRef,
+        tables: &'a [TableInfo],
+        default_schema: &str,
+    ) -> Option<&'a TableInfo> {
+        let Some(qualifier) = &column.qualifier else {
+            let mut candidates = tables.iter().filter(|t| t.column(&column.column).is_some());
+            let table = candidates.next()?;
+            if candidates.next().is_some() {
+                return None;
+            }
+            return Some(table);
+        };
+        let table = self.tables.iter().find(|t| {
+            t.alias
+                .as_ref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(qualifier))
+                || t.name.eq_ignore_ascii_case(qualifier)
+        })?;
+        let schema = table.schema.as_deref().unwrap_or(default_schema);
+        tables.iter().find(|t| t.is(schema, &table.name))
+    }
+}
+
+/// SQLite qualifies the index instead of the table, the others create the
+/// index in the table's schema.
+fn create_index(dialect: &Dialect, table: &TableInfo, columns: &[String]) -> String {
+    let name: String = format!("idx_{}_{}", table.name, columns.join("_"))
+        .chars()
+        .take(MAX_INDEX_NAME_LENGTH)
+        .collect();
+    let (name, target) = match dialect {
+        Dialect::Sqlite => (
+            quote_qualified(dialect, &[&table.schema, &name]),
+            quote_ident(dialect, &table.name),
+        ),
+        _ => (
+            quote_ident(dialect, &name),
+            quote_qualified(dialect, &[&table.schema, &table.name]),
+        ),
+    };
+    let columns: Vec<String> = columns.iter().map(|c| quote_ident(dialect, c)).collect();
+    format!(
+        "CREATE INDEX {} ON {} ({});",
+        name,
+        target,
+        columns.join(", ")
+    )
+}
+
+type FilterColumns = (Vec<String>, Vec<String>);
+
+fn suggest_indices(
+    usage: &ColumnUsage,
+    tables: &[TableInfo],
+    dialect: &Dialect,
+    default_schema: &str,
+) -> Vec<IndexSuggestion> {
+    // equality and range columns of each schema and table
+    let mut filters: HashMap<(&str, &str), FilterColumns> = HashMap::new();
+    for column in &usage.filters {
+        let Some(table) = usage.resolve(column, tables, default_schema) else {
+            continue;
+        };
+        let Some(name) = table.column(&column.column) else {
+            continue;
+        };
+        let (equality, range) = filters.entry((&table.schema, &table.name)).or_default();
+        let target = match column.equality {
+            true => equality,
+            false => range,
+        };
+        if !target.contains(name) {
+            target.push(name.clone());
+        }
+    }
+
+    let mut suggestions: Vec<IndexSuggestion> = vec![];
+    for table in tables {
+        if let Some((equality, range)) = filters.get(&(table.schema.as_str(), table.name.as_str()))
+        {
+            let covered = equality.iter().chain(range).any(|c| table.is_covered(c));
+            if !covered {
+                // equality columns first, a range column can only use the index as the last key
+                let mut columns = equality.clone();
+                columns.extend(range.first().cloned());
+                suggestions.push(IndexSuggestion {
+                    statement: create_index(dialect, table, &columns),
+                    schema: table.schema.clone(),
+                    table: table.name.clone(),
+                    columns,
+                    reason: IndexReason::Filter,
+                });
+            }
+        }
+    }
+
+    for column in &usage.joins {
+        let Some(table) = usage.resolve(column, tables, default_schema) else {
+            continue;
+        };
+        let Some(name) = table.column(&column.column) else {
+            continue;
+        };
+        let suggested = suggestions
+            .iter()
+            .any(|s| table.is(&s.schema, &s.table) && s.columns.first() == Some(name));
+        if table.is_covered(name) || suggested {
+            continue;
+        }
+        let columns = vec![name.clone()];
+        suggestions.push(IndexSuggestion {
+            statement: create_index(dialect, table, &columns),
+            schema: table.schema.clone(),
+            table: table.name.clone(),
+            columns,
+            reason: IndexReason::Join,
+        });
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod test {
+    use sqlparser::{dialect::GenericDialect, parser::Parser};
+
+    use super::{collect_statement, create_index, suggest_indices, TableInfo};
+    use crate::engine::types::{advice::IndexReason, config::Dialect};
+
+    #[test]
+    fn test_suggest_uncovered_filter_and_join_columns() {
+        let sql = "SELECT * FROM orders o JOIN sales.users u ON u.id = o.user_id \
+            WHERE o.status = 'open' AND o.created_at > '2024-01-01' AND u.id = 3";
+        let statement = Parser::parse_sql(&GenericDialect {}, sql)
+            .unwrap()
+            .remove(0);
+        let usage = collect_statement(&statement);
+        let tables = vec![
+            TableInfo {
+                schema: "public".to_string(),
+                name: "orders".to_string(),
+                columns: vec!["id", "user_id", "status", "created_at"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                indices: vec![vec!["id".to_string()]],
+            },
+            TableInfo {
+                schema: "sales".to_string(),
+                name: "users".to_string(),
+                columns: vec!["id".to_string(), "name".to_string()],
+                indices: vec![vec!["id".to_string()]],
+            },
+        ];
+        let suggestions = suggest_indices(&usage, &tables, &Dialect::Postgresql, "public");
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].columns, vec!["status", "created_at"]);
+        assert_eq!(
+            suggestions[0].statement,
+            "CREATE INDEX \"idx_orders_status_created_at\" ON \"public\".\"orders\" (\"status\", \"created_at\");"
+        );
+        assert_eq!(suggestions[1].reason, IndexReason::Join);
+        assert_eq!(suggestions[1].columns, vec!["user_id"]);
+        assert_eq!(
+            create_index(&Dialect::Sqlite, &tables[1], &["name".to_string()]),
+            "CREATE INDEX \"sales\".\"idx_users_name\" ON \"users\" (\"name\");"
+        );
+    }
+}
@@ -0,0 +1,934 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use super::quote::{quote_ident, quote_literal, quote_qualified};
+use super::types::alter::{ColumnDefinition, TableChange};
+use super::types::catalog::{
+    Column, Constraint, ConstraintType, DefaultKind, ForeignKey, Index, TableStructure,
+};
+use super::types::config::Dialect;
+
+const REFERENTIAL_ACTIONS: [&str; 5] = [
+    "CASCADE",
+    "RESTRICT",
+    "SET NULL",
+    "SET DEFAULT",
+    "NO ACTION",
+];
+
+/// Turns a table designer change set into the DDL script for the dialect,
+/// `structure` is the table as `get_table_structure` returns it.
+///
+/// Postgresql and MySQL get one statement per change. SQLite only alters
+/// columns and indices in place, any other change rebuilds the table: a new
+/// table is created from the changed structure, the rows are copied over and
+/// the indices and triggers are recreated. noir's SQLite connections don't
+/// enforce foreign keys, so the rebuild runs in a single transaction.
+pub fn alter_table(
+    dialect: &Dialect,
+    schema: &str,
+    structure: &TableStructure,
+    changes: &[TableChange],
+) -> Result<Vec<String>> {
+    let mut table = structure.clone();
+    // SQLite rebuilds copy each remaining column from its original name
+    let mut sources: HashMap<String, String> = structure
+        .columns
+        .iter()
+        .map(|c| (c.column_name.clone(), c.column_name.clone()))
+        .collect();
+    let mut statements = vec![];
+    for change in changes {
+        let before = table.clone();
+        apply(&mut table, change)?;
+        match change {
+            TableChange::AddColumn { column } => {
+                sources.remove(&column.column_name);
+            }
+            TableChange::DropColumn { column_name } => {
+                sources.remove(column_name);
+            }
+            TableChange::RenameColumn {
+                column_name,
+                new_name,
+            } => {
+                if let Some(source) = sources.remove(column_name) {
+                    sources.insert(new_name.clone(), source);
+                }
+            }
+            _ => {}
+        }
+        match dialect {
+            Dialect::Postgresql => statements.extend(postgres_change(schema, &table, change)?),
+            Dialect::Mysql | Dialect::MariaDB => {
+                statements.extend(mysql_change(dialect, schema, &before, &table, change)?)
+            }
+            Dialect::Sqlite => {}
+        }
+    }
+    if let Dialect::Sqlite = dialect {
+        statements = match changes.iter().all(alters_in_place) {
+            true => changes
+                .iter()
+                .map(|c| sqlite_change(&structure.table, c))
+                .collect::<Result<_>>()?,
+            false => rebuild_sqlite_table(&table, &sources),
+        };
+    }
+    Ok(statements)
+}
+
+/// Applies a change to the structure, so the following changes see the
+/// columns, keys and indices as they will be.
+fn apply(table: &mut TableStructure, change: &TableChange) -> Result<()> {
+    let name = table.table.clone();
+    let missing = |kind: &str, item: &str| anyhow!("{} {} does not exist in {}", kind, item, name);
+    let exists = |kind: &str, item: &str| anyhow!("{} {} already exists in {}", kind, item, name);
+    match change {
+        TableChange::AddColumn { column } => {
+            if find_column(table, &column.column_name).is_some() {
+                return Err(exists("Column", &column.column_name));
+            }
+            table.columns.push(Column {
+                table_name: name.clone(),
+                column_name: column.column_name.clone(),
+                ordinal_position: table.columns.len() as i64 + 1,
+                data_type: base_type(&column.column_type),
+                column_type: column.column_type.clone(),
+                is_nullable: column.is_nullable,
+                column_default: column.column_default.clone(),
+                default_kind: column.column_default.as_deref().map(DefaultKind::of),
+                is_auto_increment: column.is_auto_increment,
+                comment: column.comment.clone(),
+                ..Default::default()
+            });
+        }
+        TableChange::DropColumn { column_name } => {
+            find_column(table, column_name).ok_or_else(|| missing("Column", column_name))?;
+            let of = |c: &String| c == column_name;
+            table.columns.retain(|c| !of(&c.column_name));
+            table.primary_key.retain(|k| !of(&k.column_name));
+            table.indices.retain(|i| !i.columns.iter().any(of));
+            table.constraints.retain(|c| !c.columns.iter().any(of));
+            let dropped: Vec<String> = table
+                .foreign_keys
+                .iter()
+                .filter(|k| of(&k.column_name))
+                .map(|k| k.constraint_name.clone())
+                .collect();
+            table
+                .foreign_keys
+                .retain(|k| !dropped.contains(&k.constraint_name));
+        }
+        TableChange::RenameColumn {
+            column_name,
+            new_name,
+        } => {
+            if find_column(table, new_name).is_some() {
+                return Err(exists("Column", new_name));
+            }
+            let column =
+                find_column(table, column_name).ok_or_else(|| missing("Column", column_name))?;
+            column.column_name = new_name.clone();
+            let rename = |c: &mut String| {
+                if c == column_name {
+                    *c = new_name.clone();
+                }
+            };
+            table
+                .primary_key
+                .iter_mut()
+                .for_each(|k| rename(&mut k.column_name));
+            table
+                .foreign_keys
+                .iter_mut()
+                .for_each(|k| rename(&mut k.column_name));
+            for index in table.indices.iter_mut() {
+                if index.columns.contains(column_name) {
+                    index.columns.iter_mut().for_each(rename);
+                    // the stored definition still has the old name
+                    index.definition = None;
+                }
+            }
+            for constraint in table.constraints.iter_mut() {
+                constraint.columns.iter_mut().for_each(rename);
+            }
+        }
+        TableChange::ChangeType {
+            column_name,
+            column_type,
+        } => {
+            let column =
+                find_column(table, column_name).ok_or_else(|| missing("Column", column_name))?;
+            column.data_type = base_type(column_type);
+            column.column_type = column_type.clone();
+        }
+        TableChange::SetNullable {
+            column_name,
+            is_nullable,
+        } => {
+            find_column(table, column_name)
+                .ok_or_else(|| missing("Column", column_name))?
+                .is_nullable = *is_nullable;
+        }
+        TableChange::SetDefault {
+            column_name,
+            column_default,
+        } => {
+            let column =
+                find_column(table, column_name).ok_or_else(|| missing("Column", column_name))?;
+            column.column_default = Some(column_default.clone());
+            column.default_kind = Some(DefaultKind::of(column_default));
+        }
+        TableChange::DropDefault { column_name } => {
+            let column =
+                find_column(table, column_name).ok_or_else(|| missing("Column", column_name))?;
+            column.column_default = None;
+            column.default_kind = None;
+        }
+        TableChange::AddIndex {
+            index_name,
+            columns,
+            is_unique,
+        } => {
+            if table.indices.iter().any(|i| &i.index_name == index_name) {
+                return Err(exists("Index", index_name));
+            }
+            table.indices.push(Index {
+                index_name: index_name.clone(),
+                table_name: name.clone(),
+                columns: columns.clone(),
+                is_unique: *is_unique,
+                ..Default::default()
+            });
+        }
+        TableChange::DropIndex { index_name } => {
+            let count = table.indices.len();
+            table.indices.retain(|i| &i.index_name != index_name);
+            if table.indices.len() == count {
+                return Err(missing("Index", index_name));
+            }
+        }
+        TableChange::AddForeignKey {
+            constraint_name,
+            columns,
+            referenced_table_name,
+            referenced_columns,
+            update_rule,
+            delete_rule,
+        } => {
+            if columns.len() != referenced_columns.len() {
+                return Err(anyhow!(
+                    "Foreign key {} references {} columns with {}",
+                    constraint_name,
+                    referenced_columns.len(),
+                    columns.len()
+                ));
+            }
+            for rule in update_rule.iter().chain(delete_rule) {
+                referential_action(rule)?;
+            }
+            table
+                .foreign_keys
+                .extend(columns.iter().zip(referenced_columns).enumerate().map(
+                    |(i, (column, referenced))| ForeignKey {
+                        constraint_name: constraint_name.clone(),
+                        table_name: name.clone(),
+                        column_name: column.clone(),
+                        ordinal_position: i as i64 + 1,
+                        referenced_table_name: referenced_table_name.clone(),
+                        referenced_column_name: Some(referenced.clone()),
+                        update_rule: update_rule.clone(),
+                        delete_rule: delete_rule.clone(),
+                        ..Default::default()
+                    },
+                ));
+        }
+        TableChange::DropForeignKey { constraint_name } => {
+            let count = table.foreign_keys.len();
+            table
+                .foreign_keys
+                .retain(|k| &k.constraint_name != constraint_name);
+            if table.foreign_keys.len() == count {
+                return Err(missing("Foreign key", constraint_name));
+            }
+        }
+        TableChange::AddUnique {
+            constraint_name,
+            columns,
+        } => table.constraints.push(Constraint {
+            constraint_name: constraint_name.clone(),
+            table_name: name.clone(),
+            constraint_type: ConstraintType::Unique,
+            columns: columns.clone(),
+            ..Default::default()
+        }),
+        TableChange::AddCheck {
+            constraint_name,
+            check_clause,
+        } => table.constraints.push(Constraint {
+            constraint_name: constraint_name.clone(),
+            table_name: name.clone(),
+            constraint_type: ConstraintType::Check,
+            check_clause: Some(check_clause.clone()),
+            ..Default::default()
+        }),
+        TableChange::DropConstraint { constraint_name } => {
+            let count = table.constraints.len();
+            table
+                .constraints
+                .retain(|c| &c.constraint_name != constraint_name);
+            if table.constraints.len() == count {
+                return Err(missing("Constraint", constraint_name));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn find_column<'a>(table: &'a mut TableStructure, name: &str) -> Option<&'a mut Column> {
+    table.columns.iter_mut().find(|c| c.column_name == name)
+}
+
+fn base_type(column_type: &str) -> String {
+    column_type
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// Referential actions are written into the statement, only the keywords
+/// are accepted.
+fn referential_action(rule: &str) -> Result<&'static str> {
+    REFERENTIAL_ACTIONS
+        .into_iter()
+        .find(|a| a.eq_ignore_ascii_case(rule.trim()))
+        .ok_or_else(|| anyhow!("Unknown referential action {}", rule))
+}
+
+fn idents(dialect: &Dialect, names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| quote_ident(dialect, n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn column_definition(dialect: &Dialect, column: &ColumnDefinition) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote_ident(dialect, &column.column_name),
+        column.column_type
+    );
+    if column.is_auto_increment {
+        match dialect {
+            Dialect::Postgresql => sql.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+            Dialect::Mysql | Dialect::MariaDB => sql.push_str(" AUTO_INCREMENT"),
+            // only an INTEGER PRIMARY KEY is filled by SQLite
+            Dialect::Sqlite => {}
+        }
+    }
+    if !column.is_nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.column_default {
+        sql.push_str(&format!(" DEFAULT {}", default));
+    }
+    if let (Dialect::Mysql | Dialect::MariaDB, Some(comment)) = (dialect, &column.comment) {
+        sql.push_str(&format!(" COMMENT {}", quote_literal(dialect, comment)));
+    }
+    sql
+}
+
+fn index_statement(
+    dialect: &Dialect,
+    table: &str,
+    name: &str,
+    columns: &[String],
+    unique: bool,
+) -> String {
+    format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if unique { "UNIQUE " } else { "" },
+        quote_ident(dialect, name),
+        table,
+        idents(dialect, columns)
+    )
+}
+
+fn foreign_key_clause(
+    dialect: &Dialect,
+    schema: Option<&str>,
+    keys: &[&ForeignKey],
+) -> Result<String> {
+    let Some(first) = keys.first() else {
+        return Ok(String::new());
+    };
+    let columns: Vec<String> = keys.iter().map(|k| k.column_name.clone()).collect();
+    let referenced: Vec<String> = keys
+        .iter()
+        .filter_map(|k| k.referenced_column_name.clone())
+        .collect();
+    let referenced_table = match schema {
+        Some(schema) => quote_qualified(dialect, &[schema, &first.referenced_table_name]),
+        None => quote_ident(dialect, &first.referenced_table_name),
+    };
+    let mut sql = format!(
+        "FOREIGN KEY ({}) REFERENCES {}",
+        idents(dialect, &columns),
+        referenced_table
+    );
+    // SQLite keys may reference the primary key implicitly
+    if !referenced.is_empty() {
+        sql.push_str(&format!(" ({})", idents(dialect, &referenced)));
+    }
+    for (clause, rule) in [
+        ("UPDATE", &first.update_rule),
+        ("DELETE", &first.delete_rule),
+    ] {
+        match rule.as_deref().map(referential_action).transpose()? {
+            None | Some("NO ACTION") => {}
+            Some(action) => sql.push_str(&format!(" ON {} {}", clause, action)),
+        }
+    }
+    Ok(sql)
+}
+
+fn postgres_change(
+    schema: &str,
+    table: &TableStructure,
+    change: &TableChange,
+) -> Result<Vec<String>> {
+    let dialect = &Dialect::Postgresql;
+    let q = |name: &str| quote_ident(dialect, name);
+    let name = quote_qualified(dialect, &[schema, &table.table]);
+    let alter = |action: String| format!("ALTER TABLE {} {}", name, action);
+    let statements = match change {
+        TableChange::AddColumn { column } => {
+            let mut statements = vec![alter(format!(
+                "ADD COLUMN {}",
+                column_definition(dialect, column)
+            ))];
+            if let Some(comment) = &column.comment {
+                statements.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {}",
+                    name,
+                    q(&column.column_name),
+                    quote_literal(dialect, comment)
+                ));
+            }
+            statements
+        }
+        TableChange::DropColumn { column_name } => {
+            vec![alter(format!("DROP COLUMN {}", q(column_name)))]
+        }
+        TableChange::RenameColumn {
+            column_name,
+            new_name,
+        } => vec![alter(format!(
+            "RENAME COLUMN {} TO {}",
+            q(column_name),
+            q(new_name)
+        ))],
+        TableChange::ChangeType {
+            column_name,
+            column_type,
+        } => vec![alter(format!(
+            "ALTER COLUMN {} TYPE {}",
+            q(column_name),
+            column_type
+        ))],
+        TableChange::SetNullable {
+            column_name,
+            is_nullable,
+        } => vec![alter(format!(
+            "ALTER COLUMN {} {} NOT NULL",
+            q(column_name),
+            if *is_nullable { "DROP" } else { "SET" }
+        ))],
+        TableChange::SetDefault {
+            column_name,
+            column_default,
+        } => vec![alter(format!(
+            "ALTER COLUMN {} SET DEFAULT {}",
+            q(column_name),
+            column_default
+        ))],
+        TableChange::DropDefault { column_name } => {
+            vec![alter(format!(
+                "ALTER COLUMN {} DROP DEFAULT",
+                q(column_name)
+            ))]
+        }
+        TableChange::AddIndex {
+            index_name,
+            columns,
+            is_unique,
+        } => vec![index_statement(
+            dialect, &name, index_name, columns, *is_unique,
+        )],
+        TableChange::DropIndex { index_name } => vec![format!(
+            "DROP INDEX {}",
+            quote_qualified(dialect, &[schema, index_name])
+        )],
+        TableChange::AddForeignKey {
+            constraint_name, ..
+        } => {
+            let keys: Vec<&ForeignKey> = table
+                .foreign_keys
+                .iter()
+                .filter(|k| &k.constraint_name == constraint_name)
+                .collect();
+            vec![alter(format!(
+                "ADD CONSTRAINT {} {}",
+                q(constraint_name),
+                foreign_key_clause(dialect, Some(schema), &keys)?
+            ))]
+        }
+        TableChange::AddUnique {
+            constraint_name,
+            columns,
+        } => vec![alter(format!(
+            "ADD CONSTRAINT {} UNIQUE ({})",
+            q(constraint_name),
+            idents(dialect, columns)
+        ))],
+        TableChange::AddCheck {
+            constraint_name,
+            check_clause,
+        } => vec![alter(format!(
+            "ADD CONSTRAINT {} CHECK ({})",
+            q(constraint_name),
+            check_clause
+        ))],
+        TableChange::DropForeignKey { constraint_name }
+        | TableChange::DropConstraint { constraint_name } => {
+            vec![alter(format!("DROP CONSTRAINT {}", q(constraint_name)))]
+        }
+    };
+    Ok(statements)
+}
+
+/// MySQL can only change a column's type or nullability by restating the
+/// whole column, `after` has it as it is once the change is applied.
+fn mysql_change(
+    dialect: &Dialect,
+    schema: &str,
+    before: &TableStructure,
+    after: &TableStructure,
+    change: &TableChange,
+) -> Result<Vec<String>> {
+    let q = |name: &str| quote_ident(dialect, name);
+    let name = quote_qualified(dialect, &[schema, &after.table]);
+    let alter = |action: String| format!("ALTER TABLE {} {}", name, action);
+    let modify = |column_name: &String| {
+        after
+            .columns
+            .iter()
+            .find(|c| &c.column_name == column_name)
+            .map(|c| alter(format!("MODIFY COLUMN {}", mysql_column(dialect, c))))
+            .ok_or_else(|| anyhow!("Column {} does not exist in {}", column_name, after.table))
+    };
+    let statements = match change {
+        TableChange::AddColumn { column } => vec![alter(format!(
+            "ADD COLUMN {}",
+            column_definition(dialect, column)
+        ))],
+        TableChange::DropColumn { column_name } => {
+            vec![alter(format!("DROP COLUMN {}", q(column_name)))]
+        }
+        TableChange::RenameColumn {
+            column_name,
+            new_name,
+        } => vec![alter(format!(
+            "RENAME COLUMN {} TO {}",
+            q(column_name),
+            q(new_name)
+        ))],
+        TableChange::ChangeType { column_name, .. }
+        | TableChange::SetNullable { column_name, .. } => vec![modify(column_name)?],
+        TableChange::SetDefault {
+            column_name,
+            column_default,
+        } => vec![alter(format!(
+            "ALTER COLUMN {} SET DEFAULT {}",
+            q(column_name),
+            column_default
+        ))],
+        TableChange::DropDefault { column_name } => {
+            vec![alter(format!(
+                "ALTER COLUMN {} DROP DEFAULT",
+                q(column_name)
+            ))]
+        }
+        TableChange::AddIndex {
+            index_name,
+            columns,
+            is_unique,
+        } => vec![index_statement(
+            dialect, &name, index_name, columns, *is_unique,
+        )],
+        TableChange::DropIndex { index_name } => {
+            vec![format!("DROP INDEX {} ON {}", q(index_name), name)]
+        }
+        TableChange::AddForeignKey {
+            constraint_name, ..
+        } => {
+            let keys: Vec<&ForeignKey> = after
+                .foreign_keys
+                .iter()
+                .filter(|k| &k.constraint_name == constraint_name)
+                .collect();
+            vec![alter(format!(
+                "ADD CONSTRAINT {} {}",
+                q(constraint_name),
+                foreign_key_clause(dialect, Some(schema), &keys)?
+            ))]
+        }
+        TableChange::DropForeignKey { constraint_name } => {
+            vec![alter(format!("DROP FOREIGN KEY {}", q(constraint_name)))]
+        }
+        TableChange::AddUnique {
+            constraint_name,
+            columns,
+        } => vec![alter(format!(
+            "ADD CONSTRAINT {} UNIQUE ({})",
+            q(constraint_name),
+            idents(dialect, columns)
+        ))],
+        TableChange::AddCheck {
+            constraint_name,
+            check_clause,
+        } => vec![alter(format!(
+            "ADD CONSTRAINT {} CHECK ({})",
+            q(constraint_name),
+            check_clause
+        ))],
+        TableChange::DropConstraint { constraint_name } => {
+            // unique constraints are indices to MySQL
+            let unique = before.constraints.iter().any(|c| {
+                &c.constraint_name == constraint_name && c.constraint_type == ConstraintType::Unique
+            });
+            let kind = if unique { "INDEX" } else { "CONSTRAINT" };
+            vec![alter(format!("DROP {} {}", kind, q(constraint_name)))]
+        }
+    };
+    Ok(statements)
+}
+
+/// Restates an existing MySQL column, keeping what `MODIFY COLUMN` would
+/// otherwise reset.
+fn mysql_column(dialect: &Dialect, column: &Column) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote_ident(dialect, &column.column_name),
+        column.column_type
+    );
+    if let Some(collation) = &column.collation_name {
+        sql.push_str(&format!(" COLLATE {}", collation));
+    }
+    if let (Some(generated), Some(expression)) = (&column.generated, &column.generation_expression)
+    {
+        sql.push_str(&format!(
+            " GENERATED ALWAYS AS ({}) {}",
+            expression, generated
+        ));
+    }
+    if !column.is_nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = mysql_default(dialect, column) {
+        sql.push_str(&format!(" DEFAULT {}", default));
+    }
+    if column.is_auto_increment {
+        sql.push_str(" AUTO_INCREMENT");
+    }
+    if let Some(comment) = &column.comment {
+        sql.push_str(&format!(" COMMENT {}", quote_literal(dialect, comment)));
+    }
+    sql
+}
+
+/// MySQL reports string defaults unquoted and expressions without the
+/// parentheses they are declared with, MariaDB reports both as SQL.
+fn mysql_default(dialect: &Dialect, column: &Column) -> Option<String> {
+    let default = column.column_default.as_deref()?;
+    let sql = match column.default_kind {
+        Some(DefaultKind::Expression)
+            if default.starts_with('(')
+                || default
+                    .to_ascii_uppercase()
+                    .starts_with("CURRENT_TIMESTAMP") =>
+        {
+            default.to_string()
+        }
+        Some(DefaultKind::Expression) => format!("({})", default),
+        _ if default.starts_with('\'')
+            || default.parse::<f64>().is_ok()
+            || default.eq_ignore_ascii_case("NULL") =>
+        {
+            default.to_string()
+        }
+        _ => quote_literal(dialect, default),
+    };
+    Some(sql)
+}
+
+fn alters_in_place(change: &TableChange) -> bool {
+    matches!(
+        change,
+        TableChange::AddColumn { .. }
+            | TableChange::DropColumn { .. }
+            | TableChange::RenameColumn { .. }
+            | TableChange::AddIndex { .. }
+            | TableChange::DropIndex { .. }
+    )
+}
+
+fn sqlite_change(table: &str, change: &TableChange) -> Result<String> {
+    let dialect = &Dialect::Sqlite;
+    let q = |name: &str| quote_ident(dialect, name);
+    let alter = |action: String| format!("ALTER TABLE {} {}", q(table), action);
+    let statement = match change {
+        TableChange::AddColumn { column } => {
+            alter(format!("ADD COLUMN {}", column_definition(dialect, column)))
+        }
+        TableChange::DropColumn { column_name } => alter(format!("DROP COLUMN {}", q(column_name))),
+        TableChange::RenameColumn {
+            column_name,
+            new_name,
+        } => alter(format!(
+            "RENAME COLUMN {} TO {}",
+            q(column_name),
+            q(new_name)
+        )),
+        TableChange::AddIndex {
+            index_name,
+            columns,
+            is_unique,
+        } => index_statement(dialect, &q(table), index_name, columns, *is_unique),
+        TableChange::DropIndex { index_name } => format!("DROP INDEX {}", q(index_name)),
+        _ => {
+            return Err(anyhow!(
+                "SQLite can't alter a table in place for {:?}",
+                change
+            ))
+        }
+    };
+    Ok(statement)
+}
+
+/// The table rebuild of https://www.sqlite.org/lang_altertable.html#otheralter,
+/// `legacy_alter_table` keeps the rename from checking views that refer to
+/// the dropped table.
+fn rebuild_sqlite_table(table: &TableStructure, sources: &HashMap<String, String>) -> Vec<String> {
+    let dialect = &Dialect::Sqlite;
+    let q = |name: &str| quote_ident(dialect, name);
+    let name = q(&table.table);
+    let new_name = q(&format!("new_{}", table.table));
+
+    let mut lines: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|c| !c.is_hidden)
+        .map(|c| {
+            let mut sql = format!("{} {}", q(&c.column_name), c.column_type);
+            if !c.is_nullable {
+                sql.push_str(" NOT NULL");
+            }
+            match (&c.generated, &c.generation_expression, &c.column_default) {
+                (Some(generated), Some(expression), _) => sql.push_str(&format!(
+                    " GENERATED ALWAYS AS ({}) {}",
+                    expression, generated
+                )),
+                (_, _, Some(default)) => sql.push_str(&format!(" DEFAULT {}", default)),
+                _ => {}
+            }
+            sql
+        })
+        .collect();
+    let mut primary_key = table.primary_key.clone();
+    primary_key.sort_by_key(|k| k.ordinal_position);
+    if !primary_key.is_empty() {
+        let columns: Vec<String> = primary_key.into_iter().map(|k| k.column_name).collect();
+        lines.push(format!("PRIMARY KEY ({})", idents(dialect, &columns)));
+    }
+    for constraint in &table.constraints {
+        // auto index and position names are generated by SQLite
+        let named = !constraint.constraint_name.starts_with("sqlite_autoindex_")
+            && constraint.constraint_name.parse::<u64>().is_err();
+        let prefix = match named {
+            true => format!("CONSTRAINT {} ", q(&constraint.constraint_name)),
+            false => String::new(),
+        };
+        lines.push(match constraint.constraint_type {
+            ConstraintType::Unique => format!(
+                "{}UNIQUE ({})",
+                prefix,
+                idents(dialect, &constraint.columns)
+            ),
+            ConstraintType::Check => format!(
+                "{}CHECK ({})",
+                prefix,
+                constraint.check_clause.clone().unwrap_or_default()
+            ),
+        });
+    }
+    let mut foreign_keys: Vec<(&String, Vec<&ForeignKey>)> = vec![];
+    for key in &table.foreign_keys {
+        match foreign_keys
+            .iter_mut()
+            .find(|(n, _)| *n == &key.constraint_name)
+        {
+            Some((_, keys)) => keys.push(key),
+            None => foreign_keys.push((&key.constraint_name, vec![key])),
+        }
+    }
+    for (_, mut keys) in foreign_keys {
+        keys.sort_by_key(|k| k.ordinal_position);
+        // rules were checked when the key was added or come from the catalog
+        if let Ok(clause) = foreign_key_clause(dialect, None, &keys) {
+            lines.push(clause);
+        }
+    }
+
+    let copied: Vec<(&String, &String)> = table
+        .columns
+        .iter()
+        .filter(|c| c.generated.is_none() && !c.is_hidden)
+        .filter_map(|c| sources.get(&c.column_name).map(|s| (&c.column_name, s)))
+        .collect();
+    let targets: Vec<String> = copied.iter().map(|(t, _)| (*t).clone()).collect();
+    let sources: Vec<String> = copied.iter().map(|(_, s)| (*s).clone()).collect();
+
+    let mut statements = vec![
+        "PRAGMA legacy_alter_table = ON".to_string(),
+        format!(
+            "CREATE TABLE {} (\n    {}\n)",
+            new_name,
+            lines.join(",\n    ")
+        ),
+    ];
+    if !copied.is_empty() {
+        statements.push(format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            new_name,
+            idents(dialect, &targets),
+            idents(dialect, &sources),
+            name
+        ));
+    }
+    statements.push(format!("DROP TABLE {}", name));
+    statements.push(format!("ALTER TABLE {} RENAME TO {}", new_name, name));
+    for index in &table.indices {
+        // constraint indices come back with the constraints
+        if index.is_primary || index.index_name.starts_with("sqlite_autoindex_") {
+            continue;
+        }
+        statements.push(match &index.definition {
+            Some(definition) => definition.clone(),
+            None => index_statement(
+                dialect,
+                &name,
+                &index.index_name,
+                &index.columns,
+                index.is_unique,
+            ),
+        });
+    }
+    statements.extend(
+        table
+            .triggers
+            .iter()
+            .filter_map(|t| t.action_statement.clone()),
+    );
+    statements.push("PRAGMA legacy_alter_table = OFF".to_string());
+    statements
+}
+
+#[cfg(test)]
+mod test {
+    use super::alter_table;
+    use crate::engine::types::alter::{ColumnDefinition, TableChange};
+    use crate::engine::types::catalog::{Column, DefaultKind, PrimaryKey, TableStructure};
+    use crate::engine::types::config::Dialect;
+
+    fn structure() -> TableStructure {
+        let column = |name: &str, column_type: &str| Column {
+            table_name: "t".to_string(),
+            column_name: name.to_string(),
+            column_type: column_type.to_string(),
+            is_nullable: true,
+            ..Default::default()
+        };
+        TableStructure {
+            table: "t".to_string(),
+            columns: vec![
+                Column {
+                    is_nullable: false,
+                    ..column("id", "INTEGER")
+                },
+                Column {
+                    column_default: Some("draft".to_string()),
+                    default_kind: Some(DefaultKind::Literal),
+                    comment: Some("state".to_string()),
+                    ..column("status", "varchar(20)")
+                },
+            ],
+            primary_key: vec![PrimaryKey {
+                table_name: "t".to_string(),
+                column_name: "id".to_string(),
+                ordinal_position: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let changes = vec![
+            TableChange::RenameColumn {
+                column_name: "status".to_string(),
+                new_name: "state".to_string(),
+            },
+            TableChange::SetNullable {
+                column_name: "state".to_string(),
+                is_nullable: false,
+            },
+        ];
+        assert_eq!(
+            alter_table(&Dialect::Postgresql, "public", &structure(), &changes).unwrap(),
+            vec![
+                "ALTER TABLE \"public\".\"t\" RENAME COLUMN \"status\" TO \"state\"",
+                "ALTER TABLE \"public\".\"t\" ALTER COLUMN \"state\" SET NOT NULL",
+            ]
+        );
+        assert_eq!(
+            alter_table(&Dialect::Mysql, "db", &structure(), &changes).unwrap()[1],
+            "ALTER TABLE `db`.`t` MODIFY COLUMN `state` varchar(20) NOT NULL DEFAULT 'draft' COMMENT 'state'"
+        );
+
+        let rebuild = alter_table(&Dialect::Sqlite, "main", &structure(), &changes).unwrap();
+        assert_eq!(
+            rebuild[1],
+            "CREATE TABLE \"new_t\" (\n    \"id\" INTEGER NOT NULL,\n    \"state\" varchar(20) NOT NULL DEFAULT draft,\n    PRIMARY KEY (\"id\")\n)"
+        );
+        assert_eq!(
+            rebuild[2],
+            "INSERT INTO \"new_t\" (\"id\", \"state\") SELECT \"id\", \"status\" FROM \"t\""
+        );
+
+        let add = vec![TableChange::AddColumn {
+            column: ColumnDefinition {
+                column_name: "id".to_string(),
+                column_type: "int".to_string(),
+                ..Default::default()
+            },
+        }];
+        assert!(alter_table(&Dialect::Sqlite, "main", &structure(), &add).is_err());
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use sqlparser::ast::{SetExpr, Statement};
+use sqlparser::parser::Parser;
+
+use super::types::catalog::{
+    Column, Constraint, ForeignKey, Index, PrimaryKey, Routine, SchemaSnapshot, Table, Trigger,
+    View,
+};
+use super::types::config::Dialect;
+
+/// Whether any statement of `sql` can change the catalog, SQL that doesn't
+/// parse is assumed to.
+pub fn changes_schema(dialect: &Dialect, sql: &str) -> bool {
+    match Parser::parse_sql(dialect.parser_dialect().as_ref(), sql) {
+        Ok(statements) => statements.iter().any(statement_changes_schema),
+        Err(_) => true,
+    }
+}
+
+/// Only statements known to leave the catalog alone are let through,
+/// anything else (DDL, `RENAME`, `COMMENT`, procedure calls) may change it.
+fn statement_changes_schema(statement: &Statement) -> bool {
+    match statement {
+        // `SELECT ... INTO` creates a table in Postgres
+        Statement::Query(query) => {
+            matches!(query.body.as_ref(), SetExpr::Select(select) if select.into.is_some())
+        }
+        Statement::Insert(_)
+        | Statement::Update { .. }
+        | Statement::Delete(_)
+        | Statement::Merge { .. }
+        | Statement::Copy { .. }
+        | Statement::LoadData { .. }
+        | Statement::Analyze { .. }
+        | Statement::Explain { .. }
+        | Statement::ExplainTable { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCollation { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowDatabases { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowSchemas { .. }
+        | Statement::ShowStatus { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowViews { .. }
+        | Statement::Use(_)
+        | Statement::SetVariable { .. }
+        | Statement::SetNames { .. }
+        | Statement::SetNamesDefault { .. }
+        | Statement::SetRole { .. }
+        | Statement::SetTimeZone { .. }
+        | Statement::SetTransaction { .. }
+        | Statement::StartTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Savepoint { .. }
+        | Statement::ReleaseSavepoint { .. }
+        | Statement::Declare { .. }
+        | Statement::Fetch { .. }
+        | Statement::Close { .. }
+        | Statement::Prepare { .. }
+        | Statement::Deallocate { .. }
+        | Statement::Kill { .. }
+        | Statement::LockTables { .. }
+        | Statement::UnlockTables
+        | Statement::LISTEN { .. }
+        | Statement::UNLISTEN { .. }
+        | Statement::NOTIFY { .. } => false,
+        _ => true,
+    }
+}
+
+/// Everything cached for one table, each part is filled on its own when it
+/// is first looked up, or all at once from a snapshot page.
+#[derive(Debug, Clone, Default)]
+pub struct TableEntry {
+    pub table: Option<Table>,
+    pub columns: Option<Vec<Column>>,
+    pub primary_key: Option<Vec<PrimaryKey>>,
+    pub foreign_keys: Option<Vec<ForeignKey>>,
+    pub indices: Option<Vec<Index>>,
+    /// Not part of snapshots, only filled on lookup.
+    pub constraints: Option<Vec<Constraint>>,
+    pub triggers: Option<Vec<Trigger>>,
+    pub view: Option<View>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaEntry {
+    pub tables: BTreeMap<String, TableEntry>,
+    pub routines: Vec<Routine>,
+    /// Every table of the schema has been loaded from snapshot pages, lookups
+    /// over the whole schema can be answered from the cache.
+    pub complete: bool,
+    /// Table names in the order the database pages them, its collation
+    /// doesn't sort like the map.
+    pub order: Vec<String>,
+}
+
+/// Catalog cache of one connection keyed by schema. Any statement that may
+/// change the catalog drops it entirely, the `generation` keeps a warm-up
+/// that raced with such a statement from marking stale data complete.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    schemas: RwLock<HashMap<String, SchemaEntry>>,
+    generation: AtomicU64,
+}
+
+impl MetadataCache {
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.schemas
+            .write()
+            .expect("Metadata cache poisoned")
+            .clear();
+    }
+
+    pub fn table<T>(
+        &self,
+        schema: &str,
+        table: &str,
+        get: impl Fn(&TableEntry) -> Option<T>,
+    ) -> Option<T> {
+        let schemas = self.schemas.read().expect("Metadata cache poisoned");
+        schemas.get(schema)?.tables.get(table).and_then(get)
+    }
+
+    /// Updates a table entry with data queried at `generation`, the write is
+    /// dropped if the cache was invalidated since.
+    pub fn set_table(
+        &self,
+        schema: &str,
+        table: &str,
+        generation: u64,
+        set: impl FnOnce(&mut TableEntry),
+    ) {
+        let mut schemas = self.schemas.write().expect("Metadata cache poisoned");
+        if self.generation() != generation {
+            return;
+        }
+        let entry = schemas
+            .entry(schema.to_string())
+            .or_default()
+            .tables
+            .entry(table.to_string())
+            .or_default();
+        set(entry);
+    }
+
+    /// Reads a complete schema, `None` until it has been warmed.
+    pub fn schema<T>(&self, schema: &str, get: impl Fn(&SchemaEntry) -> T) -> Option<T> {
+        let schemas = self.schemas.read().expect("Metadata cache poisoned");
+        schemas.get(schema).filter(|s| s.complete).map(get)
+    }
+
+    /// Stores a snapshot page queried at `generation`, routines are only part
+    /// of the first page.
+    pub fn add_snapshot(
+        &self,
+        schema: &str,
+        first: bool,
+        snapshot: &SchemaSnapshot,
+        generation: u64,
+    ) {
+        let mut schemas = self.schemas.write().expect("Metadata cache poisoned");
+        if self.generation() != generation {
+            return;
+        }
+        let entry = schemas.entry(schema.to_string()).or_default();
+        if first {
+            entry.routines = snapshot.routines.clone();
+        }
+        for table in &snapshot.tables {
+            let name = &table.table_name;
+            let of = |t: &str| t == name;
+            entry.tables.insert(
+                name.clone(),
+                TableEntry {
+                    table: Some(table.clone()),
+                    columns: Some(filter(&snapshot.columns, |c| of(&c.table_name))),
+                    primary_key: Some(filter(&snapshot.primary_keys, |k| of(&k.table_name))),
+                    foreign_keys: Some(filter(&snapshot.foreign_keys, |k| of(&k.table_name))),
+                    indices: Some(filter(&snapshot.indices, |i| of(&i.table_name))),
+                    triggers: Some(filter(&snapshot.triggers, |t| of(&t.event_object_table))),
+                    view: snapshot.views.iter().find(|v| of(&v.table_name)).cloned(),
+                    constraints: None,
+                },
+            );
+        }
+    }
+
+    /// Marks a schema complete unless the cache was invalidated since the
+    /// warm-up started at `generation`. `order` lists its tables as they were
+    /// paged.
+    pub fn set_complete(&self, schema: &str, order: Vec<String>, generation: u64) {
+        let mut schemas = self.schemas.write().expect("Metadata cache poisoned");
+        if self.generation() == generation {
+            if let Some(entry) = schemas.get_mut(schema) {
+                entry.complete = true;
+                entry.order = order;
+            }
+        }
+    }
+
+    /// Serves a snapshot page from a complete schema. Pages follow the order
+    /// of the database, a client may have started paging there; an unknown
+    /// `after` is left to the database.
+    pub fn snapshot(
+        &self,
+        schema: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Option<SchemaSnapshot> {
+        self.schema(schema, |entry| {
+            let start = match after {
+                Some(after) => entry.order.iter().position(|t| t == after)? + 1,
+                None => 0,
+            };
+            let names: Vec<&String> = entry.order.iter().skip(start).take(limit).collect();
+            let page: Vec<&TableEntry> =
+                names.iter().filter_map(|t| entry.tables.get(*t)).collect();
+            let mut snapshot = SchemaSnapshot {
+                schema: schema.to_string(),
+                next: (names.len() == limit)
+                    .then(|| names.last().map(|t| t.to_string()))
+                    .flatten(),
+                routines: if after.is_none() {
+                    entry.routines.clone()
+                } else {
+                    vec![]
+                },
+                ..Default::default()
+            };
+            for t in page {
+                snapshot.tables.extend(t.table.clone());
+                snapshot
+                    .columns
+                    .extend(t.columns.clone().unwrap_or_default());
+                snapshot
+                    .primary_keys
+                    .extend(t.primary_key.clone().unwrap_or_default());
+                snapshot
+                    .foreign_keys
+                    .extend(t.foreign_keys.clone().unwrap_or_default());
+                snapshot
+                    .indices
+                    .extend(t.indices.clone().unwrap_or_default());
+                snapshot
+                    .triggers
+                    .extend(t.triggers.clone().unwrap_or_default());
+                snapshot.views.extend(t.view.clone());
+            }
+            Some(snapshot)
+        })
+        .flatten()
+    }
+}
+
+fn filter<T: Clone>(items: &[T], keep: impl Fn(&T) -> bool) -> Vec<T> {
+    items.iter().filter(|i| keep(i)).cloned().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{changes_schema, MetadataCache};
+    use crate::engine::types::catalog::{Index, SchemaSnapshot, Table};
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_stale_writes_are_dropped() {
+        let cache = MetadataCache::default();
+        let generation = cache.generation();
+        // a DDL statement lands between the catalog query and the write
+        cache.invalidate();
+        cache.set_table("public", "users", generation, |t| {
+            t.indices = Some(vec![Index::default()])
+        });
+        let snapshot = SchemaSnapshot {
+            tables: vec![Table {
+                table_name: "users".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        cache.add_snapshot("public", true, &snapshot, generation);
+        cache.set_complete("public", vec!["users".to_string()], generation);
+        assert!(cache
+            .table("public", "users", |t| t.table.clone())
+            .is_none());
+        assert!(cache
+            .table("public", "users", |t| t.indices.clone())
+            .is_none());
+
+        let generation = cache.generation();
+        cache.set_table("public", "users", generation, |t| t.indices = Some(vec![]));
+        assert_eq!(
+            cache.table("public", "users", |t| t.indices.clone()),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_snapshot_follows_database_order() {
+        let cache = MetadataCache::default();
+        let generation = cache.generation();
+        // a case insensitive collation, byte order puts `Banana` first
+        let order: Vec<String> = ["apple", "Banana", "cherry"].map(String::from).to_vec();
+        let snapshot = SchemaSnapshot {
+            tables: order
+                .iter()
+                .map(|name| Table {
+                    table_name: name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        cache.add_snapshot("db", true, &snapshot, generation);
+        cache.set_complete("db", order, generation);
+
+        let page = |after: Option<&str>| {
+            cache.snapshot("db", after, 2).map(|s| {
+                let tables: Vec<String> = s.tables.into_iter().map(|t| t.table_name).collect();
+                (tables, s.next)
+            })
+        };
+        let first = page(None).unwrap();
+        assert_eq!(first.0, ["apple", "Banana"]);
+        assert_eq!(first.1.as_deref(), Some("Banana"));
+        // the first page came from the database, the cache continues after it
+        assert_eq!(page(Some("apple")).unwrap().0, ["Banana", "cherry"]);
+        assert_eq!(
+            page(Some("Banana")).unwrap(),
+            (vec!["cherry".to_string()], None)
+        );
+        assert!(page(Some("dropped")).is_none());
+    }
+
+    #[test]
+    fn test_changes_schema() {
+        assert!(changes_schema(&Dialect::Mysql, "RENAME TABLE a TO b"));
+        assert!(changes_schema(&Dialect::Mysql, "/* c */ DROP TABLE a"));
+        assert!(changes_schema(
+            &Dialect::Mysql,
+            "SELECT 1; ALTER TABLE a ADD b INT"
+        ));
+        assert!(changes_schema(
+            &Dialect::Postgresql,
+            "COMMENT ON TABLE a IS 'b'"
+        ));
+        assert!(changes_schema(
+            &Dialect::Postgresql,
+            "SELECT * INTO b FROM a"
+        ));
+        assert!(changes_schema(&Dialect::Postgresql, "CALL refresh()"));
+        assert!(changes_schema(&Dialect::Sqlite, "not sql"));
+        // the parser has no data-modifying CTEs, they are assumed to change it
+        assert!(changes_schema(
+            &Dialect::Postgresql,
+            "WITH old AS (SELECT id FROM a) DELETE FROM a WHERE id IN (SELECT id FROM old)"
+        ));
+        assert!(!changes_schema(
+            &Dialect::Postgresql,
+            "WITH old AS (SELECT id FROM a) SELECT * FROM old"
+        ));
+        assert!(!changes_schema(&Dialect::MariaDB, "UPDATE a SET b = 1"));
+        assert!(!changes_schema(&Dialect::Sqlite, "SELECT * FROM a"));
+    }
+}
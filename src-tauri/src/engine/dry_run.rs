@@ -0,0 +1,98 @@
+use anyhow::{bail, Result};
+use sqlparser::ast::{SelectItem, Statement, WildcardAdditionalOptions};
+use sqlparser::parser::Parser;
+
+use super::exec;
+use super::types::config::Dialect;
+use super::types::connection::InitiatedConnection;
+use super::types::result::TxResult;
+
+/// Rows of each statement's preview that are kept.
+pub const PREVIEW_ROWS: usize = 100;
+
+/// Runs `INSERT`, `UPDATE` and `DELETE` statements in a transaction that is
+/// always rolled back, so their affected rows can be seen before they are run
+/// for real. Statements without `RETURNING` get one where the dialect has it,
+/// their rows are the preview.
+///
+/// Sequences and auto increment counters aren't transactional, inserts still
+/// advance them.
+pub async fn dry_run(conn: &InitiatedConnection, queries: Vec<&str>) -> Result<TxResult> {
+    let statements = preview_statements(&conn.config.dialect, &queries)?;
+    let statements = statements.iter().map(String::as_str).collect();
+    let mut result = exec::execute_tx(conn, statements, true).await?;
+    for statement in &mut result.statements {
+        statement.rows.truncate(PREVIEW_ROWS);
+    }
+    Ok(result)
+}
+
+/// Splits the queries in statements, rejecting the ones that aren't data
+/// changes, and adds `RETURNING *` where it's supported.
+pub fn preview_statements(dialect: &Dialect, queries: &[&str]) -> Result<Vec<String>> {
+    let parser_dialect = dialect.parser_dialect();
+    let mut statements = vec![];
+    for query in queries {
+        for mut statement in Parser::parse_sql(parser_dialect.as_ref(), query)? {
+            let supported = supports_returning(dialect, &statement);
+            let returning = match &mut statement {
+                Statement::Insert(insert) => &mut insert.returning,
+                Statement::Update { returning, .. } => returning,
+                Statement::Delete(delete) => &mut delete.returning,
+                _ => bail!(
+                    "Only INSERT, UPDATE and DELETE statements can be dry run: {}",
+                    statement
+                ),
+            };
+            if supported && returning.is_none() {
+                let wildcard = SelectItem::Wildcard(WildcardAdditionalOptions::default());
+                *returning = Some(vec![wildcard]);
+            }
+            statements.push(statement.to_string());
+        }
+    }
+    Ok(statements)
+}
+
+/// MySQL has no `RETURNING`, MariaDB only has it for inserts and deletes.
+fn supports_returning(dialect: &Dialect, statement: &Statement) -> bool {
+    match dialect {
+        Dialect::Postgresql | Dialect::Sqlite => true,
+        Dialect::MariaDB => !matches!(statement, Statement::Update { .. }),
+        Dialect::Mysql => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::preview_statements;
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_preview_statements() {
+        let queries = [
+            "DELETE FROM t WHERE id > 10",
+            "UPDATE t SET a = 1 RETURNING id; INSERT INTO t (a) VALUES (2)",
+        ];
+        assert_eq!(
+            preview_statements(&Dialect::Postgresql, &queries).unwrap(),
+            vec![
+                "DELETE FROM t WHERE id > 10 RETURNING *",
+                "UPDATE t SET a = 1 RETURNING id",
+                "INSERT INTO t (a) VALUES (2) RETURNING *",
+            ]
+        );
+        assert_eq!(
+            preview_statements(&Dialect::MariaDB, &queries[1..]).unwrap(),
+            vec![
+                "UPDATE t SET a = 1 RETURNING id",
+                "INSERT INTO t (a) VALUES (2) RETURNING *",
+            ]
+        );
+        assert_eq!(
+            preview_statements(&Dialect::Mysql, &queries[..1]).unwrap(),
+            vec!["DELETE FROM t WHERE id > 10"]
+        );
+        assert!(preview_statements(&Dialect::Sqlite, &["DROP TABLE t"]).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use super::quote::{quote_ident, quote_qualified};
+use super::types::config::Dialect;
+use super::types::connection::InitiatedConnection;
+use super::types::edit::{BoundStatement, EditKind, RowConflict, RowEdits, RowEditsResult};
+
+/// Applies grid edits in one transaction, updates and deletes identify their
+/// row by the primary key, or the first unique index of tables without one.
+pub async fn apply_row_edits(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+    edits: &RowEdits,
+) -> Result<RowEditsResult> {
+    let key = key_columns(conn, schema, table).await?;
+    let statements = row_statements(&conn.config.dialect, schema, table, &key, edits)?;
+    let affected = conn.execute_bound_tx(statements).await?;
+
+    // statements are inserts, then updates, then deletes
+    let mut conflicts = vec![];
+    let checked = affected.iter().skip(edits.inserts.len()).copied();
+    let rows = edits
+        .updates
+        .iter()
+        .enumerate()
+        .map(|(i, u)| (EditKind::Update, i, &u.row))
+        .chain(
+            edits
+                .deletes
+                .iter()
+                .enumerate()
+                .map(|(i, d)| (EditKind::Delete, i, &d.row)),
+        );
+    // updates without values have no statement
+    let rows = rows.filter(|(kind, i, _)| match kind {
+        EditKind::Update => !edits.updates[*i].values.is_empty(),
+        EditKind::Delete => true,
+    });
+    for ((kind, index, row), affected_rows) in rows.zip(checked) {
+        if affected_rows != 1 {
+            conflicts.push(RowConflict {
+                kind,
+                index,
+                key: key_values(&key, row)?,
+                affected_rows,
+            });
+        }
+    }
+    Ok(RowEditsResult {
+        applied: conflicts.is_empty(),
+        affected_rows: match conflicts.is_empty() {
+            true => affected.iter().sum(),
+            false => 0,
+        },
+        conflicts,
+    })
+}
+
+/// Primary key, or the first unique index of tables without one.
+pub async fn key_columns(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>> {
+    let mut primary_key = conn.get_primary_key(schema, table).await?;
+    if !primary_key.is_empty() {
+        primary_key.sort_by_key(|k| k.ordinal_position);
+        return Ok(primary_key.into_iter().map(|k| k.column_name).collect());
+    }
+    conn.get_indices(schema, table)
+        .await?
+        .into_iter()
+        .find(|i| i.is_unique && !i.columns.is_empty())
+        .map(|i| i.columns)
+        .ok_or_else(|| {
+            anyhow!(
+                "{} has no primary key or unique key, its rows can't be edited safely",
+                table
+            )
+        })
+}
+
+fn key_values(key: &[String], row: &Map<String, Value>) -> Result<Map<String, Value>> {
+    key.iter()
+        .map(|column| {
+            row.get(column)
+                .map(|value| (column.clone(), value.clone()))
+                .ok_or_else(|| anyhow!("The row has no value for the key column {}", column))
+        })
+        .collect()
+}
+
+/// Placeholder for the `n`th parameter, counting from 1.
+fn placeholder(dialect: &Dialect, n: usize) -> String {
+    match dialect {
+        Dialect::Postgresql => format!("${}", n),
+        Dialect::Mysql | Dialect::MariaDB => "?".to_string(),
+        Dialect::Sqlite => format!("?{}", n),
+    }
+}
+
+/// Builds a `WHERE` clause matching `values`, nulls are matched with
+/// `IS NULL` as they aren't equal to anything.
+fn condition(dialect: &Dialect, values: &Map<String, Value>, params: &mut Vec<Value>) -> String {
+    values
+        .iter()
+        .map(|(column, value)| match value {
+            Value::Null => format!("{} IS NULL", quote_ident(dialect, column)),
+            _ => {
+                params.push(value.clone());
+                format!(
+                    "{} = {}",
+                    quote_ident(dialect, column),
+                    placeholder(dialect, params.len())
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+pub fn row_statements(
+    dialect: &Dialect,
+    schema: &str,
+    table: &str,
+    key: &[String],
+    edits: &RowEdits,
+) -> Result<Vec<BoundStatement>> {
+    let name = quote_qualified(dialect, &[schema, table]);
+    let mut statements = vec![];
+    for values in &edits.inserts {
+        let sql = match (values.is_empty(), dialect) {
+            (true, Dialect::Mysql | Dialect::MariaDB) => {
+                format!("INSERT INTO {} () VALUES ()", name)
+            }
+            (true, _) => format!("INSERT INTO {} DEFAULT VALUES", name),
+            (false, _) => format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                name,
+                values
+                    .keys()
+                    .map(|c| quote_ident(dialect, c))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (1..=values.len())
+                    .map(|n| placeholder(dialect, n))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        statements.push(BoundStatement {
+            sql,
+            params: values.values().cloned().collect(),
+            expect_row: false,
+        });
+    }
+    for update in edits.updates.iter().filter(|u| !u.values.is_empty()) {
+        let mut params = vec![];
+        let set = update
+            .values
+            .iter()
+            .map(|(column, value)| {
+                params.push(value.clone());
+                format!(
+                    "{} = {}",
+                    quote_ident(dialect, column),
+                    placeholder(dialect, params.len())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut matched = key_values(key, &update.row)?;
+        if edits.verify {
+            for column in update.values.keys() {
+                let original = update.row.get(column).ok_or_else(|| {
+                    anyhow!("The row has no original value for the column {}", column)
+                })?;
+                matched.insert(column.clone(), original.clone());
+            }
+        }
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            name,
+            set,
+            condition(dialect, &matched, &mut params)
+        );
+        statements.push(BoundStatement {
+            sql,
+            params,
+            expect_row: true,
+        });
+    }
+    for delete in &edits.deletes {
+        let mut params = vec![];
+        let matched = key_values(key, &delete.row)?;
+        let sql = format!(
+            "DELETE FROM {} WHERE {}",
+            name,
+            condition(dialect, &matched, &mut params)
+        );
+        statements.push(BoundStatement {
+            sql,
+            params,
+            expect_row: true,
+        });
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::row_statements;
+    use crate::engine::types::config::Dialect;
+    use crate::engine::types::edit::{RowDelete, RowEdits, RowUpdate};
+
+    #[test]
+    fn test_row_statements() {
+        let row = json!({"id": 7, "name": "a", "note": null});
+        let edits = RowEdits {
+            updates: vec![RowUpdate {
+                row: row.as_object().unwrap().clone(),
+                values: json!({"note": "b"}).as_object().unwrap().clone(),
+            }],
+            deletes: vec![RowDelete {
+                row: row.as_object().unwrap().clone(),
+            }],
+            verify: true,
+            ..Default::default()
+        };
+        let key = vec!["id".to_string()];
+        let statements = row_statements(&Dialect::Postgresql, "public", "t", &key, &edits).unwrap();
+        assert_eq!(
+            statements[0].sql,
+            "UPDATE \"public\".\"t\" SET \"note\" = $1 WHERE \"id\" = $2 AND \"note\" IS NULL"
+        );
+        assert_eq!(statements[0].params, vec![json!("b"), json!(7)]);
+        assert_eq!(
+            row_statements(&Dialect::Mysql, "db", "t", &key, &edits).unwrap()[1].sql,
+            "DELETE FROM `db`.`t` WHERE `id` = ?"
+        );
+
+        let key = vec!["code".to_string()];
+        assert!(row_statements(&Dialect::Sqlite, "main", "t", &key, &edits).is_err());
+    }
+}
@@ -1,73 +1,136 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
 
 use crate::database::QueryType;
 
-use super::types::result::ResultSet;
+use super::types::catalog::{
+    Column, CompositeType, Constraint, Domain, EnumType, Event, Extension, ForeignKey, Index,
+    Inheritance, MaterializedView, ObjectKind, Partition, PrimaryKey, Routine, SchemaSnapshot,
+    Sequence, SystemTime, Trigger, View,
+};
+use super::types::edit::BoundStatement;
+use super::types::monitor::{LockWait, Session, TopQuery, TopQueryOrder};
+use super::types::plan::QueryPlan;
+use super::types::result::{ResultSet, TxResult};
 use super::types::{config::ConnectionPool::*, connection::InitiatedConnection};
 use super::{mysql, postgresql, sqlite};
 
-pub async fn get_table_structure(conn: &InitiatedConnection, table: String) -> Result<Value> {
+/// A SQLite connection's schema is its database file, which is `main`, other
+/// schemas are attached databases.
+fn sqlite_schema<'a>(conn: &InitiatedConnection, schema: &'a str) -> &'a str {
+    match schema == conn.get_schema() {
+        true => "main",
+        false => schema,
+    }
+}
+
+pub async fn get_indices(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<Index>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_table_structure(conn, pool, table).await,
-        Postgresql(pool) => postgresql::tables::get_table_structure(conn, pool, table).await,
-        Sqlite(pool) => sqlite::tables::get_table_structure(pool, table).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_indices(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_indices(pool, schema, table).await,
+        Sqlite(pool) => sqlite::tables::get_indices(pool, sqlite_schema(conn, schema), table).await,
     }
 }
 
-pub async fn get_indices(conn: &InitiatedConnection, table: &str) -> Result<Vec<Value>> {
+pub async fn get_constraints(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<Constraint>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_indices(conn, pool, table).await,
-        Postgresql(pool) => postgresql::tables::get_indices(conn, pool, table).await,
-        Sqlite(pool) => sqlite::tables::get_indices(pool, table).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_constraints(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_constraints(pool, schema, table).await,
+        Sqlite(pool) => {
+            sqlite::tables::get_constraints(pool, sqlite_schema(conn, schema), table).await
+        }
     }
 }
 
-pub async fn get_columns(conn: &InitiatedConnection, table: Option<&str>) -> Result<Vec<Value>> {
+pub async fn get_row_estimate(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Option<f64>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_columns(conn, pool, table).await,
-        Postgresql(pool) => postgresql::tables::get_columns(conn, pool, table).await,
-        Sqlite(pool) => sqlite::tables::get_columns(pool, table).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_row_estimate(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_row_estimate(pool, schema, table).await,
+        Sqlite(pool) => {
+            sqlite::tables::get_row_estimate(pool, sqlite_schema(conn, schema), table).await
+        }
     }
 }
 
-pub async fn get_primary_key(conn: &InitiatedConnection, table: &str) -> Result<Vec<Value>> {
+pub async fn get_columns(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Column>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_primary_key(conn, pool, table).await,
-        Postgresql(pool) => postgresql::tables::get_primary_key(conn, pool, table).await,
-        Sqlite(pool) => sqlite::tables::get_primary_key(pool, table).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_columns(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_columns(pool, schema, table).await,
+        Sqlite(pool) => sqlite::tables::get_columns(pool, sqlite_schema(conn, schema), table).await,
     }
 }
 
-pub async fn get_foreign_keys(conn: &InitiatedConnection, table: &str) -> Result<Vec<Value>> {
+pub async fn get_primary_key(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<PrimaryKey>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_foreign_keys(conn, pool, table).await,
-        Postgresql(pool) => postgresql::tables::get_foreign_keys(conn, pool, table).await,
-        Sqlite(pool) => sqlite::tables::get_foreign_keys(pool, table).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_primary_key(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_primary_key(pool, schema, table).await,
+        Sqlite(pool) => {
+            sqlite::tables::get_primary_key(pool, sqlite_schema(conn, schema), table).await
+        }
     }
 }
 
-pub async fn get_functions(conn: &InitiatedConnection) -> Result<Vec<Value>> {
+pub async fn get_foreign_keys(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ForeignKey>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_functions(conn, pool).await,
-        Postgresql(pool) => postgresql::tables::get_functions(conn, pool).await,
-        Sqlite(_pool) => Ok(vec![]),
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_foreign_keys(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_foreign_keys(pool, schema, table).await,
+        Sqlite(pool) => {
+            sqlite::tables::get_foreign_keys(pool, sqlite_schema(conn, schema), table).await
+        }
     }
 }
 
-pub async fn get_procedures(conn: &InitiatedConnection) -> Result<Vec<Value>> {
+pub async fn get_functions(conn: &InitiatedConnection, schema: &str) -> Result<Vec<Routine>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_procedures(conn, pool).await,
-        Postgresql(pool) => postgresql::tables::get_procedures(conn, pool).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_functions(pool, schema).await,
+        Postgresql(pool) => postgresql::tables::get_functions(pool, schema).await,
+        Sqlite(pool) => sqlite::tables::get_functions(pool, sqlite_schema(conn, schema)).await,
+    }
+}
+
+pub async fn get_procedures(conn: &InitiatedConnection, schema: &str) -> Result<Vec<Routine>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_procedures(pool, schema).await,
+        Postgresql(pool) => postgresql::tables::get_procedures(pool, schema).await,
         Sqlite(_pool) => Ok(vec![]),
     }
 }
 
-pub async fn get_triggers(conn: &InitiatedConnection) -> Result<Vec<Value>> {
+pub async fn get_triggers(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Trigger>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_triggers(conn, pool, None).await,
-        Postgresql(pool) => postgresql::tables::get_triggers(conn, pool, None).await,
-        Sqlite(pool) => sqlite::tables::get_triggers(pool, None).await,
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_triggers(pool, schema, table).await,
+        Postgresql(pool) => postgresql::tables::get_triggers(pool, schema, table).await,
+        Sqlite(pool) => {
+            sqlite::tables::get_triggers(pool, sqlite_schema(conn, schema), table).await
+        }
     }
 }
 
@@ -81,11 +144,194 @@ pub async fn get_schemas(conn: &InitiatedConnection) -> Result<Vec<Value>> {
     }
 }
 
-pub async fn get_views(conn: &InitiatedConnection) -> Result<Vec<Value>> {
+pub async fn get_views(conn: &InitiatedConnection, schema: &str) -> Result<Vec<View>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_views(pool, schema).await,
+        Postgresql(pool) => postgresql::tables::get_views(pool, schema).await,
+        Sqlite(pool) => sqlite::tables::get_views(pool, sqlite_schema(conn, schema)).await,
+    }
+}
+
+pub async fn get_schema_snapshot(
+    conn: &InitiatedConnection,
+    schema: &str,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<SchemaSnapshot> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => {
+            mysql::tables::get_schema_snapshot(pool, schema, after, limit).await
+        }
+        Postgresql(pool) => {
+            postgresql::tables::get_schema_snapshot(pool, schema, after, limit).await
+        }
+        Sqlite(pool) => {
+            sqlite::tables::get_schema_snapshot(pool, sqlite_schema(conn, schema), after, limit)
+                .await
+        }
+    }
+}
+
+pub async fn get_sequences(conn: &InitiatedConnection, schema: &str) -> Result<Vec<Sequence>> {
+    match &conn.pool {
+        MariaDB(pool) => mysql::objects::get_mariadb_sequences(pool, schema).await,
+        Postgresql(pool) => postgresql::objects::get_sequences(pool, schema).await,
+        _ => Err(anyhow!(
+            "Sequences are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_enums(conn: &InitiatedConnection, schema: &str) -> Result<Vec<EnumType>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_enums(pool, schema).await,
+        _ => Err(anyhow!(
+            "Enum types are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_domains(conn: &InitiatedConnection, schema: &str) -> Result<Vec<Domain>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_domains(pool, schema).await,
+        _ => Err(anyhow!(
+            "Domains are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_composite_types(
+    conn: &InitiatedConnection,
+    schema: &str,
+) -> Result<Vec<CompositeType>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_composite_types(pool, schema).await,
+        _ => Err(anyhow!(
+            "Composite types are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_materialized_views(
+    conn: &InitiatedConnection,
+    schema: &str,
+) -> Result<Vec<MaterializedView>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_materialized_views(pool, schema).await,
+        _ => Err(anyhow!(
+            "Materialized views are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn refresh_materialized_view(
+    conn: &InitiatedConnection,
+    schema: &str,
+    name: &str,
+    concurrently: bool,
+) -> Result<()> {
+    match &conn.pool {
+        Postgresql(pool) => {
+            postgresql::objects::refresh_materialized_view(pool, schema, name, concurrently).await
+        }
+        _ => Err(anyhow!(
+            "Materialized views are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_extensions(conn: &InitiatedConnection) -> Result<Vec<Extension>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_extensions(pool).await,
+        _ => Err(anyhow!(
+            "Extensions are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_inheritance(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Inheritance>> {
+    match &conn.pool {
+        Postgresql(pool) => postgresql::objects::get_inheritance(pool, schema, table).await,
+        _ => Err(anyhow!(
+            "Table inheritance is not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_events(conn: &InitiatedConnection, schema: &str) -> Result<Vec<Event>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::objects::get_events(pool, schema).await,
+        _ => Err(anyhow!(
+            "Events are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_partitions(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Partition>> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::tables::get_views(conn, pool).await,
-        Postgresql(pool) => postgresql::tables::get_views(conn, pool).await,
-        Sqlite(pool) => sqlite::tables::get_views(pool).await,
+        Mysql(pool) | MariaDB(pool) => mysql::objects::get_partitions(pool, schema, table).await,
+        Postgresql(_pool) => Err(anyhow!(
+            "Postgresql partitions are listed with the table inheritance"
+        )),
+        Sqlite(_pool) => Err(anyhow!("Partitions are not available for Sqlite")),
+    }
+}
+
+pub async fn get_modules(conn: &InitiatedConnection) -> Result<Vec<String>> {
+    match &conn.pool {
+        Sqlite(pool) => sqlite::tables::get_modules(pool).await,
+        _ => Err(anyhow!(
+            "Virtual table modules are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_table_history(
+    conn: &InitiatedConnection,
+    schema: &str,
+    table: &str,
+    time: &SystemTime,
+    limit: Option<usize>,
+) -> Result<ResultSet> {
+    match &conn.pool {
+        MariaDB(pool) => {
+            mysql::objects::get_mariadb_table_history(pool, schema, table, time, limit).await
+        }
+        _ => Err(anyhow!(
+            "System-versioned tables are not available for {}",
+            conn.config.dialect
+        )),
+    }
+}
+
+pub async fn get_object_ddl(
+    conn: &InitiatedConnection,
+    schema: &str,
+    object: &str,
+    kind: ObjectKind,
+) -> Result<String> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::ddl::get_object_ddl(pool, schema, object, kind).await,
+        Postgresql(pool) => postgresql::ddl::get_object_ddl(pool, schema, object, kind).await,
+        Sqlite(pool) => sqlite::ddl::get_object_ddl(pool, object, kind).await,
     }
 }
 
@@ -97,10 +343,132 @@ pub async fn execute_query(conn: &InitiatedConnection, q: &str, t: QueryType) ->
     }
 }
 
-pub async fn execute_tx(conn: &InitiatedConnection, queries: Vec<&str>) -> Result<()> {
+/// Runs the queries in a transaction on one connection, a `dry_run` is always
+/// rolled back.
+pub async fn execute_tx(
+    conn: &InitiatedConnection,
+    queries: Vec<&str>,
+    dry_run: bool,
+) -> Result<TxResult> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::query::execute_tx(pool, queries, dry_run).await,
+        Postgresql(pool) => postgresql::query::execute_tx(pool, queries, dry_run).await,
+        Sqlite(pool) => sqlite::query::execute_tx(pool, queries, dry_run).await,
+    }
+}
+
+/// Runs parameterized statements in a transaction, it is rolled back when an
+/// `expect_row` statement doesn't affect exactly one row.
+pub async fn execute_bound_tx(
+    conn: &InitiatedConnection,
+    statements: Vec<BoundStatement>,
+) -> Result<Vec<u64>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::query::execute_bound_tx(pool, statements).await,
+        Postgresql(pool) => postgresql::query::execute_bound_tx(pool, statements).await,
+        Sqlite(pool) => sqlite::query::execute_bound_tx(pool, statements).await,
+    }
+}
+
+pub async fn explain_query(
+    conn: &InitiatedConnection,
+    sql: &str,
+    analyze: bool,
+) -> Result<QueryPlan> {
+    match &conn.pool {
+        Mysql(pool) => mysql::explain::explain_query(pool, sql, analyze).await,
+        MariaDB(pool) => mysql::explain::explain_mariadb_query(pool, sql, analyze).await,
+        Postgresql(pool) => postgresql::explain::explain_query(pool, sql, analyze).await,
+        Sqlite(pool) => sqlite::explain::explain_query(pool, sql).await,
+    }
+}
+
+pub async fn get_top_queries(
+    conn: &InitiatedConnection,
+    order: TopQueryOrder,
+    limit: usize,
+) -> Result<Vec<TopQuery>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::monitor::get_top_queries(pool, order, limit).await,
+        Postgresql(pool) => postgresql::monitor::get_top_queries(pool, order, limit).await,
+        Sqlite(_pool) => Err(anyhow!("Query statistics are not available for Sqlite")),
+    }
+}
+
+pub async fn get_sessions(conn: &InitiatedConnection) -> Result<Vec<Session>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::monitor::get_sessions(pool).await,
+        Postgresql(pool) => postgresql::monitor::get_sessions(pool).await,
+        Sqlite(_pool) => Err(anyhow!("Session monitoring is not available for Sqlite")),
+    }
+}
+
+pub async fn cancel_session(conn: &InitiatedConnection, id: i64) -> Result<()> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::monitor::cancel_session(pool, id).await,
+        Postgresql(pool) => postgresql::monitor::cancel_session(pool, id).await,
+        Sqlite(_pool) => Err(anyhow!("Session monitoring is not available for Sqlite")),
+    }
+}
+
+pub async fn terminate_session(conn: &InitiatedConnection, id: i64) -> Result<()> {
     match &conn.pool {
-        Mysql(pool) | MariaDB(pool) => mysql::query::execute_tx(pool, queries).await,
-        Postgresql(pool) => postgresql::query::execute_tx(pool, queries).await,
-        Sqlite(pool) => sqlite::query::execute_tx(pool, queries).await,
+        Mysql(pool) | MariaDB(pool) => mysql::monitor::terminate_session(pool, id).await,
+        Postgresql(pool) => postgresql::monitor::terminate_session(pool, id).await,
+        Sqlite(_pool) => Err(anyhow!("Session monitoring is not available for Sqlite")),
+    }
+}
+
+pub async fn get_lock_waits(conn: &InitiatedConnection) -> Result<Vec<LockWait>> {
+    match &conn.pool {
+        Mysql(pool) => mysql::monitor::get_lock_waits(pool).await,
+        MariaDB(pool) => mysql::monitor::get_mariadb_lock_waits(pool).await,
+        Postgresql(pool) => postgresql::monitor::get_lock_waits(pool).await,
+        Sqlite(_pool) => Err(anyhow!("Lock monitoring is not available for Sqlite")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use sqlx::mysql::MySqlPoolOptions;
+    use uuid::Uuid;
+
+    use super::get_sequences;
+    use crate::engine::session::SessionSchema;
+    use crate::engine::types::config::{ConnectionConfig, ConnectionPool, Dialect, Mode};
+    use crate::engine::types::connection::InitiatedConnection;
+
+    fn connection(dialect: Dialect) -> InitiatedConnection {
+        // nothing listens there, dispatched queries fail to connect
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://127.0.0.1:1/noir")
+            .unwrap();
+        InitiatedConnection {
+            pool: ConnectionPool::mysql(&dialect, pool),
+            config: ConnectionConfig {
+                id: Uuid::new_v4(),
+                dialect,
+                mode: Mode::Host,
+                credentials: Default::default(),
+                schema: "noir".to_string(),
+                name: "noir".to_string(),
+                color: String::new(),
+                metadata: Default::default(),
+            },
+            schema: Arc::new(SessionSchema::new("noir".to_string())),
+            cache: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mariadb_dispatch() {
+        let mysql = get_sequences(&connection(Dialect::Mysql), "noir").await;
+        assert!(mysql.unwrap_err().to_string().contains("not available"));
+        let mariadb = get_sequences(&connection(Dialect::MariaDB), "noir").await;
+        assert!(!mariadb.unwrap_err().to_string().contains("not available"));
     }
 }
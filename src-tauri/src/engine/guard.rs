@@ -0,0 +1,109 @@
+use sqlparser::ast::{AlterTableOperation, BinaryOperator, Expr, Statement, UnaryOperator, Value};
+
+/// Statements that drop objects or data, or change every row of a table: a
+/// `WHERE` that is always true doesn't count as a condition.
+pub fn is_destructive(statement: &Statement) -> bool {
+    match statement {
+        Statement::Drop { .. }
+        | Statement::DropFunction { .. }
+        | Statement::DropProcedure { .. }
+        | Statement::DropTrigger { .. }
+        | Statement::DropExtension { .. }
+        | Statement::Truncate { .. } => true,
+        Statement::AlterTable { operations, .. } => operations.iter().any(|o| {
+            matches!(
+                o,
+                AlterTableOperation::DropColumn { .. }
+                    | AlterTableOperation::DropConstraint { .. }
+                    | AlterTableOperation::DropPrimaryKey
+                    | AlterTableOperation::DropPartitions { .. }
+            )
+        }),
+        Statement::Update { selection, .. } => unconditional(selection.as_ref()),
+        Statement::Delete(delete) => unconditional(delete.selection.as_ref()),
+        _ => false,
+    }
+}
+
+fn unconditional(selection: Option<&Expr>) -> bool {
+    selection.is_none_or(always_true)
+}
+
+/// Conditions that hold for every row, like `1 = 1`, `id = id` or `true`.
+fn always_true(expr: &Expr) -> bool {
+    match expr {
+        Expr::Nested(expr) => always_true(expr),
+        Expr::Value(value) => literal_truth(value) == Some(true),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => always_false(expr),
+        Expr::BinaryOp { left, op, right } => match op {
+            BinaryOperator::Or => always_true(left) || always_true(right),
+            BinaryOperator::And => always_true(left) && always_true(right),
+            BinaryOperator::Eq | BinaryOperator::GtEq | BinaryOperator::LtEq => left == right,
+            BinaryOperator::NotEq => {
+                matches!((left.as_ref(), right.as_ref()), (Expr::Value(l), Expr::Value(r)) if l != r)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn always_false(expr: &Expr) -> bool {
+    match expr {
+        Expr::Nested(expr) => always_false(expr),
+        Expr::Value(value) => literal_truth(value) == Some(false),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => always_true(expr),
+        _ => false,
+    }
+}
+
+/// Booleans and numbers as conditions, MySQL and SQLite take `WHERE 1`.
+fn literal_truth(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(b) => Some(*b),
+        Value::Number(n, _) => n.parse::<f64>().ok().map(|n| n != 0.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlparser::dialect::MySqlDialect;
+    use sqlparser::parser::Parser;
+
+    use super::is_destructive;
+
+    #[test]
+    fn test_is_destructive() {
+        let destructive = |sql: &str| {
+            let statements = Parser::parse_sql(&MySqlDialect {}, sql).unwrap();
+            is_destructive(&statements[0])
+        };
+        assert!(destructive("DROP TABLE users"));
+        assert!(destructive("TRUNCATE TABLE users"));
+        assert!(destructive(
+            "ALTER TABLE users ADD COLUMN a INT, DROP COLUMN b"
+        ));
+        assert!(destructive("DELETE FROM users"));
+        assert!(destructive("DELETE FROM users WHERE 1"));
+        assert!(destructive(
+            "UPDATE users SET a = 1 WHERE id = id OR id = 5"
+        ));
+        assert!(destructive(
+            "UPDATE users SET a = 1 WHERE (1 = 1) AND NOT false"
+        ));
+        assert!(destructive("UPDATE users SET a = 1 WHERE 'a' <> 'b'"));
+        assert!(!destructive(
+            "UPDATE users SET a = 1 WHERE id = 5 AND 1 = 1"
+        ));
+        assert!(!destructive("DELETE FROM users WHERE 0"));
+        assert!(!destructive("ALTER TABLE users ADD COLUMN a INT"));
+        assert!(!destructive("SELECT * FROM users"));
+    }
+}
@@ -1,10 +1,17 @@
 use anyhow::{anyhow, Result};
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use deadpool_postgres::{
-    Config as PsqlConfig, ManagerConfig as PsqlManagerConfig, Pool, RecyclingMethod, SslMode,
+    ClientWrapper, Config as PsqlConfig, Hook, HookError, ManagerConfig as PsqlManagerConfig, Pool,
+    PoolBuilder, RecyclingMethod, SslMode,
+};
+use deadpool_sqlite::{
+    Config as SqliteConfig, Hook as SqliteHook, HookError as SqliteHookError, Pool as SqlitePool,
 };
-use deadpool_sqlite::Config as SqliteConfig;
 use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
 use postgres::NoTls;
 use postgres_openssl::MakeTlsConnector;
@@ -16,9 +23,13 @@ use sqlx::{
 use tauri::AppHandle;
 
 use crate::{
-    engine::types::{
-        config::{ConnectionConfig, ConnectionPool, Dialect, Mode},
-        connection::InitiatedConnection,
+    engine::{
+        quote::quote_ident,
+        session::SessionSchema,
+        types::{
+            config::{ConnectionConfig, ConnectionPool, Dialect, Mode},
+            connection::InitiatedConnection,
+        },
     },
     state::ServiceAccess,
     utils::{
@@ -35,27 +46,34 @@ pub async fn init_conn(
         Dialect::Mysql | Dialect::MariaDB => {
             let (pool_opts, options) =
                 build_mysql_pool_opts(cfg.clone(), app_handle.clone()).await?;
-            let schema = options.get_database().unwrap_or("").to_string();
-            let pool = pool_opts.connect_with(options).await?;
+            let schema = Arc::new(SessionSchema::new(
+                options.get_database().unwrap_or("").to_string(),
+            ));
+            let pool = with_current_database(pool_opts, schema.clone())
+                .connect_with(options)
+                .await?;
             if pool.execute("SELECT 1").await.is_err() {
                 app_handle.cancel_token(cfg.id.to_string()).await?;
                 return Err(Error::from(anyhow::anyhow!("Could not connect")));
             }
             Ok(InitiatedConnection {
                 config: cfg.clone(),
-                pool: ConnectionPool::Mysql(pool),
+                pool: ConnectionPool::mysql(&cfg.dialect, pool),
                 schema,
+                cache: Default::default(),
             })
         }
         Dialect::Postgresql => {
             let _cfg = cfg.clone();
-            let pool = build_psql_pool(cfg, app_handle).await?;
+            let schema = Arc::new(SessionSchema::new(psql_schema(&cfg)));
+            let pool = build_psql_pool(cfg, app_handle, schema.clone()).await?;
             let conn = pool.get().await?;
             conn.execute("SELECT 1", &[]).await?;
             Ok(InitiatedConnection {
                 config: _cfg,
                 pool: ConnectionPool::Postgresql(pool),
-                schema: "public".to_string(),
+                schema,
+                cache: Default::default(),
             })
         }
         Dialect::Sqlite => {
@@ -67,8 +85,11 @@ pub async fn init_conn(
                 .get("path")
                 .cloned()
                 .unwrap_or("".to_string());
-            let config = SqliteConfig::new(PathBuf::from(path.clone()));
-            match config.create_pool(deadpool_sqlite::Runtime::Tokio1) {
+            let config = match cfg.read_only() {
+                true => SqliteConfig::new(PathBuf::from(sqlite_read_only_uri(&path))),
+                false => SqliteConfig::new(PathBuf::from(path.clone())),
+            };
+            match create_sqlite_pool(&config, cfg.read_only()) {
                 Ok(pool) => match pool.get().await {
                     Ok(_) => {
                         let conn = pool.get().await?;
@@ -76,7 +97,8 @@ pub async fn init_conn(
                         Ok(InitiatedConnection {
                             config: cfg.clone(),
                             pool: ConnectionPool::Sqlite(pool),
-                            schema: path.to_string(),
+                            schema: Arc::new(SessionSchema::new(path.to_string())),
+                            cache: Default::default(),
                         })
                     }
                     Err(e) => Err(Error::DeadpoolSqlitePool(e)),
@@ -87,8 +109,159 @@ pub async fn init_conn(
     }
 }
 
-fn create_psql_pool(config: PsqlConfig, cfg: ConnectionConfig) -> Result<Pool> {
-    let rt = Some(deadpool_postgres::Runtime::Tokio1);
+/// Opens SQLite files read-only, `query_only` also stops writes to attached
+/// databases and temporary tables.
+fn create_sqlite_pool(
+    config: &SqliteConfig,
+    read_only: bool,
+) -> Result<SqlitePool, deadpool_sqlite::CreatePoolError> {
+    if !read_only {
+        return config.create_pool(deadpool_sqlite::Runtime::Tokio1);
+    }
+    let builder = config
+        .builder(deadpool_sqlite::Runtime::Tokio1)
+        .map_err(deadpool_sqlite::CreatePoolError::Config)?;
+    builder
+        .post_create(SqliteHook::async_fn(|conn, _| {
+            Box::pin(async move {
+                conn.interact(|c| c.execute_batch("PRAGMA query_only = ON"))
+                    .await
+                    .map_err(|e| SqliteHookError::Message(e.to_string()))?
+                    .map_err(SqliteHookError::Backend)
+            })
+        }))
+        .build()
+        .map_err(deadpool_sqlite::CreatePoolError::Build)
+}
+
+/// URI filename opening `path` with `mode=ro`, characters that have a meaning
+/// in URIs are escaped.
+fn sqlite_read_only_uri(path: &str) -> String {
+    let mut path = path
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23")
+        .replace('\\', "/");
+    // Windows paths start with the drive letter, like `C:/`
+    if path.as_bytes().get(1) == Some(&b':') {
+        path.insert(0, '/');
+    }
+    format!("file:{}?mode=ro", path)
+}
+
+/// Schema to put on the `search_path`, the one last selected in noir is
+/// persisted as the connection's schema and wins over the credential.
+fn psql_schema(cfg: &ConnectionConfig) -> String {
+    [Some(&cfg.schema), cfg.credentials.get("schema")]
+        .into_iter()
+        .flatten()
+        .find(|s| !s.is_empty())
+        .cloned()
+        .unwrap_or("public".to_string())
+}
+
+/// Switches idle MySQL sessions to the current database before they are
+/// handed out, new sessions connect to it directly. Sessions that were in use
+/// during a switch are checked when they are released.
+fn with_current_database(
+    pool_opts: PoolOptions<sqlx::MySql>,
+    schema: Arc<SessionSchema>,
+) -> PoolOptions<sqlx::MySql> {
+    let on_release = schema.clone();
+    pool_opts
+        .before_acquire(move |conn, meta| {
+            let schema = schema.clone();
+            Box::pin(async move {
+                // the session was released `idle_for` ago and not used since
+                if schema.is_stale(Instant::now() - meta.idle_for) {
+                    use_database(conn, &schema.get()).await?;
+                }
+                Ok(true)
+            })
+        })
+        .after_release(move |conn, meta| {
+            let schema = on_release.clone();
+            Box::pin(async move {
+                // the checkout time is unknown, sessions older than the switch
+                // compare their database until those checked out during it
+                // have been reset
+                if schema.has_unreleased() && schema.is_stale(Instant::now() - meta.age) {
+                    let current: Option<String> = sqlx::query_scalar("SELECT DATABASE()")
+                        .fetch_one(&mut *conn)
+                        .await?;
+                    let name = schema.get();
+                    if current.as_deref() != Some(name.as_str()) {
+                        use_database(conn, &name).await?;
+                        schema.released();
+                    }
+                }
+                Ok(true)
+            })
+        })
+}
+
+async fn use_database(conn: &mut sqlx::MySqlConnection, name: &str) -> Result<(), sqlx::Error> {
+    let query = format!("USE {}", quote_ident(&Dialect::Mysql, name));
+    conn.execute(query.as_str()).await?;
+    Ok(())
+}
+
+/// Applies the current schema as `search_path` to new sessions and to pooled
+/// ones handed out for the first time after a switch.
+fn with_search_path(builder: PoolBuilder, schema: Arc<SessionSchema>) -> PoolBuilder {
+    let on_create = schema.clone();
+    builder
+        .post_create(Hook::async_fn(move |client, _| {
+            let schema = on_create.clone();
+            Box::pin(async move { set_search_path(client, &schema.get()).await })
+        }))
+        .post_recycle(Hook::async_fn(move |client, metrics| {
+            let schema = schema.clone();
+            Box::pin(async move {
+                if schema.is_stale(metrics.recycled.unwrap_or(metrics.created)) {
+                    set_search_path(client, &schema.get()).await?;
+                }
+                Ok(())
+            })
+        }))
+}
+
+/// Makes every transaction of new sessions read-only.
+fn with_read_only(builder: PoolBuilder, read_only: bool) -> PoolBuilder {
+    if !read_only {
+        return builder;
+    }
+    builder.post_create(Hook::async_fn(|client, _| {
+        Box::pin(async move {
+            client
+                .batch_execute("SET default_transaction_read_only = on")
+                .await
+                .map_err(HookError::Backend)
+        })
+    }))
+}
+
+async fn set_search_path(client: &ClientWrapper, schema: &str) -> Result<(), HookError> {
+    let query = format!(
+        "SET search_path TO {}",
+        quote_ident(&Dialect::Postgresql, schema)
+    );
+    client
+        .batch_execute(&query)
+        .await
+        .map_err(HookError::Backend)
+}
+
+fn create_psql_pool(
+    config: PsqlConfig,
+    cfg: ConnectionConfig,
+    schema: Arc<SessionSchema>,
+) -> Result<Pool> {
+    let rt = deadpool_postgres::Runtime::Tokio1;
+    let read_only = cfg.read_only();
+    let build = |builder: PoolBuilder| {
+        with_read_only(with_search_path(builder.runtime(rt), schema), read_only).build()
+    };
     let ca_cert = cfg
         .credentials
         .get("ca_cert")
@@ -120,7 +293,7 @@ fn create_psql_pool(config: PsqlConfig, cfg: ConnectionConfig) -> Result<Pool> {
                 builder.set_ca_file(ca_cert)?;
                 builder.set_certificate_chain_file(client_cert)?;
                 builder.set_private_key_file(client_key, SslFiletype::PEM)?;
-                config.create_pool(rt, MakeTlsConnector::new(builder.build()))?
+                build(config.builder(MakeTlsConnector::new(builder.build()))?)?
             } else if !ca_cert.is_empty() {
                 let mut builder = SslConnector::builder(SslMethod::tls_client())?;
                 builder.set_verify(SslVerifyMode::PEER); // peer - veirfy ca - must add ca file, none - allow self signed or without ca
@@ -129,20 +302,24 @@ fn create_psql_pool(config: PsqlConfig, cfg: ConnectionConfig) -> Result<Pool> {
                         .get("ca_cert")
                         .expect("Should have a ca cert"),
                 )?;
-                config.create_pool(rt, MakeTlsConnector::new(builder.build()))?
+                build(config.builder(MakeTlsConnector::new(builder.build()))?)?
             } else {
                 let mut builder = SslConnector::builder(SslMethod::tls())?;
                 builder.set_verify(SslVerifyMode::NONE); // peer - veirfy ca - must add ca file, none - allow self signed or without ca
                 let connector = MakeTlsConnector::new(builder.build());
-                config.create_pool(rt, connector)?
+                build(config.builder(connector)?)?
             }
         } else {
-            config.create_pool(rt, NoTls)?
+            build(config.builder(NoTls)?)?
         },
     )
 }
 
-async fn build_psql_pool(cfg: ConnectionConfig, app_handle: AppHandle) -> Result<Pool> {
+async fn build_psql_pool(
+    cfg: ConnectionConfig,
+    app_handle: AppHandle,
+    schema: Arc<SessionSchema>,
+) -> Result<Pool> {
     if cfg.mode == Mode::File {
         return Err(anyhow::anyhow!("File mode is not supported for Postgresql"));
     }
@@ -189,7 +366,7 @@ async fn build_psql_pool(cfg: ConnectionConfig, app_handle: AppHandle) -> Result
             .await?;
             config.host = Some("127.0.0.1".to_string());
             config.port = Some(available_port);
-            Ok(create_psql_pool(config, cfg)?)
+            Ok(create_psql_pool(config, cfg, schema)?)
         }
         Mode::File => Err(anyhow!("Should never reach here")),
         _ => {
@@ -199,7 +376,7 @@ async fn build_psql_pool(cfg: ConnectionConfig, app_handle: AppHandle) -> Result
                 .get("port")
                 .cloned()
                 .map(|p| p.parse::<u16>().expect("Port should be a valid number"));
-            Ok(create_psql_pool(config, cfg)?)
+            Ok(create_psql_pool(config, cfg, schema)?)
         }
     }
 }
@@ -317,10 +494,18 @@ async fn build_mysql_pool_opts(
         }
         _ => MySqlConnectOptions::new(),
     };
-    let pool_opts = MySqlPoolOptions::new()
+    let mut pool_opts = MySqlPoolOptions::new()
         .max_connections(10)
         .idle_timeout(Duration::from_secs(30 * 60))
         .max_lifetime(Duration::from_secs(60 * 60))
         .acquire_timeout(Duration::from_secs(10));
+    if cfg.read_only() {
+        pool_opts = pool_opts.after_connect(|conn, _| {
+            Box::pin(async move {
+                conn.execute("SET SESSION TRANSACTION READ ONLY").await?;
+                Ok(())
+            })
+        });
+    }
     Ok((pool_opts, options))
 }
@@ -1,6 +1,15 @@
+pub mod advisor;
+pub mod alter;
+pub mod cache;
+pub mod dry_run;
+pub mod edit;
 pub mod exec;
+pub mod guard;
 pub mod init;
 pub mod mysql;
 pub mod postgresql;
+pub mod provenance;
+pub mod quote;
+pub mod session;
 pub mod sqlite;
 pub mod types;
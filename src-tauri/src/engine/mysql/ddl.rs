@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+use sqlx::{Executor, MySqlPool};
+
+use crate::engine::{
+    quote::quote_qualified,
+    types::{catalog::ObjectKind, config::Dialect},
+};
+
+use super::sql_to_json::row_to_json;
+
+/// Definition from `SHOW CREATE`, sent unprepared as not every `SHOW CREATE`
+/// is allowed in prepared statements.
+pub async fn get_object_ddl(
+    pool: &MySqlPool,
+    schema: &str,
+    object: &str,
+    kind: ObjectKind,
+) -> Result<String> {
+    let (statement, column) = match kind {
+        ObjectKind::Table => ("TABLE", "Create Table"),
+        ObjectKind::View => ("VIEW", "Create View"),
+        ObjectKind::Function => ("FUNCTION", "Create Function"),
+        ObjectKind::Procedure => ("PROCEDURE", "Create Procedure"),
+        ObjectKind::Trigger => ("TRIGGER", "SQL Original Statement"),
+        ObjectKind::Event => ("EVENT", "Create Event"),
+        // MariaDB sequences are tables underneath
+        ObjectKind::Sequence => ("SEQUENCE", "Create Table"),
+        ObjectKind::MaterializedView | ObjectKind::Index => {
+            return Err(anyhow!(
+                "{:?} definitions are not available for MySQL",
+                kind
+            ))
+        }
+    };
+    let query = format!(
+        "SHOW CREATE {} {};",
+        statement,
+        quote_qualified(&Dialect::Mysql, &[schema, object])
+    );
+    let row = row_to_json(pool.fetch_one(query.as_str()).await?);
+    // routines the user may not see the body of have a null definition
+    row[column]
+        .as_str()
+        .map(|ddl| format!("{};", ddl))
+        .ok_or_else(|| anyhow!("The definition of {}.{} is not visible", schema, object))
+}
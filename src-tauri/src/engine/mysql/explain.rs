@@ -0,0 +1,470 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use sqlx::{MySqlPool, Row};
+
+use crate::engine::types::plan::{NodeKind, PlanNode, QueryPlan};
+
+async fn fetch_plan(pool: &MySqlPool, query: &str) -> Result<String> {
+    let mut tx = pool.begin().await?;
+    // ANALYZE executes the statement, the transaction makes sure explaining a write has no effect
+    let row = sqlx::raw_sql(query).fetch_one(&mut *tx).await;
+    tx.rollback().await?;
+    Ok(row?.try_get_unchecked::<String, _>(0)?)
+}
+
+pub async fn explain_query(pool: &MySqlPool, sql: &str, analyze: bool) -> Result<QueryPlan> {
+    match analyze {
+        true => {
+            let tree = fetch_plan(pool, &format!("EXPLAIN ANALYZE {}", sql)).await?;
+            Ok(QueryPlan {
+                analyzed: true,
+                root: parse_tree(&tree)?,
+                planning_time_ms: None,
+                execution_time_ms: None,
+                raw: Value::String(tree),
+            })
+        }
+        false => {
+            let plan = fetch_plan(pool, &format!("EXPLAIN FORMAT=JSON {}", sql)).await?;
+            parse_json_plan(serde_json::from_str(&plan)?, false)
+        }
+    }
+}
+
+/// MariaDB has no tree output, `ANALYZE FORMAT=JSON` returns the regular json
+/// plan with the measured `r_*` values added to each node.
+pub async fn explain_mariadb_query(
+    pool: &MySqlPool,
+    sql: &str,
+    analyze: bool,
+) -> Result<QueryPlan> {
+    let statement = match analyze {
+        true => "ANALYZE FORMAT=JSON",
+        false => "EXPLAIN FORMAT=JSON",
+    };
+    let plan = fetch_plan(pool, &format!("{} {}", statement, sql)).await?;
+    parse_json_plan(serde_json::from_str(&plan)?, analyze)
+}
+
+fn number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|v| v.parse().ok()))
+}
+
+fn parse_json_plan(raw: Value, analyzed: bool) -> Result<QueryPlan> {
+    let block = raw
+        .get("query_block")
+        .ok_or(anyhow!("Unexpected plan format, missing query_block"))?;
+    let root = parse_query_block(block);
+    let execution_time_ms = number(&block["r_total_time_ms"]);
+    Ok(QueryPlan {
+        analyzed,
+        root,
+        planning_time_ms: None,
+        execution_time_ms,
+        raw,
+    })
+}
+
+fn parse_query_block(block: &Value) -> PlanNode {
+    PlanNode {
+        operation: "query_block".to_string(),
+        cost: number(&block["cost_info"]["query_cost"]).or(number(&block["cost"])),
+        actual_time_ms: number(&block["r_total_time_ms"]),
+        children: parse_children(block),
+        ..Default::default()
+    }
+}
+
+fn parse_children(value: &Value) -> Vec<PlanNode> {
+    value
+        .as_object()
+        .map(|object| {
+            object
+                .iter()
+                .flat_map(|(key, value)| parse_operation(key, value))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_operation(key: &str, value: &Value) -> Vec<PlanNode> {
+    match key {
+        "query_block" => vec![parse_query_block(value)],
+        "table" => vec![parse_table(value)],
+        "nested_loop" => vec![PlanNode {
+            kind: NodeKind::Join,
+            operation: key.to_string(),
+            children: value
+                .as_array()
+                .map(|tables| tables.iter().flat_map(parse_children).collect())
+                .unwrap_or_default(),
+            ..Default::default()
+        }],
+        "ordering_operation" | "grouping_operation" | "duplicates_removal" | "windowing" => {
+            let kind = match key {
+                "ordering_operation" => NodeKind::Other,
+                _ => NodeKind::Aggregate,
+            };
+            let mut node = PlanNode {
+                kind,
+                operation: key.to_string(),
+                children: parse_children(value),
+                ..Default::default()
+            };
+            if value["using_filesort"] == true {
+                node = wrap(NodeKind::Sort, "filesort", node);
+            }
+            if value["using_temporary_table"] == true {
+                node = wrap(NodeKind::TempTable, "temporary_table", node);
+            }
+            vec![node]
+        }
+        // MariaDB spells sorts and temporary tables out as separate operations
+        "filesort" => vec![PlanNode {
+            kind: NodeKind::Sort,
+            operation: key.to_string(),
+            condition: value["sort_key"].as_str().map(String::from),
+            actual_rows: number(&value["r_output_rows"]),
+            children: parse_children(value),
+            ..Default::default()
+        }],
+        "temporary_table" => vec![PlanNode {
+            kind: NodeKind::TempTable,
+            operation: key.to_string(),
+            children: parse_children(value),
+            ..Default::default()
+        }],
+        "read_sorted_file" => parse_children(value),
+        "union_result" => vec![PlanNode {
+            kind: match value["using_temporary_table"] == true {
+                true => NodeKind::TempTable,
+                false => NodeKind::Other,
+            },
+            operation: key.to_string(),
+            relation: value["table_name"].as_str().map(String::from),
+            children: parse_operation("query_specifications", &value["query_specifications"]),
+            ..Default::default()
+        }],
+        "query_specifications" => value
+            .as_array()
+            .map(|specs| specs.iter().flat_map(parse_children).collect())
+            .unwrap_or_default(),
+        "attached_subqueries" | "optimized_away_subqueries" | "subqueries" => value
+            .as_array()
+            .map(|subqueries| {
+                subqueries
+                    .iter()
+                    .map(|subquery| PlanNode {
+                        kind: NodeKind::Subquery,
+                        operation: key.to_string(),
+                        children: parse_children(subquery),
+                        ..Default::default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+fn wrap(kind: NodeKind, operation: &str, child: PlanNode) -> PlanNode {
+    PlanNode {
+        kind,
+        operation: operation.to_string(),
+        children: vec![child],
+        ..Default::default()
+    }
+}
+
+fn parse_table(table: &Value) -> PlanNode {
+    let access_type = table["access_type"].as_str().unwrap_or_default();
+    let covering = table["using_index"] == true;
+    let kind = match access_type {
+        "ALL" => NodeKind::FullScan,
+        _ if covering => NodeKind::IndexOnlyScan,
+        "" => NodeKind::Other,
+        _ => NodeKind::IndexScan,
+    };
+    let children = table
+        .get("materialized_from_subquery")
+        .map(|subquery| {
+            vec![PlanNode {
+                kind: NodeKind::TempTable,
+                operation: "materialized_from_subquery".to_string(),
+                children: parse_children(subquery),
+                ..Default::default()
+            }]
+        })
+        .unwrap_or_default();
+    PlanNode {
+        kind,
+        operation: format!("table ({})", access_type),
+        relation: table["table_name"].as_str().map(String::from),
+        index: table["key"].as_str().map(String::from),
+        condition: table["attached_condition"].as_str().map(String::from),
+        estimated_rows: number(&table["rows_examined_per_scan"]).or(number(&table["rows"])),
+        actual_rows: number(&table["r_rows"]),
+        loops: number(&table["r_loops"]),
+        cost: number(&table["cost_info"]["prefix_cost"]).or(number(&table["cost"])),
+        actual_time_ms: number(&table["r_total_time_ms"]),
+        buffers: None,
+        children,
+    }
+}
+
+/// Parses the indented text tree returned by MySQL's `EXPLAIN ANALYZE`, e.g.
+/// `-> Table scan on t  (cost=0.35 rows=1) (actual time=0.02..0.03 rows=1 loops=1)`.
+fn parse_tree(tree: &str) -> Result<PlanNode> {
+    // stack of (indent, node), children are attached to their parent when popped
+    let mut stack: Vec<(usize, PlanNode)> = vec![];
+    let mut roots: Vec<PlanNode> = vec![];
+    for line in tree.lines() {
+        let Some(indent) = line.find("-> ") else {
+            continue;
+        };
+        let node = parse_tree_line(&line[indent + 3..]);
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            pop(&mut stack, &mut roots);
+        }
+        stack.push((indent, node));
+    }
+    while !stack.is_empty() {
+        pop(&mut stack, &mut roots);
+    }
+    match roots.len() {
+        0 => Err(anyhow!("Unexpected plan format, no nodes found")),
+        1 => Ok(roots.remove(0)),
+        _ => Ok(PlanNode {
+            operation: "query_block".to_string(),
+            children: roots,
+            ..Default::default()
+        }),
+    }
+}
+
+fn pop(stack: &mut Vec<(usize, PlanNode)>, roots: &mut Vec<PlanNode>) {
+    if let Some((_, node)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+fn parse_tree_line(line: &str) -> PlanNode {
+    let label_end = [line.find("  (cost="), line.find(" (actual time=")]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(line.len());
+    let label = line[..label_end].trim();
+    let stats = &line[label_end..];
+
+    let kind = tree_node_kind(label);
+    let (relation, index) = match kind {
+        NodeKind::FullScan | NodeKind::IndexScan | NodeKind::IndexOnlyScan => {
+            let target = label.split_once(" on ").map(|(_, t)| t).unwrap_or_default();
+            let (relation, rest) = target.split_once(" using ").unwrap_or((target, ""));
+            let relation = relation.split_whitespace().next().map(String::from);
+            let index = rest.split_whitespace().next().map(String::from);
+            (relation, index)
+        }
+        _ => (None, None),
+    };
+    let condition = label
+        .split_once(": ")
+        .map(|(_, c)| c.to_string())
+        .or_else(|| {
+            label
+                .find(" (")
+                .filter(|_| index.is_some())
+                .map(|i| label[i + 1..].to_string())
+        });
+
+    let estimate = stats
+        .find("(cost=")
+        .map(|i| &stats[i..])
+        .and_then(|s| s.find(')').map(|e| &s[..e]));
+    let actual = stats
+        .find("(actual time=")
+        .map(|i| &stats[i..])
+        .and_then(|s| s.find(')').map(|e| &s[..e]));
+
+    PlanNode {
+        kind,
+        operation: label.to_string(),
+        relation,
+        index,
+        condition,
+        estimated_rows: estimate.and_then(|s| tree_stat(s, "rows=")),
+        actual_rows: actual.and_then(|s| tree_stat(s, "rows=")),
+        loops: actual.and_then(|s| tree_stat(s, "loops=")),
+        cost: estimate.and_then(|s| tree_stat(s, "cost=")),
+        // `actual time=first..last`, the time until the last row was returned
+        actual_time_ms: actual
+            .and_then(|s| s.split_once(".."))
+            .and_then(|(_, t)| t.split_whitespace().next())
+            .and_then(|t| t.parse().ok()),
+        buffers: None,
+        children: vec![],
+    }
+}
+
+fn tree_stat(stats: &str, key: &str) -> Option<f64> {
+    let start = stats.find(key)? + key.len();
+    stats[start..]
+        .split(|c: char| c.is_whitespace() || c == ')')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn tree_node_kind(label: &str) -> NodeKind {
+    let label = label.to_lowercase();
+    if label.starts_with("table scan") {
+        NodeKind::FullScan
+    } else if label.starts_with("covering index") {
+        NodeKind::IndexOnlyScan
+    } else if label.contains("index") && (label.contains("scan") || label.contains("lookup")) {
+        NodeKind::IndexScan
+    } else if label.contains("join") || label.starts_with("nested loop") {
+        NodeKind::Join
+    } else if label.starts_with("sort") {
+        NodeKind::Sort
+    } else if label.contains("aggregate") {
+        NodeKind::Aggregate
+    } else if label.starts_with("materialize") || label.starts_with("temporary table") {
+        NodeKind::TempTable
+    } else if label.starts_with("select #") {
+        NodeKind::Subquery
+    } else {
+        NodeKind::Other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{parse_json_plan, parse_tree};
+    use crate::engine::types::plan::NodeKind;
+
+    #[test]
+    fn test_parse_explain_analyze_tree() {
+        let tree = "-> Sort: u.name  (actual time=0.210..0.211 rows=2 loops=1)\n    \
+            -> Nested loop inner join  (cost=1.10 rows=2) (actual time=0.050..0.090 rows=2 loops=1)\n        \
+            -> Table scan on u  (cost=0.45 rows=2) (actual time=0.030..0.040 rows=2 loops=1)\n        \
+            -> Index lookup on o using user_id (user_id=u.id)  (cost=0.30 rows=1) (actual time=0.010..0.012 rows=1 loops=2)\n";
+        let root = parse_tree(tree).unwrap();
+        assert_eq!(root.kind, NodeKind::Sort);
+        assert_eq!(root.actual_time_ms, Some(0.211));
+        let join = &root.children[0];
+        assert_eq!(join.kind, NodeKind::Join);
+        assert_eq!(join.children.len(), 2);
+        assert_eq!(join.children[0].kind, NodeKind::FullScan);
+        assert_eq!(join.children[0].relation.as_deref(), Some("u"));
+        let lookup = &join.children[1];
+        assert_eq!(lookup.kind, NodeKind::IndexScan);
+        assert_eq!(lookup.index.as_deref(), Some("user_id"));
+        assert_eq!(lookup.estimated_rows, Some(1.0));
+        assert_eq!(lookup.loops, Some(2.0));
+    }
+
+    #[test]
+    fn test_parse_json_plan() {
+        let raw = json!({"query_block": {
+            "select_id": 1,
+            "cost_info": {"query_cost": "3.40"},
+            "ordering_operation": {
+                "using_filesort": true,
+                "table": {
+                    "table_name": "users",
+                    "access_type": "ALL",
+                    "rows_examined_per_scan": 12,
+                    "cost_info": {"prefix_cost": "3.40"},
+                    "attached_condition": "(`users`.`age` > 30)"
+                }
+            }
+        }});
+        let plan = parse_json_plan(raw, false).unwrap();
+        assert_eq!(plan.root.cost, Some(3.4));
+        let nodes = plan.root.walk();
+        assert!(nodes.iter().any(|n| n.kind == NodeKind::Sort));
+        let scan = nodes.iter().find(|n| n.kind == NodeKind::FullScan).unwrap();
+        assert_eq!(scan.relation.as_deref(), Some("users"));
+        assert_eq!(scan.estimated_rows, Some(12.0));
+    }
+
+    #[test]
+    fn test_parse_mariadb_analyzed_plan() {
+        // ANALYZE FORMAT=JSON output of MariaDB 10.11
+        let raw = json!({"query_block": {
+            "select_id": 1,
+            "r_loops": 1,
+            "r_total_time_ms": 0.412,
+            "filesort": {
+                "sort_key": "u.`name`",
+                "r_loops": 1,
+                "r_total_time_ms": 0.031,
+                "r_used_priority_queue": false,
+                "r_output_rows": 3,
+                "r_buffer_size": "2047Kb",
+                "r_sort_mode": "sort_key,addon_fields",
+                "temporary_table": {
+                    "nested_loop": [
+                        {
+                            "table": {
+                                "table_name": "u",
+                                "access_type": "ALL",
+                                "r_loops": 1,
+                                "rows": 12,
+                                "r_rows": 12,
+                                "r_table_time_ms": 0.021,
+                                "filtered": 100,
+                                "r_filtered": 25,
+                                "attached_condition": "u.age > 30"
+                            }
+                        },
+                        {
+                            "table": {
+                                "table_name": "o",
+                                "access_type": "ref",
+                                "possible_keys": ["user_id"],
+                                "key": "user_id",
+                                "key_length": "5",
+                                "used_key_parts": ["user_id"],
+                                "ref": ["noir.u.id"],
+                                "r_loops": 3,
+                                "rows": 1,
+                                "r_rows": 1.33,
+                                "r_table_time_ms": 0.018,
+                                "filtered": 100,
+                                "r_filtered": 100
+                            }
+                        }
+                    ]
+                }
+            }
+        }});
+        let plan = parse_json_plan(raw, true).unwrap();
+        assert!(plan.analyzed);
+        assert_eq!(plan.execution_time_ms, Some(0.412));
+        let sort = &plan.root.children[0];
+        assert_eq!(sort.kind, NodeKind::Sort);
+        assert_eq!(sort.condition.as_deref(), Some("u.`name`"));
+        assert_eq!(sort.actual_rows, Some(3.0));
+        assert_eq!(sort.children[0].kind, NodeKind::TempTable);
+        let join = &sort.children[0].children[0];
+        assert_eq!(join.kind, NodeKind::Join);
+        let scan = &join.children[0];
+        assert_eq!(scan.kind, NodeKind::FullScan);
+        assert_eq!(scan.estimated_rows, Some(12.0));
+        assert_eq!(scan.actual_rows, Some(12.0));
+        let lookup = &join.children[1];
+        assert_eq!(lookup.kind, NodeKind::IndexScan);
+        assert_eq!(lookup.index.as_deref(), Some("user_id"));
+        assert_eq!(lookup.loops, Some(3.0));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod ddl;
+pub mod explain;
+pub mod monitor;
+pub mod objects;
 pub mod query;
 pub mod sql_to_json;
 pub mod tables;
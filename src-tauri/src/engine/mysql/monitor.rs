@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use sqlx::MySqlPool;
+
+use crate::engine::types::{
+    monitor::{LockWait, Session, TopQuery, TopQueryOrder},
+    value::json_to_f64,
+};
+
+use super::sql_to_json::row_to_json;
+
+pub async fn get_top_queries(
+    pool: &MySqlPool,
+    order: TopQueryOrder,
+    limit: usize,
+) -> Result<Vec<TopQuery>> {
+    let enabled = sqlx::query("SELECT @@performance_schema AS enabled;")
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    if enabled.first().and_then(|r| json_to_f64(&r["enabled"])) != Some(1.0) {
+        return Err(anyhow!(
+            "performance_schema is disabled on this server. Start it with performance_schema=ON to collect statement statistics."
+        ));
+    }
+    let consumer = sqlx::query(
+        "SELECT ENABLED AS enabled FROM performance_schema.setup_consumers WHERE NAME = 'statements_digest';",
+    )
+    .map(row_to_json)
+    .fetch_all(pool)
+    .await?;
+    if consumer.first().and_then(|r| r["enabled"].as_str()) != Some("YES") {
+        return Err(anyhow!(
+            "The statements_digest consumer is disabled. Enable it with UPDATE performance_schema.setup_consumers SET ENABLED = 'YES' WHERE NAME = 'statements_digest';"
+        ));
+    }
+    // timers are in picoseconds
+    let query = format!(
+        "SELECT DIGEST AS query_id, DIGEST_TEXT AS query, SCHEMA_NAME AS `database`,
+            COUNT_STAR AS calls, SUM_TIMER_WAIT / 1e9 AS total_time_ms, AVG_TIMER_WAIT / 1e9 AS mean_time_ms,
+            SUM_ROWS_SENT + SUM_ROWS_AFFECTED AS row_count
+            FROM performance_schema.events_statements_summary_by_digest
+            WHERE DIGEST_TEXT IS NOT NULL
+            ORDER BY {} DESC LIMIT {};",
+        order.column(),
+        limit
+    );
+    let rows = sqlx::query(&query).map(row_to_json).fetch_all(pool).await?;
+    Ok(rows.iter().map(TopQuery::from_row).collect())
+}
+
+pub async fn get_sessions(pool: &MySqlPool) -> Result<Vec<Session>> {
+    let query =
+        "SELECT ID AS id, USER AS user_name, DB AS `database`, HOST AS client, NULL AS application,
+        COMMAND AS state, TIME * 1000 AS duration_ms, STATE AS wait_event, INFO AS query
+        FROM information_schema.PROCESSLIST WHERE ID <> CONNECTION_ID() AND COMMAND <> 'Daemon'
+        ORDER BY TIME DESC;";
+    let rows = sqlx::query(query).map(row_to_json).fetch_all(pool).await?;
+    Ok(rows.iter().map(Session::from_row).collect())
+}
+
+// KILL is sent over the text protocol, it is not allowed in prepared statements on every server
+pub async fn cancel_session(pool: &MySqlPool, id: i64) -> Result<()> {
+    sqlx::raw_sql(&format!("KILL QUERY {};", id))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn terminate_session(pool: &MySqlPool, id: i64) -> Result<()> {
+    sqlx::raw_sql(&format!("KILL {};", id))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_lock_waits(pool: &MySqlPool) -> Result<Vec<LockWait>> {
+    let query = "SELECT rt.PROCESSLIST_ID AS waiting, bt.PROCESSLIST_ID AS blocking,
+        CONCAT(l.OBJECT_SCHEMA, '.', l.OBJECT_NAME) AS relation, l.LOCK_MODE AS lock_mode,
+        TIMESTAMPDIFF(MICROSECOND, t.trx_wait_started, NOW(6)) / 1e3 AS wait_ms
+        FROM performance_schema.data_lock_waits w
+        JOIN performance_schema.threads rt ON rt.THREAD_ID = w.REQUESTING_THREAD_ID
+        JOIN performance_schema.threads bt ON bt.THREAD_ID = w.BLOCKING_THREAD_ID
+        JOIN performance_schema.data_locks l ON l.ENGINE_LOCK_ID = w.REQUESTING_ENGINE_LOCK_ID
+        LEFT JOIN information_schema.INNODB_TRX t ON t.trx_mysql_thread_id = rt.PROCESSLIST_ID;";
+    let rows = sqlx::query(query).map(row_to_json).fetch_all(pool).await?;
+    Ok(rows.iter().map(LockWait::from_row).collect())
+}
+
+/// MariaDB has no `data_lock_waits`, InnoDB still exposes its lock tables in
+/// information_schema. Their table names are quoted, like `` `db`.`t` ``.
+pub async fn get_mariadb_lock_waits(pool: &MySqlPool) -> Result<Vec<LockWait>> {
+    let query = "SELECT r.trx_mysql_thread_id AS waiting, b.trx_mysql_thread_id AS blocking,
+        REPLACE(l.lock_table, '`', '') AS relation, l.lock_mode AS lock_mode,
+        TIMESTAMPDIFF(MICROSECOND, r.trx_wait_started, NOW(6)) / 1e3 AS wait_ms
+        FROM information_schema.INNODB_LOCK_WAITS w
+        JOIN information_schema.INNODB_TRX r ON r.trx_id = w.requesting_trx_id
+        JOIN information_schema.INNODB_TRX b ON b.trx_id = w.blocking_trx_id
+        LEFT JOIN information_schema.INNODB_LOCKS l ON l.lock_id = w.requested_lock_id;";
+    let rows = sqlx::query(query).map(row_to_json).fetch_all(pool).await?;
+    Ok(rows.iter().map(LockWait::from_row).collect())
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde_json::Value;
+use sqlx::MySqlPool;
+
+use crate::database::QueryType;
+use crate::engine::{
+    quote::{quote_ident, quote_literal, quote_qualified},
+    types::{
+        catalog::{Event, Partition, Sequence, SystemTime},
+        config::Dialect,
+        result::ResultSet,
+    },
+};
+
+use super::{query::execute_query, sql_to_json::row_to_json};
+
+// MySQL and MariaDB objects without a counterpart in the other dialects,
+// the `mariadb` ones only exist in MariaDB.
+
+pub async fn get_events(pool: &MySqlPool, schema: &str) -> Result<Vec<Event>> {
+    let query = "SELECT EVENT_SCHEMA AS event_schema, EVENT_NAME AS event_name,
+        DEFINER AS definer, TIME_ZONE AS time_zone, EVENT_TYPE AS event_type,
+        EXECUTE_AT AS execute_at, INTERVAL_VALUE AS interval_value,
+        INTERVAL_FIELD AS interval_field, STARTS AS starts, ENDS AS ends, STATUS AS status,
+        ON_COMPLETION AS on_completion, LAST_EXECUTED AS last_executed,
+        EVENT_DEFINITION AS event_definition, EVENT_COMMENT AS comment
+        FROM information_schema.EVENTS WHERE EVENT_SCHEMA = ? ORDER BY EVENT_NAME;";
+    let rows = sqlx::query(query)
+        .bind(schema)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Event::from_row).collect())
+}
+
+pub async fn get_partitions(
+    pool: &MySqlPool,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Partition>> {
+    // unpartitioned tables have a single row without a name
+    let query = "SELECT TABLE_SCHEMA AS table_schema, TABLE_NAME AS table_name,
+        PARTITION_NAME AS partition_name, SUBPARTITION_NAME AS subpartition_name,
+        PARTITION_ORDINAL_POSITION AS ordinal_position, PARTITION_METHOD AS partition_method,
+        PARTITION_EXPRESSION AS partition_expression,
+        PARTITION_DESCRIPTION AS partition_description, TABLE_ROWS AS table_rows,
+        PARTITION_COMMENT AS comment
+        FROM information_schema.PARTITIONS
+        WHERE TABLE_SCHEMA = ? AND PARTITION_NAME IS NOT NULL";
+    let order = "ORDER BY TABLE_NAME, PARTITION_ORDINAL_POSITION, SUBPARTITION_ORDINAL_POSITION";
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND TABLE_NAME = ? {};", query, order);
+            sqlx::query(&query)
+                .bind(schema)
+                .bind(table)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
+        None => {
+            let query = format!("{} {};", query, order);
+            sqlx::query(&query)
+                .bind(schema)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
+    };
+    Ok(rows.iter().map(Partition::from_row).collect())
+}
+
+/// MariaDB has no catalog view of sequence settings, each sequence is read
+/// as the single row table it is.
+pub async fn get_mariadb_sequences(pool: &MySqlPool, schema: &str) -> Result<Vec<Sequence>> {
+    let names: Vec<Value> = sqlx::query(
+        "SELECT TABLE_NAME AS name FROM information_schema.TABLES
+        WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'SEQUENCE' ORDER BY TABLE_NAME;",
+    )
+    .bind(schema)
+    .map(row_to_json)
+    .fetch_all(pool)
+    .await?;
+    let mut sequences = vec![];
+    for name in names.iter().filter_map(|n| n["name"].as_str()) {
+        let query = format!(
+            "SELECT {} AS sequence_schema, {} AS sequence_name, start_value,
+            minimum_value AS min_value, maximum_value AS max_value, increment,
+            cycle_option AS cycle FROM {};",
+            quote_literal(&Dialect::MariaDB, schema),
+            quote_literal(&Dialect::MariaDB, name),
+            quote_qualified(&Dialect::MariaDB, &[schema, name])
+        );
+        let row = sqlx::query(&query).map(row_to_json).fetch_one(pool).await?;
+        sequences.push(Sequence {
+            data_type: Some("bigint".to_string()),
+            ..Sequence::from_row(&row)
+        });
+    }
+    Ok(sequences)
+}
+
+/// Rows of a system-versioned table at the given system time, with the
+/// period columns that are invisible to `SELECT *`.
+pub async fn get_mariadb_table_history(
+    pool: &MySqlPool,
+    schema: &str,
+    table: &str,
+    time: &SystemTime,
+    limit: Option<usize>,
+) -> Result<ResultSet> {
+    let period: Vec<Value> = sqlx::query(
+        "SELECT COLUMN_NAME AS name FROM information_schema.COLUMNS
+        WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+        AND (EXTRA LIKE '%ROW START%' OR EXTRA LIKE '%ROW END%')
+        ORDER BY EXTRA LIKE '%ROW END%';",
+    )
+    .bind(schema)
+    .bind(table)
+    .map(row_to_json)
+    .fetch_all(pool)
+    .await?;
+    let mut columns = vec!["*".to_string()];
+    match period.len() {
+        // implicit period columns are not part of the catalog
+        0 => columns.extend(["ROW_START".to_string(), "ROW_END".to_string()]),
+        _ => columns.extend(
+            period
+                .iter()
+                .filter_map(|c| c["name"].as_str())
+                .map(|c| quote_ident(&Dialect::MariaDB, c)),
+        ),
+    }
+    let literal = |t: &str| format!("TIMESTAMP {}", quote_literal(&Dialect::MariaDB, t));
+    let time = match time {
+        SystemTime::All => "ALL".to_string(),
+        SystemTime::AsOf { at } => format!("AS OF {}", literal(at)),
+        SystemTime::Between { from, to } => {
+            format!("BETWEEN {} AND {}", literal(from), literal(to))
+        }
+    };
+    let mut query = format!(
+        "SELECT {} FROM {} FOR SYSTEM_TIME {}",
+        columns.join(", "),
+        quote_qualified(&Dialect::MariaDB, &[schema, table]),
+        time
+    );
+    if let Some(limit) = limit {
+        query.push_str(&format!(" LIMIT {}", limit));
+    }
+    execute_query(pool, &query, QueryType::Select).await
+}
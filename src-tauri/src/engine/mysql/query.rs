@@ -1,9 +1,15 @@
 use crate::database::QueryType;
-use crate::engine::types::result::{ResultSet, TableMetadata};
+use crate::engine::types::edit::BoundStatement;
+use crate::engine::types::result::{
+    ResultSet, StatementError, StatementResult, TableMetadata, TxResult,
+};
 use anyhow::{anyhow, Result};
-use sqlx::MySqlPool;
+use futures::TryStreamExt;
+use serde_json::Value;
+use sqlx::{Either, Executor, MySqlPool, Row};
+use std::time::Instant;
 
-use super::sql_to_json::row_to_json;
+use super::sql_to_json::{result_columns, row_to_json};
 
 pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Result<ResultSet> {
     let start_time = std::time::SystemTime::now()
@@ -12,7 +18,17 @@ pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Resul
         .as_millis() as u64;
     match t {
         QueryType::Select => {
-            let rows = sqlx::query(query).map(row_to_json).fetch_all(pool).await?;
+            let rows = sqlx::query(query).fetch_all(pool).await?;
+            let columns = match rows.first() {
+                Some(row) => result_columns(row.columns()),
+                // without rows the columns come from the prepared statement
+                None => pool
+                    .describe(query)
+                    .await
+                    .map(|d| result_columns(d.columns()))
+                    .unwrap_or_default(),
+            };
+            let rows = rows.into_iter().map(row_to_json).collect();
             Ok(ResultSet {
                 start_time,
                 end_time: std::time::SystemTime::now()
@@ -20,8 +36,10 @@ pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Resul
                     .expect("Time went backwards")
                     .as_millis() as u64,
                 affected_rows: 0,
+                columns,
                 rows,
                 table: TableMetadata::default(),
+                row_keys: vec![],
             })
         }
         _ => {
@@ -35,26 +53,94 @@ pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Resul
                 start_time,
                 end_time,
                 affected_rows,
+                columns: vec![],
                 rows: vec![],
                 table: TableMetadata::default(),
+                row_keys: vec![],
             })
         }
     }
 }
 
-pub async fn execute_tx(pool: &MySqlPool, queries: Vec<&str>) -> Result<()> {
+pub async fn execute_tx(pool: &MySqlPool, queries: Vec<&str>, dry_run: bool) -> Result<TxResult> {
     let mut transaction = pool.begin().await?;
+    let mut result = TxResult::default();
+    for (index, q) in queries.into_iter().enumerate() {
+        let start = Instant::now();
+        let mut outcome = StatementResult::default();
+        let mut stream = (&mut *transaction).fetch_many(sqlx::query(q));
+        let error = loop {
+            match stream.try_next().await {
+                Ok(Some(Either::Left(done))) => {
+                    outcome.affected_rows += done.rows_affected();
+                    if done.last_insert_id() > 0 {
+                        outcome.last_insert_id = i64::try_from(done.last_insert_id()).ok();
+                    }
+                }
+                Ok(Some(Either::Right(row))) => outcome.rows.push(row_to_json(row)),
+                Ok(None) => break None,
+                Err(e) => break Some(e),
+            }
+        };
+        drop(stream);
+        if let Some(e) = error {
+            result.error = Some(StatementError {
+                index,
+                code: e
+                    .as_database_error()
+                    .and_then(|d| d.code())
+                    .map(|c| c.to_string()),
+                message: e.to_string(),
+            });
+            transaction.rollback().await?;
+            return Ok(result);
+        }
+        outcome.duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+        result.statements.push(outcome);
+    }
+    match dry_run {
+        true => transaction.rollback().await?,
+        false => transaction.commit().await?,
+    }
+    result.committed = !dry_run;
+    Ok(result)
+}
 
-    for q in queries {
-        match sqlx::query(q).execute(&mut *transaction).await {
-            Ok(_) => continue,
+pub async fn execute_bound_tx(
+    pool: &MySqlPool,
+    statements: Vec<BoundStatement>,
+) -> Result<Vec<u64>> {
+    let mut transaction = pool.begin().await?;
+    let mut affected = vec![];
+    let mut conflict = false;
+    for statement in statements {
+        let mut query = sqlx::query(&statement.sql);
+        for param in &statement.params {
+            query = match param {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(b) => query.bind(*b),
+                Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                    (Some(i), _) => query.bind(i),
+                    (_, Some(u)) => query.bind(u),
+                    _ => query.bind(n.as_f64()),
+                },
+                Value::String(s) => query.bind(s.as_str()),
+                _ => query.bind(param.to_string()),
+            };
+        }
+        match query.execute(&mut *transaction).await {
+            Ok(result) => affected.push(result.rows_affected()),
             Err(e) => {
-                // Attempt to rollback the transaction
-                let _ = transaction.rollback();
+                transaction.rollback().await?;
                 return Err(anyhow!("Query failed: {}", e));
             }
         }
+        // the remaining statements still run to find every conflict
+        conflict |= statement.expect_row && affected.last() != Some(&1);
+    }
+    match conflict {
+        true => transaction.rollback().await?,
+        false => transaction.commit().await?,
     }
-    transaction.commit().await?;
-    Ok(())
+    Ok(affected)
 }
@@ -1,9 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde_json::{self, json, Value};
-use sqlx::mysql::MySqlRow;
+use sqlx::mysql::{MySqlColumn, MySqlRow};
 use sqlx::Decode;
 use sqlx::{Column, Row, TypeInfo, ValueRef};
 
+use crate::engine::types::{
+    result::{CellType, ResultColumn},
+    value::{i64_to_json, timestamptz_to_json, u64_to_json},
+};
+
 pub fn row_to_json(row: MySqlRow) -> Value {
     let mut object = json!({});
     for column in row.columns().iter() {
@@ -14,6 +19,43 @@ pub fn row_to_json(row: MySqlRow) -> Value {
     object
 }
 
+pub fn result_columns(columns: &[MySqlColumn]) -> Vec<ResultColumn> {
+    columns
+        .iter()
+        .map(|column| {
+            let db_type = column.type_info().name().to_string();
+            ResultColumn {
+                name: column.name().to_string(),
+                cell_type: cell_type(&db_type),
+                db_type,
+                origin: None,
+                editable: false,
+            }
+        })
+        .collect()
+}
+
+fn cell_type(db_type: &str) -> CellType {
+    match db_type.trim_end_matches(" UNSIGNED") {
+        "BOOL" | "BOOLEAN" => CellType::Bool,
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "INTEGER" | "YEAR" => CellType::Integer,
+        "BIGINT" => CellType::BigInt,
+        "REAL" | "FLOAT" | "DOUBLE" => CellType::Float,
+        "NUMERIC" | "DECIMAL" | "FIXED" => CellType::Decimal,
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" => {
+            CellType::Text
+        }
+        "JSON" => CellType::Json,
+        "DATE" => CellType::Date,
+        "TIME" => CellType::Time,
+        "DATETIME" => CellType::Timestamp,
+        "TIMESTAMP" => CellType::TimestampTz,
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BIT"
+        | "GEOMETRY" => CellType::Binary,
+        _ => CellType::Other,
+    }
+}
+
 pub fn sql_to_json(row: &MySqlRow, col: &sqlx::mysql::MySqlColumn) -> Value {
     let raw_value_result = row.try_get_raw(col.ordinal());
     match raw_value_result {
@@ -41,14 +83,16 @@ pub fn sql_nonnull_to_json<'r>(
 ) -> Value {
     let raw_value = get_ref();
     match raw_value.type_info().name() {
-        "REAL" | "FLOAT" | "NUMERIC" | "DECIMAL" | "DOUBLE" | "FIXED" => {
-            <f64 as Decode<sqlx::MySql>>::decode(raw_value)
-                .unwrap_or(f64::NAN)
-                .into()
-        }
-        "BIGINT UNSIGNED" => <u64 as Decode<sqlx::MySql>>::decode(raw_value)
+        "REAL" | "FLOAT" | "DOUBLE" => <f64 as Decode<sqlx::MySql>>::decode(raw_value)
+            .unwrap_or(f64::NAN)
+            .into(),
+        // decimals are sent as text, keep it so no digits are lost
+        "NUMERIC" | "DECIMAL" | "FIXED" => <String as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
+        "BIGINT UNSIGNED" => {
+            u64_to_json(<u64 as Decode<sqlx::MySql>>::decode(raw_value).unwrap_or_default())
+        }
         "INT UNSIGNED" => <u32 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
@@ -58,10 +102,10 @@ pub fn sql_nonnull_to_json<'r>(
         "TINYINT UNSIGNED" => <u8 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
-        "BIGINT" => <i64 as Decode<sqlx::MySql>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
-        "INT" | "INTEGER" => <i32 as Decode<sqlx::MySql>>::decode(raw_value)
+        "BIGINT" => {
+            i64_to_json(<i64 as Decode<sqlx::MySql>>::decode(raw_value).unwrap_or_default())
+        }
+        "INT" | "INTEGER" | "MEDIUMINT" => <i32 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
         "SMALLINT" => <i16 as Decode<sqlx::MySql>>::decode(raw_value)
@@ -81,18 +125,16 @@ pub fn sql_nonnull_to_json<'r>(
         "YEAR" => <i16 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
-        "DATETIME" | "DATETIME2" | "DATETIMEOFFSET" | "TIMESTAMP" | "TIMESTAMPTZ" => {
-            let mut date_time = <DateTime<Utc> as Decode<sqlx::MySql>>::decode(get_ref());
-            if date_time.is_err() {
-                date_time = <chrono::NaiveDateTime as Decode<sqlx::MySql>>::decode(raw_value)
-                    .map(|d| d.and_utc());
-            }
-            Value::String(
-                date_time
-                    .as_ref()
-                    .map_or_else(ToString::to_string, DateTime::to_rfc3339),
-            )
-        }
+        // DATETIME has no zone, it is kept as the wall clock time that was stored
+        "DATETIME" => <chrono::NaiveDateTime as Decode<sqlx::MySql>>::decode(raw_value)
+            .as_ref()
+            .map_or_else(ToString::to_string, ToString::to_string)
+            .into(),
+        // TIMESTAMP is converted to the session zone, which sqlx pins to +00:00
+        "TIMESTAMP" => match <DateTime<Utc> as Decode<sqlx::MySql>>::decode(raw_value) {
+            Ok(date_time) => timestamptz_to_json(date_time),
+            Err(e) => Value::String(e.to_string()),
+        },
         // "JSON" | "JSON[]" | "JSONB" | "JSONB[]" => {
         //     <Value as Decode<sqlx::MySql>>::decode(raw_value).unwrap_or_default()
         // }
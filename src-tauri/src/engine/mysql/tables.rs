@@ -1,155 +1,243 @@
 use anyhow::Result;
 use futures::try_join;
-use serde_json::{json, Value};
+use serde_json::Value;
 use sqlx::MySqlPool;
 
-use crate::engine::types::connection::InitiatedConnection;
+use crate::engine::types::catalog::{
+    Column, Constraint, ForeignKey, Index, PrimaryKey, Routine, SchemaSnapshot, Table, Trigger,
+    View,
+};
 
 use super::sql_to_json::row_to_json;
 
-pub async fn get_table_structure(
-    conn: &InitiatedConnection,
-    pool: &MySqlPool,
-    table: String,
-) -> Result<Value> {
-    let (columns, foreign_keys, triggers, indices, pk) = try_join!(
-        get_columns(conn, pool, Some(&table)),
-        get_foreign_keys(conn, pool, &table),
-        get_triggers(conn, pool, Some(&table)),
-        get_indices(conn, pool, &table),
-        get_primary_key(conn, pool, &table),
-    )?;
+// The catalog queries below end with the schema filter so the single table
+// lookups and the schema snapshot can narrow them down differently.
 
-    let result = json!({
-        "table": table,
-        "columns": columns,
-        "foreign_keys": foreign_keys,
-        "indices": indices,
-        "triggers": triggers,
-        "primary_key": pk,
-    });
+const COLUMNS: &str = "SELECT
+        TABLE_SCHEMA AS table_schema,
+        TABLE_NAME AS table_name,
+        COLUMN_NAME AS column_name,
+        ORDINAL_POSITION AS ordinal_position,
+        DATA_TYPE AS data_type,
+        COLUMN_TYPE AS column_type,
+        IS_NULLABLE AS is_nullable,
+        COLUMN_DEFAULT AS column_default,
+        CHARACTER_MAXIMUM_LENGTH AS character_maximum_length,
+        COLLATION_NAME AS collation_name,
+        COLUMN_COMMENT AS comment,
+        CASE WHEN EXTRA LIKE '%VIRTUAL GENERATED%' THEN 'VIRTUAL'
+            WHEN EXTRA LIKE '%STORED GENERATED%' OR EXTRA LIKE '%PERSISTENT GENERATED%'
+            THEN 'STORED' END AS generated,
+        GENERATION_EXPRESSION AS generation_expression,
+        EXTRA LIKE '%auto_increment%' AS is_auto_increment,
+        CASE WHEN COLUMN_DEFAULT IS NULL THEN NULL
+            WHEN EXTRA LIKE '%DEFAULT_GENERATED%' OR COLUMN_DEFAULT = 'CURRENT_TIMESTAMP'
+            THEN 'Expression'
+            WHEN VERSION() NOT LIKE '%MariaDB%' THEN 'Literal' END AS default_kind
+        FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = ?";
 
-    Ok(result)
-}
+const PRIMARY_KEYS: &str =
+    "SELECT CONSTRAINT_NAME AS constraint_name, TABLE_SCHEMA AS table_schema,
+        TABLE_NAME AS table_name, COLUMN_NAME AS column_name, ORDINAL_POSITION AS ordinal_position
+        FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE CONSTRAINT_NAME = 'PRIMARY'
+        AND TABLE_SCHEMA = ?";
+
+const FOREIGN_KEYS: &str = "SELECT
+          rc.CONSTRAINT_NAME AS constraint_name,
+          kc.TABLE_SCHEMA AS table_schema,
+          kc.TABLE_NAME AS table_name,
+          kc.COLUMN_NAME AS column_name,
+          kc.ORDINAL_POSITION AS ordinal_position,
+          kc.REFERENCED_TABLE_SCHEMA AS referenced_schema,
+          kc.REFERENCED_TABLE_NAME AS referenced_table_name,
+          kc.REFERENCED_COLUMN_NAME AS referenced_column_name,
+          rc.UPDATE_RULE AS update_rule,
+          rc.DELETE_RULE AS delete_rule
+        FROM information_schema.REFERENTIAL_CONSTRAINTS rc
+        JOIN information_schema.KEY_COLUMN_USAGE kc ON rc.CONSTRAINT_SCHEMA = kc.TABLE_SCHEMA
+          AND rc.TABLE_NAME = kc.TABLE_NAME
+          AND rc.CONSTRAINT_NAME = kc.CONSTRAINT_NAME
+        WHERE rc.CONSTRAINT_SCHEMA = ?";
+
+const INDICES: &str = "SELECT INDEX_NAME AS index_name, TABLE_SCHEMA AS table_schema,
+        TABLE_NAME AS table_name, COLUMN_NAME AS column_name, NON_UNIQUE = 0 AS is_unique,
+        INDEX_NAME = 'PRIMARY' AS is_primary, INDEX_TYPE AS index_type
+        FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = ?";
+
+const TRIGGERS: &str = "SELECT TRIGGER_NAME AS trigger_name, EVENT_OBJECT_SCHEMA AS table_schema,
+        EVENT_OBJECT_TABLE AS event_object_table, EVENT_MANIPULATION AS event_manipulation,
+        ACTION_TIMING AS action_timing, ACTION_STATEMENT AS action_statement
+        FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = ?";
+
+const VIEWS: &str = "SELECT TABLE_SCHEMA AS table_schema, TABLE_NAME AS table_name,
+        VIEW_DEFINITION AS view_definition
+        FROM information_schema.VIEWS WHERE TABLE_SCHEMA = ?";
+
+const ROUTINES: &str = "SELECT ROUTINE_SCHEMA AS routine_schema, ROUTINE_NAME AS routine_name,
+        ROUTINE_TYPE AS routine_type, DTD_IDENTIFIER AS data_type,
+        ROUTINE_DEFINITION AS routine_definition
+        FROM information_schema.ROUTINES WHERE ROUTINE_SCHEMA = ?";
 
 pub async fn get_columns(
-    conn: &InitiatedConnection,
     pool: &MySqlPool,
+    schema: &str,
     table: Option<&str>,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT 
-        COLUMN_NAME, 
-        COLUMN_TYPE, 
-        COLUMN_KEY,
-        DATA_TYPE, 
-        IS_NULLABLE,
-        COLUMN_DEFAULT,
-        CHARACTER_MAXIMUM_LENGTH,
-        ORDINAL_POSITION,
-        CHARACTER_OCTET_LENGTH,
-        CHARACTER_SET_NAME,
-        COLLATION_NAME,
-        COLUMN_COMMENT,
-        TABLE_SCHEMA,
-        TABLE_NAME
-        FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = '{}'",
-        schema
-    );
-    let query = match table {
-        Some(table) => format!(
-            "{} AND TABLE_NAME = '{}' ORDER BY ORDINAL_POSITION;",
-            query, table
-        ),
-        None => format!("{} ORDER BY ORDINAL_POSITION;", query),
+) -> Result<Vec<Column>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION;", COLUMNS);
+            sqlx::query(&query)
+                .bind(schema)
+                .bind(table)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
+        None => {
+            let query = format!("{} ORDER BY TABLE_NAME, ORDINAL_POSITION;", COLUMNS);
+            sqlx::query(&query)
+                .bind(schema)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
     };
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    Ok(rows.iter().map(Column::from_row).collect())
 }
 
 pub async fn get_primary_key(
-    conn: &InitiatedConnection,
     pool: &MySqlPool,
+    schema: &str,
     table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
+) -> Result<Vec<PrimaryKey>> {
     let query = format!(
-        "SELECT TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME, ORDINAL_POSITION,
-                         CONSTRAINT_NAME, REFERENCED_COLUMN_NAME, REFERENCED_TABLE_NAME FROM
-                         INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE CONSTRAINT_NAME = 'PRIMARY' AND TABLE_SCHEMA = '{}'",
-        schema
+        "{} AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION;",
+        PRIMARY_KEYS
     );
-    let query = format!("{} AND TABLE_NAME = '{}'", query, table);
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    let rows = sqlx::query(&query)
+        .bind(schema)
+        .bind(table)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(PrimaryKey::from_row).collect())
 }
 
 pub async fn get_foreign_keys(
-    conn: &InitiatedConnection,
     pool: &MySqlPool,
+    schema: &str,
     table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
+) -> Result<Vec<ForeignKey>> {
     let query = format!(
-        "SELECT rc.constraint_name, kc.column_name, kc.referenced_table_name, kc.referenced_column_name,  rc.update_rule, rc.delete_rule
-            FROM information_schema.referential_constraints rc
-            JOIN information_schema.key_column_usage kc ON rc.constraint_schema = kc.table_schema
-                 AND rc.table_name = kc.table_name
-                 AND rc.constraint_name = kc.constraint_name
-            WHERE rc.constraint_schema = '{}'",
-        schema
+        "{} AND rc.TABLE_NAME = ? ORDER BY rc.CONSTRAINT_NAME, kc.ORDINAL_POSITION;",
+        FOREIGN_KEYS
     );
-    let query = format!("{} AND rc.TABLE_NAME = '{}'", query, table);
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    let rows = sqlx::query(&query)
+        .bind(schema)
+        .bind(table)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(ForeignKey::from_row).collect())
 }
 
-pub async fn get_functions(conn: &InitiatedConnection, pool: &MySqlPool) -> Result<Vec<Value>> {
-    let db = conn
-        .config
-        .credentials
-        .get("db_name")
-        .expect("Failed to get db_name from credentials")
-        .as_str();
-    let query = format!("SHOW FUNCTION STATUS WHERE DB = '{}';", db);
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+async fn get_routines(pool: &MySqlPool, schema: &str, routine_type: &str) -> Result<Vec<Routine>> {
+    let query = format!("{} AND ROUTINE_TYPE = ? ORDER BY ROUTINE_NAME;", ROUTINES);
+    let rows = sqlx::query(&query)
+        .bind(schema)
+        .bind(routine_type)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Routine::from_row).collect())
 }
 
-pub async fn get_procedures(conn: &InitiatedConnection, pool: &MySqlPool) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!("SELECT * FROM information_schema.routines WHERE routine_type = 'PROCEDURE' AND routine_schema = '{}';", schema);
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+pub async fn get_functions(pool: &MySqlPool, schema: &str) -> Result<Vec<Routine>> {
+    get_routines(pool, schema, "FUNCTION").await
 }
 
-pub async fn get_indices(
-    conn: &InitiatedConnection,
-    pool: &MySqlPool,
-    table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
+pub async fn get_procedures(pool: &MySqlPool, schema: &str) -> Result<Vec<Routine>> {
+    get_routines(pool, schema, "PROCEDURE").await
+}
+
+pub async fn get_indices(pool: &MySqlPool, schema: &str, table: &str) -> Result<Vec<Index>> {
     let query = format!(
-        "SELECT INDEX_NAME, COLUMN_NAME, TABLE_SCHEMA, TABLE_NAME FROM
-                        information_schema.statistics WHERE table_schema = '{}'",
-        schema
+        "{} AND TABLE_NAME = ? ORDER BY INDEX_NAME, SEQ_IN_INDEX;",
+        INDICES
     );
-    let query = format!("{} and TABLE_NAME = '{}';", query, table);
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    let rows = sqlx::query(&query)
+        .bind(schema)
+        .bind(table)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(Index::from_rows(&rows))
+}
+
+/// Unique and check constraints, checks have no columns.
+pub async fn get_constraints(
+    pool: &MySqlPool,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<Constraint>> {
+    let query = "SELECT tc.CONSTRAINT_NAME AS constraint_name, tc.TABLE_SCHEMA AS table_schema,
+        tc.TABLE_NAME AS table_name, tc.CONSTRAINT_TYPE AS constraint_type,
+        k.COLUMN_NAME AS column_name, cc.CHECK_CLAUSE AS check_clause
+        FROM information_schema.TABLE_CONSTRAINTS tc
+        LEFT JOIN information_schema.KEY_COLUMN_USAGE k
+            ON k.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA AND k.TABLE_NAME = tc.TABLE_NAME
+            AND k.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+        LEFT JOIN information_schema.CHECK_CONSTRAINTS cc
+            ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+        WHERE tc.CONSTRAINT_TYPE IN ('UNIQUE', 'CHECK') AND tc.TABLE_SCHEMA = ? AND tc.TABLE_NAME = ?
+        ORDER BY tc.CONSTRAINT_NAME, k.ORDINAL_POSITION;";
+    let rows = sqlx::query(query)
+        .bind(schema)
+        .bind(table)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(Constraint::from_rows(&rows))
+}
+
+/// Row count estimate from the table statistics, exact for MyISAM and approximate for InnoDB.
+pub async fn get_row_estimate(pool: &MySqlPool, schema: &str, table: &str) -> Result<Option<f64>> {
+    let query =
+        "SELECT TABLE_ROWS FROM information_schema.TABLES WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?;";
+    let rows = sqlx::query(query)
+        .bind(schema)
+        .bind(table)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.first().and_then(|r| r["TABLE_ROWS"].as_f64()))
 }
 
 pub async fn get_triggers(
-    conn: &InitiatedConnection,
     pool: &MySqlPool,
+    schema: &str,
     table: Option<&str>,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT trigger_name, event_manipulation, action_timing, action_statement, created,
-            action_condition FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = '{}'",
-        schema
-    );
-    let query = match table {
-        Some(table) => format!("{} AND EVENT_OBJECT_TABLE = '{}';", query, table),
-        None => format!("{};", query),
+) -> Result<Vec<Trigger>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND EVENT_OBJECT_TABLE = ?;", TRIGGERS);
+            sqlx::query(&query)
+                .bind(schema)
+                .bind(table)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
+        None => {
+            let query = format!("{};", TRIGGERS);
+            sqlx::query(&query)
+                .bind(schema)
+                .map(row_to_json)
+                .fetch_all(pool)
+                .await?
+        }
     };
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    Ok(rows.iter().map(Trigger::from_row).collect())
 }
 
 pub async fn get_schemas(pool: &sqlx::mysql::MySqlPool) -> Result<Vec<Value>> {
@@ -157,13 +245,110 @@ pub async fn get_schemas(pool: &sqlx::mysql::MySqlPool) -> Result<Vec<Value>> {
     Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
 }
 
-pub async fn get_views(conn: &InitiatedConnection, pool: &MySqlPool) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT TABLE_SCHEMA, TABLE_NAME 
-            FROM information_schema.tables 
-            WHERE TABLE_TYPE LIKE 'VIEW' and table_schema = '{}';",
-        schema
+pub async fn get_views(pool: &MySqlPool, schema: &str) -> Result<Vec<View>> {
+    let rows = sqlx::query(VIEWS)
+        .bind(schema)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(View::from_row).collect())
+}
+
+/// Runs `query` filtered by schema and the tables of a snapshot page.
+async fn fetch_for_tables(
+    pool: &MySqlPool,
+    query: &str,
+    schema: &str,
+    tables: &[String],
+) -> Result<Vec<Value>> {
+    let mut query = sqlx::query(query).bind(schema);
+    for table in tables {
+        query = query.bind(table);
+    }
+    Ok(query.map(row_to_json).fetch_all(pool).await?)
+}
+
+/// One page of the catalog of `schema`, the tables after `after` by name
+/// and everything defined on them. Routines come with the first page only.
+pub async fn get_schema_snapshot(
+    pool: &MySqlPool,
+    schema: &str,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<SchemaSnapshot> {
+    // MariaDB lists its sequences as tables too
+    let query = "SELECT TABLE_SCHEMA AS table_schema, TABLE_NAME AS table_name,
+        IF(TABLE_TYPE = 'VIEW', 'View', 'Table') AS table_type, TABLE_COMMENT AS comment,
+        ENGINE AS engine, ROW_FORMAT AS row_format,
+        TABLE_TYPE = 'SYSTEM VERSIONED' AS system_versioned
+        FROM information_schema.TABLES WHERE TABLE_SCHEMA = ? AND TABLE_NAME > ?
+        AND TABLE_TYPE <> 'SEQUENCE'
+        ORDER BY TABLE_NAME LIMIT ?;";
+    let rows = sqlx::query(query)
+        .bind(schema)
+        .bind(after.unwrap_or_default())
+        .bind(limit as u64)
+        .map(row_to_json)
+        .fetch_all(pool)
+        .await?;
+    let tables: Vec<Table> = rows.iter().map(Table::from_row).collect();
+    let names: Vec<String> = tables.iter().map(|t| t.table_name.clone()).collect();
+
+    // `IN ()` is a syntax error, `IN (NULL)` matches nothing
+    let list = match names.len() {
+        0 => "NULL".to_string(),
+        n => vec!["?"; n].join(", "),
+    };
+    let columns = format!(
+        "{} AND TABLE_NAME IN ({}) ORDER BY TABLE_NAME, ORDINAL_POSITION;",
+        COLUMNS, list
     );
-    Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
+    let primary_keys = format!(
+        "{} AND TABLE_NAME IN ({}) ORDER BY TABLE_NAME, ORDINAL_POSITION;",
+        PRIMARY_KEYS, list
+    );
+    let foreign_keys = format!(
+        "{} AND rc.TABLE_NAME IN ({}) ORDER BY rc.TABLE_NAME, rc.CONSTRAINT_NAME, kc.ORDINAL_POSITION;",
+        FOREIGN_KEYS, list
+    );
+    let indices = format!(
+        "{} AND TABLE_NAME IN ({}) ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX;",
+        INDICES, list
+    );
+    let triggers = format!("{} AND EVENT_OBJECT_TABLE IN ({});", TRIGGERS, list);
+    let views = format!("{} AND TABLE_NAME IN ({});", VIEWS, list);
+    let routines = format!(
+        "{} AND ROUTINE_TYPE IN ('FUNCTION', 'PROCEDURE') ORDER BY ROUTINE_NAME;",
+        ROUTINES
+    );
+    let routines = async {
+        match after {
+            None => fetch_for_tables(pool, &routines, schema, &[]).await,
+            Some(_) => Ok(vec![]),
+        }
+    };
+    let (columns, primary_keys, foreign_keys, indices, triggers, views, routines) = try_join!(
+        fetch_for_tables(pool, &columns, schema, &names),
+        fetch_for_tables(pool, &primary_keys, schema, &names),
+        fetch_for_tables(pool, &foreign_keys, schema, &names),
+        fetch_for_tables(pool, &indices, schema, &names),
+        fetch_for_tables(pool, &triggers, schema, &names),
+        fetch_for_tables(pool, &views, schema, &names),
+        routines,
+    )?;
+
+    Ok(SchemaSnapshot {
+        schema: schema.to_string(),
+        next: (tables.len() == limit)
+            .then(|| names.last().cloned())
+            .flatten(),
+        tables,
+        columns: columns.iter().map(Column::from_row).collect(),
+        primary_keys: primary_keys.iter().map(PrimaryKey::from_row).collect(),
+        foreign_keys: foreign_keys.iter().map(ForeignKey::from_row).collect(),
+        indices: Index::from_rows(&indices),
+        triggers: triggers.iter().map(Trigger::from_row).collect(),
+        views: views.iter().map(View::from_row).collect(),
+        routines: routines.iter().map(Routine::from_row).collect(),
+    })
 }
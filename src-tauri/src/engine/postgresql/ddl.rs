@@ -0,0 +1,286 @@
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Pool;
+use postgres::types::ToSql;
+use serde_json::Value;
+
+use crate::engine::{
+    quote::quote_literal,
+    types::{catalog::ObjectKind, config::Dialect},
+};
+
+use super::query::raw_query;
+
+// Postgresql has no `SHOW CREATE`, tables are rebuilt from the catalog and
+// everything else comes from the server's own `pg_get_*def` functions.
+// Every query takes the schema and object name as $1 and $2.
+
+const RELATION: &str = "to_regclass(format('%I.%I', $1::text, $2::text))";
+
+pub async fn get_object_ddl(
+    pool: &Pool,
+    schema: &str,
+    object: &str,
+    kind: ObjectKind,
+) -> Result<String> {
+    let definitions = match kind {
+        ObjectKind::Table => return get_table_ddl(pool, schema, object).await,
+        ObjectKind::View | ObjectKind::MaterializedView => {
+            let create = match kind {
+                ObjectKind::View => "CREATE OR REPLACE VIEW",
+                _ => "CREATE MATERIALIZED VIEW",
+            };
+            let query = format!(
+                "SELECT '{} ' || format('%I.%I', n.nspname, c.relname) || E' AS\\n'
+                || pg_get_viewdef(c.oid, true) AS definition
+                FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.oid = {} AND c.relkind::text = $3;",
+                create, RELATION
+            );
+            let relkind = match kind {
+                ObjectKind::View => "v",
+                _ => "m",
+            };
+            definitions(pool, &query, &[schema, object, relkind]).await?
+        }
+        ObjectKind::Function | ObjectKind::Procedure => {
+            // every overload of the name
+            let query = "SELECT pg_get_functiondef(p.oid) AS definition
+                FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace
+                WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind::text = $3
+                ORDER BY pg_get_function_identity_arguments(p.oid);";
+            let prokind = match kind {
+                ObjectKind::Function => "f",
+                _ => "p",
+            };
+            definitions(pool, query, &[schema, object, prokind])
+                .await?
+                .into_iter()
+                .map(|d| format!("{};", d.trim_end()))
+                .collect()
+        }
+        ObjectKind::Trigger => {
+            let query = "SELECT pg_get_triggerdef(t.oid, true) || ';' AS definition
+                FROM pg_trigger t
+                JOIN pg_class c ON c.oid = t.tgrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND t.tgname = $2 AND NOT t.tgisinternal
+                ORDER BY c.relname;";
+            definitions(pool, query, &[schema, object]).await?
+        }
+        ObjectKind::Index => {
+            let query = format!(
+                "SELECT pg_get_indexdef(i.indexrelid) || ';' AS definition
+                FROM pg_index i WHERE i.indexrelid = {};",
+                RELATION
+            );
+            definitions(pool, &query, &[schema, object]).await?
+        }
+        ObjectKind::Sequence => {
+            let query = "SELECT format('CREATE SEQUENCE %I.%I AS %s', schemaname, sequencename,
+                    data_type) || E'\\n    INCREMENT BY ' || increment_by
+                || E'\\n    MINVALUE ' || min_value || E'\\n    MAXVALUE ' || max_value
+                || E'\\n    START WITH ' || start_value || E'\\n    CACHE ' || cache_size
+                || CASE WHEN cycle THEN E'\\n    CYCLE' ELSE E'\\n    NO CYCLE' END || ';'
+                AS definition
+                FROM pg_sequences WHERE schemaname = $1 AND sequencename = $2;";
+            definitions(pool, query, &[schema, object]).await?
+        }
+        ObjectKind::Event => {
+            return Err(anyhow!("Events are not available for Postgresql"));
+        }
+    };
+    match definitions.is_empty() {
+        true => Err(anyhow!("{:?} {}.{} was not found", kind, schema, object)),
+        false => Ok(definitions.join("\n\n")),
+    }
+}
+
+async fn definitions(pool: &Pool, query: &str, params: &[&str]) -> Result<Vec<String>> {
+    let params: Vec<&(dyn ToSql + Sync)> =
+        params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+    let rows = raw_query(pool.clone(), query, &params).await?;
+    Ok(rows
+        .iter()
+        .filter_map(|r| r["definition"].as_str().map(String::from))
+        .collect())
+}
+
+/// `CREATE TABLE` with columns and constraints, followed by the indices and
+/// triggers not backing a constraint, the comments and the owner.
+async fn get_table_ddl(pool: &Pool, schema: &str, table: &str) -> Result<String> {
+    let params: &[&(dyn ToSql + Sync)] = &[&schema, &table];
+    let query = format!(
+        "SELECT format('%I.%I', n.nspname, c.relname) AS name,
+        c.relpersistence = 'u' AS unlogged, quote_ident(pg_get_userbyid(c.relowner)) AS owner,
+        obj_description(c.oid, 'pg_class') AS comment,
+        CASE WHEN c.relkind = 'p' THEN pg_get_partkeydef(c.oid) END AS partition_key,
+        CASE WHEN c.relispartition THEN pg_get_expr(c.relpartbound, c.oid) END
+            AS partition_bound,
+        (SELECT string_agg(i.inhparent::regclass::text, ', ' ORDER BY i.inhseqno)
+            FROM pg_inherits i WHERE i.inhrelid = c.oid) AS parents
+        FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.oid = {} AND c.relkind IN ('r', 'p');",
+        RELATION
+    );
+    let rows = raw_query(pool.clone(), &query, params).await?;
+    let table_row = rows
+        .first()
+        .ok_or_else(|| anyhow!("Table {}.{} was not found", schema, table))?;
+    // inherited columns and constraints are created by the parent
+    let columns = format!(
+        "SELECT quote_ident(a.attname) AS column_name,
+        format_type(a.atttypid, a.atttypmod) AS data_type, a.attnotnull AS not_null,
+        pg_get_expr(d.adbin, d.adrelid) AS column_default, a.attidentity::text AS identity,
+        a.attgenerated::text AS generated,
+        CASE WHEN a.attcollation <> t.typcollation THEN quote_ident(co.collname) END
+            AS collation_name,
+        col_description(a.attrelid, a.attnum) AS comment
+        FROM pg_attribute a
+        JOIN pg_type t ON t.oid = a.atttypid
+        LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+        LEFT JOIN pg_collation co ON co.oid = a.attcollation
+        WHERE a.attrelid = {} AND a.attnum > 0 AND NOT a.attisdropped AND a.attislocal
+        ORDER BY a.attnum;",
+        RELATION
+    );
+    let constraints = format!(
+        "SELECT quote_ident(conname) AS constraint_name,
+        pg_get_constraintdef(oid, true) AS definition
+        FROM pg_constraint
+        WHERE conrelid = {} AND conislocal AND contype IN ('p', 'u', 'c', 'f', 'x')
+        ORDER BY array_position(ARRAY['p', 'u', 'c', 'f', 'x'], contype::text), conname;",
+        RELATION
+    );
+    // partition indices are created with the index of the partitioned table
+    let indices = format!(
+        "SELECT pg_get_indexdef(i.indexrelid) || ';' AS definition
+        FROM pg_index i
+        WHERE i.indrelid = {} AND NOT EXISTS (SELECT 1 FROM pg_constraint c
+            WHERE c.conindid = i.indexrelid AND c.conrelid = i.indrelid
+            AND c.contype IN ('p', 'u', 'x'))
+        AND NOT EXISTS (SELECT 1 FROM pg_inherits h WHERE h.inhrelid = i.indexrelid)
+        ORDER BY i.indexrelid::regclass::text;",
+        RELATION
+    );
+    let triggers = format!(
+        "SELECT pg_get_triggerdef(t.oid, true) || ';' AS definition
+        FROM pg_trigger t WHERE t.tgrelid = {} AND NOT t.tgisinternal ORDER BY t.tgname;",
+        RELATION
+    );
+    let names: &[&str] = &[schema, table];
+    let (columns, constraints, indices, triggers) = futures::try_join!(
+        raw_query(pool.clone(), &columns, params),
+        raw_query(pool.clone(), &constraints, params),
+        definitions(pool, &indices, names),
+        definitions(pool, &triggers, names),
+    )?;
+
+    let text = |row: &Value, key: &str| row[key].as_str().map(String::from);
+    let name = text(table_row, "name").unwrap_or_default();
+    let mut statements = vec![create_table(table_row, &name, &columns, &constraints)];
+    statements.extend(indices);
+    statements.extend(triggers);
+    let literal = |c: &str| quote_literal(&Dialect::Postgresql, c);
+    if let Some(comment) = text(table_row, "comment") {
+        statements.push(format!(
+            "COMMENT ON TABLE {} IS {};",
+            name,
+            literal(&comment)
+        ));
+    }
+    for column in &columns {
+        if let (Some(column_name), Some(comment)) =
+            (text(column, "column_name"), text(column, "comment"))
+        {
+            statements.push(format!(
+                "COMMENT ON COLUMN {}.{} IS {};",
+                name,
+                column_name,
+                literal(&comment)
+            ));
+        }
+    }
+    if let Some(owner) = text(table_row, "owner") {
+        statements.push(format!("ALTER TABLE {} OWNER TO {};", name, owner));
+    }
+    Ok(statements.join("\n\n"))
+}
+
+fn create_table(table: &Value, name: &str, columns: &[Value], constraints: &[Value]) -> String {
+    let text = |row: &Value, key: &str| row[key].as_str().map(String::from);
+    let mut lines: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            let mut line = format!(
+                "{} {}",
+                text(c, "column_name").unwrap_or_default(),
+                text(c, "data_type").unwrap_or_default()
+            );
+            if let Some(collation) = text(c, "collation_name") {
+                line.push_str(&format!(" COLLATE {}", collation));
+            }
+            let default = text(c, "column_default");
+            match (
+                text(c, "identity").as_deref(),
+                text(c, "generated").as_deref(),
+            ) {
+                (Some("a"), _) => line.push_str(" GENERATED ALWAYS AS IDENTITY"),
+                (Some("d"), _) => line.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+                (_, Some("s")) => line.push_str(&format!(
+                    " GENERATED ALWAYS AS ({}) STORED",
+                    default.clone().unwrap_or_default()
+                )),
+                _ => {
+                    if let Some(default) = &default {
+                        line.push_str(&format!(" DEFAULT {}", default));
+                    }
+                }
+            }
+            if c["not_null"].as_bool().unwrap_or_default() {
+                line.push_str(" NOT NULL");
+            }
+            line
+        })
+        .collect();
+    lines.extend(constraints.iter().map(|c| {
+        format!(
+            "CONSTRAINT {} {}",
+            text(c, "constraint_name").unwrap_or_default(),
+            text(c, "definition").unwrap_or_default()
+        )
+    }));
+    let unlogged = match table["unlogged"].as_bool().unwrap_or_default() {
+        true => "UNLOGGED ",
+        false => "",
+    };
+    let mut create = match text(table, "partition_bound") {
+        Some(bound) => {
+            let parent = text(table, "parents").unwrap_or_default();
+            let body = match lines.is_empty() {
+                true => String::new(),
+                false => format!(" (\n    {}\n)", lines.join(",\n    ")),
+            };
+            format!(
+                "CREATE {}TABLE {} PARTITION OF {}{}\n{}",
+                unlogged, name, parent, body, bound
+            )
+        }
+        None => {
+            let mut create = format!(
+                "CREATE {}TABLE {} (\n    {}\n)",
+                unlogged,
+                name,
+                lines.join(",\n    ")
+            );
+            if let Some(parents) = text(table, "parents") {
+                create.push_str(&format!("\nINHERITS ({})", parents));
+            }
+            create
+        }
+    };
+    if let Some(key) = text(table, "partition_key") {
+        create.push_str(&format!("\nPARTITION BY {}", key));
+    }
+    create.push(';');
+    create
+}
@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Pool;
+use serde_json::Value;
+
+use crate::engine::types::plan::{Buffers, NodeKind, PlanNode, QueryPlan};
+
+pub async fn explain_query(pool: &Pool, sql: &str, analyze: bool) -> Result<QueryPlan> {
+    let options = match analyze {
+        true => "FORMAT JSON, ANALYZE, BUFFERS",
+        false => "FORMAT JSON",
+    };
+    let query = format!("EXPLAIN ({}) {}", options, sql);
+    let mut conn = pool.get().await?;
+    // ANALYZE executes the statement, the transaction makes sure explaining a write has no effect
+    let tx = conn.transaction().await?;
+    let row = tx.query_one(&query, &[]).await;
+    tx.rollback().await?;
+    let raw: Value = row?.try_get(0)?;
+    parse_plan(raw, analyze)
+}
+
+fn parse_plan(raw: Value, analyzed: bool) -> Result<QueryPlan> {
+    let plan = raw
+        .get(0)
+        .ok_or(anyhow!("Postgresql returned an empty plan"))?;
+    let root = parse_node(&plan["Plan"]);
+    let planning_time_ms = plan["Planning Time"].as_f64();
+    let execution_time_ms = plan["Execution Time"].as_f64();
+    Ok(QueryPlan {
+        analyzed,
+        root,
+        planning_time_ms,
+        execution_time_ms,
+        raw,
+    })
+}
+
+fn parse_node(node: &Value) -> PlanNode {
+    let operation = node["Node Type"].as_str().unwrap_or_default().to_string();
+    let condition = [
+        "Index Cond",
+        "Recheck Cond",
+        "Hash Cond",
+        "Merge Cond",
+        "Join Filter",
+        "Filter",
+    ]
+    .iter()
+    .find_map(|key| node[key].as_str())
+    .map(String::from);
+    let buffers = node.get("Shared Hit Blocks").map(|_| Buffers {
+        shared_hit: node["Shared Hit Blocks"].as_u64().unwrap_or(0),
+        shared_read: node["Shared Read Blocks"].as_u64().unwrap_or(0),
+        shared_dirtied: node["Shared Dirtied Blocks"].as_u64().unwrap_or(0),
+        shared_written: node["Shared Written Blocks"].as_u64().unwrap_or(0),
+        temp_read: node["Temp Read Blocks"].as_u64().unwrap_or(0),
+        temp_written: node["Temp Written Blocks"].as_u64().unwrap_or(0),
+    });
+    PlanNode {
+        kind: node_kind(&operation),
+        relation: node["Relation Name"].as_str().map(String::from),
+        index: node["Index Name"].as_str().map(String::from),
+        condition,
+        estimated_rows: node["Plan Rows"].as_f64(),
+        actual_rows: node["Actual Rows"].as_f64(),
+        loops: node["Actual Loops"].as_f64(),
+        cost: node["Total Cost"].as_f64(),
+        actual_time_ms: node["Actual Total Time"].as_f64(),
+        buffers,
+        children: node["Plans"]
+            .as_array()
+            .map(|plans| plans.iter().map(parse_node).collect())
+            .unwrap_or_default(),
+        operation,
+    }
+}
+
+fn node_kind(operation: &str) -> NodeKind {
+    match operation {
+        "Seq Scan" => NodeKind::FullScan,
+        "Index Scan" | "Bitmap Index Scan" | "Bitmap Heap Scan" => NodeKind::IndexScan,
+        "Index Only Scan" => NodeKind::IndexOnlyScan,
+        "Nested Loop" | "Hash Join" | "Merge Join" => NodeKind::Join,
+        "Sort" | "Incremental Sort" => NodeKind::Sort,
+        "Aggregate" | "Group" | "Unique" | "WindowAgg" => NodeKind::Aggregate,
+        "Materialize" | "CTE Scan" => NodeKind::TempTable,
+        "Subquery Scan" => NodeKind::Subquery,
+        _ => NodeKind::Other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{node_kind, parse_plan};
+    use crate::engine::types::plan::NodeKind;
+
+    #[test]
+    fn test_parse_plan() {
+        let raw = json!([{"Plan": {
+            "Node Type": "Nested Loop",
+            "Join Type": "Inner",
+            "Total Cost": 16.55,
+            "Plan Rows": 5,
+            "Plans": [
+                {
+                    "Node Type": "Seq Scan",
+                    "Relation Name": "users",
+                    "Total Cost": 1.62,
+                    "Plan Rows": 8,
+                    "Filter": "(age > 30)"
+                },
+                {
+                    "Node Type": "Index Scan",
+                    "Relation Name": "orders",
+                    "Index Name": "orders_user_id_idx",
+                    "Total Cost": 8.17,
+                    "Plan Rows": 1,
+                    "Index Cond": "(user_id = users.id)"
+                }
+            ]
+        }}]);
+        let plan = parse_plan(raw, false).unwrap();
+        assert!(!plan.analyzed);
+        assert_eq!(plan.planning_time_ms, None);
+        assert_eq!(plan.root.kind, NodeKind::Join);
+        assert_eq!(plan.root.cost, Some(16.55));
+        assert_eq!(plan.root.actual_rows, None);
+        assert_eq!(plan.root.buffers, None);
+        let scan = &plan.root.children[0];
+        assert_eq!(scan.kind, NodeKind::FullScan);
+        assert_eq!(scan.relation.as_deref(), Some("users"));
+        assert_eq!(scan.condition.as_deref(), Some("(age > 30)"));
+        let lookup = &plan.root.children[1];
+        assert_eq!(lookup.kind, NodeKind::IndexScan);
+        assert_eq!(lookup.index.as_deref(), Some("orders_user_id_idx"));
+        assert_eq!(lookup.condition.as_deref(), Some("(user_id = users.id)"));
+        assert!(parse_plan(json!([]), false).is_err());
+        // the build side of a hash join isn't a materialized result
+        assert_eq!(node_kind("Hash"), NodeKind::Other);
+    }
+
+    #[test]
+    fn test_parse_analyzed_plan() {
+        let raw = json!([{
+            "Plan": {
+                "Node Type": "Sort",
+                "Total Cost": 1.77,
+                "Plan Rows": 8,
+                "Actual Total Time": 0.015,
+                "Actual Rows": 9,
+                "Actual Loops": 1,
+                "Sort Key": ["name"],
+                "Shared Hit Blocks": 3,
+                "Shared Read Blocks": 1,
+                "Shared Dirtied Blocks": 0,
+                "Shared Written Blocks": 0,
+                "Temp Read Blocks": 0,
+                "Temp Written Blocks": 0,
+                "Plans": [{
+                    "Node Type": "Seq Scan",
+                    "Relation Name": "users",
+                    "Total Cost": 1.62,
+                    "Plan Rows": 8,
+                    "Actual Total Time": 0.006,
+                    "Actual Rows": 9,
+                    "Actual Loops": 1,
+                    "Filter": "(id < 10)",
+                    "Rows Removed by Filter": 41,
+                    "Shared Hit Blocks": 3,
+                    "Shared Read Blocks": 1,
+                    "Shared Dirtied Blocks": 0,
+                    "Shared Written Blocks": 0,
+                    "Temp Read Blocks": 0,
+                    "Temp Written Blocks": 0
+                }]
+            },
+            "Planning": {"Shared Hit Blocks": 21},
+            "Planning Time": 0.076,
+            "Triggers": [],
+            "Execution Time": 0.024
+        }]);
+        let plan = parse_plan(raw, true).unwrap();
+        assert!(plan.analyzed);
+        assert_eq!(plan.planning_time_ms, Some(0.076));
+        assert_eq!(plan.execution_time_ms, Some(0.024));
+        assert_eq!(plan.root.kind, NodeKind::Sort);
+        assert_eq!(plan.root.actual_time_ms, Some(0.015));
+        let buffers = plan.root.buffers.as_ref().unwrap();
+        assert_eq!(buffers.shared_hit, 3);
+        assert_eq!(buffers.shared_read, 1);
+        let scan = &plan.root.children[0];
+        assert_eq!(scan.kind, NodeKind::FullScan);
+        assert_eq!(scan.estimated_rows, Some(8.0));
+        assert_eq!(scan.actual_rows, Some(9.0));
+        assert_eq!(scan.loops, Some(1.0));
+    }
+}
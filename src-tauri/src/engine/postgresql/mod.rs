@@ -1,3 +1,7 @@
+pub mod ddl;
+pub mod explain;
+pub mod monitor;
+pub mod objects;
 pub mod query;
 pub mod tables;
 pub mod utils;
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Pool;
+
+use crate::engine::types::monitor::{LockWait, Session, TopQuery, TopQueryOrder};
+
+use super::query::raw_query;
+
+pub async fn get_top_queries(
+    pool: &Pool,
+    order: TopQueryOrder,
+    limit: usize,
+) -> Result<Vec<TopQuery>> {
+    let installed = raw_query(
+        pool.clone(),
+        "SELECT 1 FROM pg_extension WHERE extname = 'pg_stat_statements';",
+        &[],
+    )
+    .await?;
+    if installed.is_empty() {
+        return Err(anyhow!(
+            "The pg_stat_statements extension is not installed in this database. Add it to shared_preload_libraries and run CREATE EXTENSION pg_stat_statements;"
+        ));
+    }
+    let version = raw_query(
+        pool.clone(),
+        "SELECT current_setting('server_version_num')::int AS version;",
+        &[],
+    )
+    .await?;
+    // the timing columns were renamed in Postgresql 13
+    let (total, mean) = match version.first().and_then(|r| r["version"].as_i64()) {
+        Some(version) if version >= 130000 => ("total_exec_time", "mean_exec_time"),
+        _ => ("total_time", "mean_time"),
+    };
+    let query = format!(
+        "SELECT s.queryid::text AS query_id, s.query, d.datname AS database, s.calls,
+            s.{}::float8 AS total_time_ms, s.{}::float8 AS mean_time_ms, s.rows AS row_count
+            FROM pg_stat_statements s LEFT JOIN pg_database d ON d.oid = s.dbid
+            ORDER BY {} DESC LIMIT {};",
+        total,
+        mean,
+        order.column(),
+        limit
+    );
+    match raw_query(pool.clone(), &query, &[]).await {
+        Ok(rows) => Ok(rows.iter().map(TopQuery::from_row).collect()),
+        Err(e) if e.chain().any(|c| c.to_string().contains("shared_preload_libraries")) => {
+            Err(anyhow!(
+                "pg_stat_statements is installed but not loaded. Add it to shared_preload_libraries and restart the server."
+            ))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn get_sessions(pool: &Pool) -> Result<Vec<Session>> {
+    let query = "SELECT pid AS id, usename AS user_name, datname AS database, host(client_addr) AS client,
+        application_name AS application, state,
+        (EXTRACT(EPOCH FROM now() - CASE WHEN state = 'active' THEN query_start ELSE state_change END) * 1000)::float8 AS duration_ms,
+        CASE WHEN wait_event IS NOT NULL THEN wait_event_type || ': ' || wait_event END AS wait_event,
+        query
+        FROM pg_stat_activity WHERE backend_type = 'client backend' AND pid <> pg_backend_pid()
+        ORDER BY duration_ms DESC NULLS LAST;";
+    let rows = raw_query(pool.clone(), query, &[]).await?;
+    Ok(rows.iter().map(Session::from_row).collect())
+}
+
+async fn signal_backend(pool: &Pool, function: &str, id: i64) -> Result<()> {
+    let query = format!("SELECT {}($1) AS signaled;", function);
+    let pid = id as i32;
+    let rows = raw_query(pool.clone(), &query, &[&pid]).await?;
+    match rows.first().and_then(|r| r["signaled"].as_bool()) {
+        Some(true) => Ok(()),
+        _ => Err(anyhow!(
+            "Session {} was not signaled, it may have already ended",
+            id
+        )),
+    }
+}
+
+pub async fn cancel_session(pool: &Pool, id: i64) -> Result<()> {
+    signal_backend(pool, "pg_cancel_backend", id).await
+}
+
+pub async fn terminate_session(pool: &Pool, id: i64) -> Result<()> {
+    signal_backend(pool, "pg_terminate_backend", id).await
+}
+
+/// A backend waits for one lock at a time, the wait is measured from the start
+/// of its current statement since `pg_locks.waitstart` only exists from 14 on.
+pub async fn get_lock_waits(pool: &Pool) -> Result<Vec<LockWait>> {
+    let query = "SELECT w.pid AS waiting, w.blocking,
+        COALESCE(l.relation::regclass::text, l.locktype) AS relation, l.mode AS lock_mode,
+        (EXTRACT(EPOCH FROM now() - a.query_start) * 1000)::float8 AS wait_ms
+        FROM (SELECT pid, unnest(pg_blocking_pids(pid)) AS blocking FROM pg_stat_activity
+            WHERE wait_event_type = 'Lock') w
+        JOIN pg_stat_activity a ON a.pid = w.pid
+        LEFT JOIN pg_locks l ON l.pid = w.pid AND NOT l.granted;";
+    let rows = raw_query(pool.clone(), query, &[]).await?;
+    Ok(rows.iter().map(LockWait::from_row).collect())
+}
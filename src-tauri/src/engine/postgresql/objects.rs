@@ -0,0 +1,144 @@
+use anyhow::Result;
+use deadpool_postgres::Pool;
+
+use crate::engine::{
+    quote::quote_qualified,
+    types::{
+        catalog::{
+            CompositeType, Domain, EnumType, Extension, Inheritance, MaterializedView, Sequence,
+        },
+        config::Dialect,
+    },
+};
+
+use super::query::raw_query;
+
+// Postgres objects without a counterpart in the other dialects.
+
+pub async fn get_sequences(pool: &Pool, schema: &str) -> Result<Vec<Sequence>> {
+    let query = "SELECT s.schemaname AS sequence_schema, s.sequencename AS sequence_name,
+        s.data_type::text AS data_type, s.start_value::text AS start_value,
+        s.min_value::text AS min_value, s.max_value::text AS max_value,
+        s.increment_by::text AS increment, s.cycle, s.last_value::text AS last_value,
+        (SELECT d.refobjid::regclass::text || '.' || quote_ident(a.attname)
+            FROM pg_depend d
+            JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+            WHERE d.classid = 'pg_class'::regclass AND d.deptype IN ('a', 'i')
+            AND d.objid = format('%I.%I', s.schemaname, s.sequencename)::regclass) AS owned_by
+        FROM pg_sequences s WHERE s.schemaname = $1 ORDER BY s.sequencename;";
+    let rows = raw_query(pool.clone(), query, &[&schema]).await?;
+    Ok(rows.iter().map(Sequence::from_row).collect())
+}
+
+pub async fn get_enums(pool: &Pool, schema: &str) -> Result<Vec<EnumType>> {
+    let query = "SELECT n.nspname AS type_schema, t.typname AS type_name,
+        array_agg(e.enumlabel::text ORDER BY e.enumsortorder) AS labels
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        JOIN pg_enum e ON e.enumtypid = t.oid
+        WHERE n.nspname = $1 GROUP BY n.nspname, t.typname ORDER BY t.typname;";
+    let rows = raw_query(pool.clone(), query, &[&schema]).await?;
+    Ok(rows.iter().map(EnumType::from_row).collect())
+}
+
+pub async fn get_domains(pool: &Pool, schema: &str) -> Result<Vec<Domain>> {
+    let query = "SELECT n.nspname AS domain_schema, t.typname AS domain_name,
+        format_type(t.typbasetype, t.typtypmod) AS data_type, NOT t.typnotnull AS is_nullable,
+        t.typdefault AS column_default,
+        coalesce(array_agg(pg_get_constraintdef(c.oid) ORDER BY c.conname)
+            FILTER (WHERE c.oid IS NOT NULL), '{}') AS check_constraints
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        LEFT JOIN pg_constraint c ON c.contypid = t.oid
+        WHERE t.typtype = 'd' AND n.nspname = $1
+        GROUP BY n.nspname, t.typname, t.typbasetype, t.typtypmod, t.typnotnull, t.typdefault
+        ORDER BY t.typname;";
+    let rows = raw_query(pool.clone(), query, &[&schema]).await?;
+    Ok(rows.iter().map(Domain::from_row).collect())
+}
+
+pub async fn get_composite_types(pool: &Pool, schema: &str) -> Result<Vec<CompositeType>> {
+    let query = "SELECT n.nspname AS type_schema, t.typname AS type_name,
+        json_agg(json_build_object('attribute_name', a.attname,
+            'data_type', format_type(a.atttypid, a.atttypmod)) ORDER BY a.attnum) AS attributes
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        JOIN pg_class c ON c.oid = t.typrelid AND c.relkind = 'c'
+        JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+        WHERE n.nspname = $1 GROUP BY n.nspname, t.typname ORDER BY t.typname;";
+    let rows = raw_query(pool.clone(), query, &[&schema]).await?;
+    Ok(rows.iter().map(CompositeType::from_row).collect())
+}
+
+/// Every extension available on the server, installed ones carry their version.
+pub async fn get_extensions(pool: &Pool) -> Result<Vec<Extension>> {
+    let query = "SELECT a.name AS extension_name, a.default_version, a.installed_version,
+        n.nspname AS extension_schema, a.comment
+        FROM pg_available_extensions a
+        LEFT JOIN pg_extension e ON e.extname = a.name
+        LEFT JOIN pg_namespace n ON n.oid = e.extnamespace
+        ORDER BY a.name;";
+    let rows = raw_query(pool.clone(), query, &[]).await?;
+    Ok(rows.iter().map(Extension::from_row).collect())
+}
+
+pub async fn get_materialized_views(pool: &Pool, schema: &str) -> Result<Vec<MaterializedView>> {
+    let query = "SELECT m.schemaname AS table_schema, m.matviewname AS table_name,
+        m.definition AS view_definition, m.ispopulated AS is_populated,
+        EXISTS (SELECT 1 FROM pg_index i
+            WHERE i.indrelid = format('%I.%I', m.schemaname, m.matviewname)::regclass
+            AND i.indisunique AND i.indpred IS NULL AND i.indexprs IS NULL) AS has_unique_index
+        FROM pg_matviews m WHERE m.schemaname = $1 ORDER BY m.matviewname;";
+    let rows = raw_query(pool.clone(), query, &[&schema]).await?;
+    Ok(rows.iter().map(MaterializedView::from_row).collect())
+}
+
+/// `CONCURRENTLY` keeps the view readable during the refresh but needs a
+/// unique index on it.
+pub async fn refresh_materialized_view(
+    pool: &Pool,
+    schema: &str,
+    name: &str,
+    concurrently: bool,
+) -> Result<()> {
+    let query = format!(
+        "REFRESH MATERIALIZED VIEW {}{};",
+        if concurrently { "CONCURRENTLY " } else { "" },
+        quote_qualified(&Dialect::Postgresql, &[schema, name])
+    );
+    pool.get().await?.batch_execute(&query).await?;
+    Ok(())
+}
+
+/// Inheritance and partitions where parent or child is in `schema`,
+/// optionally only those involving `table`.
+pub async fn get_inheritance(
+    pool: &Pool,
+    schema: &str,
+    table: Option<&str>,
+) -> Result<Vec<Inheritance>> {
+    let query = "SELECT pn.nspname AS parent_schema, p.relname AS parent_name,
+        cn.nspname AS child_schema, c.relname AS child_name,
+        pg_get_expr(c.relpartbound, c.oid) AS partition_bound,
+        CASE WHEN p.relkind = 'p' THEN pg_get_partkeydef(p.oid) END AS partition_key
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_namespace cn ON cn.oid = c.relnamespace
+        JOIN pg_class p ON p.oid = i.inhparent
+        JOIN pg_namespace pn ON pn.oid = p.relnamespace
+        WHERE p.relkind IN ('r', 'p') AND (pn.nspname = $1 OR cn.nspname = $1)";
+    let rows = match table {
+        Some(table) => {
+            let query = format!(
+                "{} AND (p.relname = $2 OR c.relname = $2) ORDER BY p.relname, c.relname;",
+                query
+            );
+            raw_query(pool.clone(), &query, &[&schema, &table]).await?
+        }
+        None => {
+            let query = format!("{} ORDER BY p.relname, c.relname;", query);
+            raw_query(pool.clone(), &query, &[&schema]).await?
+        }
+    };
+    Ok(rows.iter().map(Inheritance::from_row).collect())
+}
@@ -1,15 +1,28 @@
-use crate::engine::types::result::{ResultSet, TableMetadata};
+use crate::engine::quote::quote_qualified;
+use crate::engine::types::config::Dialect;
+use crate::engine::types::edit::BoundStatement;
+use crate::engine::types::result::{
+    ColumnOrigin, ResultSet, StatementError, StatementResult, TableMetadata, TxResult,
+};
 use anyhow::Result;
-use deadpool_postgres::Pool;
+use deadpool_postgres::{Client, Pool};
 use futures::{pin_mut, TryStreamExt};
+use postgres::{
+    types::{ToSql, Type},
+    Column,
+};
 use serde_json::Value;
+use std::time::Instant;
 
-use super::utils::row_to_object;
+use super::utils::{result_columns, row_to_object};
 
-pub async fn raw_query(pool: Pool, query: &str) -> Result<Vec<Value>> {
+pub async fn raw_query(
+    pool: Pool,
+    query: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<Vec<Value>> {
     let conn = pool.get().await?;
-    let params = vec![];
-    let rows = conn.query(query, &params).await?;
+    let rows = conn.query(query, params).await?;
     let mut result = Vec::new();
     for row in rows {
         result.push(row_to_object(row)?);
@@ -23,14 +36,23 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
         .expect("Time went backwards")
         .as_millis() as u64;
     let conn = pool.get().await?;
+    let stmt = conn.prepare(query).await?;
+    let mut columns = result_columns(stmt.columns());
     let params: Vec<String> = vec![];
-    let it = conn.query_raw(query, &params).await?;
+    let it = conn.query_raw(&stmt, &params).await?;
     let mut rows: Vec<Value> = Vec::new();
     pin_mut!(it);
     while let Some(row) = it.try_next().await? {
         rows.push(row_to_object(row)?);
     }
     let affected_rows = it.rows_affected().unwrap_or(0);
+    // provenance only decides editability, the rows are returned without it
+    let origins = column_origins(&conn, stmt.columns())
+        .await
+        .unwrap_or_else(|_| vec![None; columns.len()]);
+    for (column, origin) in columns.iter_mut().zip(origins) {
+        column.origin = origin;
+    }
     let end_time = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .expect("Time went backwards")
@@ -39,6 +61,7 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
         start_time,
         end_time,
         affected_rows,
+        columns,
         rows,
         table: TableMetadata {
             table: String::from(""),
@@ -46,23 +69,177 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
             primary_key: None,
             columns: None,
         },
+        row_keys: vec![],
     };
     Ok(set)
 }
 
-pub async fn execute_tx(pool: &Pool, queries: Vec<&str>) -> Result<()> {
+/// Table columns the result columns are read from, as the row description
+/// gives them by table oid and attribute number.
+async fn column_origins(conn: &Client, columns: &[Column]) -> Result<Vec<Option<ColumnOrigin>>> {
+    let mut oids: Vec<u32> = columns
+        .iter()
+        .filter_map(|c| c.table_oid())
+        .filter(|oid| *oid != 0)
+        .collect();
+    if oids.is_empty() {
+        return Ok(vec![None; columns.len()]);
+    }
+    oids.sort_unstable();
+    oids.dedup();
+    let rows = conn
+        .query(
+            "SELECT a.attrelid AS table_oid, a.attnum AS column_id, n.nspname AS schema_name,
+            c.relname AS table_name, a.attname AS column_name
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE a.attrelid = ANY($1) AND a.attnum > 0 AND NOT a.attisdropped;",
+            &[&oids],
+        )
+        .await?;
+    let attributes: Vec<(u32, i16, ColumnOrigin)> = rows
+        .iter()
+        .map(|r| {
+            (
+                r.get("table_oid"),
+                r.get("column_id"),
+                ColumnOrigin {
+                    schema: r.get("schema_name"),
+                    table: r.get("table_name"),
+                    column: r.get("column_name"),
+                },
+            )
+        })
+        .collect();
+    Ok(columns
+        .iter()
+        .map(|c| {
+            attributes
+                .iter()
+                .find(|(oid, id, _)| Some(*oid) == c.table_oid() && Some(*id) == c.column_id())
+                .map(|(_, _, origin)| origin.clone())
+        })
+        .collect())
+}
+
+pub async fn execute_tx(pool: &Pool, queries: Vec<&str>, dry_run: bool) -> Result<TxResult> {
     let mut conn = pool.get().await?;
     let tx = conn.transaction().await?;
-    for q in queries {
-        let params: Vec<String> = vec![];
-        match tx.execute_raw(q, &params).await {
-            Ok(..) => {}
+    let mut result = TxResult::default();
+    for (index, q) in queries.into_iter().enumerate() {
+        let start = Instant::now();
+        let outcome = async {
+            let params: Vec<String> = vec![];
+            let it = tx.query_raw(q, &params).await?;
+            pin_mut!(it);
+            let mut rows = vec![];
+            while let Some(row) = it.try_next().await? {
+                rows.push(row);
+            }
+            Ok::<_, postgres::Error>((rows, it.rows_affected().unwrap_or(0)))
+        };
+        match outcome.await {
+            Ok((rows, affected_rows)) => result.statements.push(StatementResult {
+                affected_rows,
+                last_insert_id: None,
+                rows: rows.into_iter().map(row_to_object).collect::<Result<_>>()?,
+                duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            }),
+            Err(e) => {
+                result.error = Some(StatementError {
+                    index,
+                    code: e.code().map(|c| c.code().to_string()),
+                    message: e
+                        .as_db_error()
+                        .map(|d| d.message().to_string())
+                        .unwrap_or_else(|| e.to_string()),
+                });
+                tx.rollback().await?;
+                return Ok(result);
+            }
+        }
+    }
+    match dry_run {
+        true => tx.rollback().await?,
+        false => tx.commit().await?,
+    }
+    result.committed = !dry_run;
+    Ok(result)
+}
+
+/// Parameters are sent as text and cast to the type the server infers for
+/// them, so JSON values don't have to be converted to every Postgres type.
+pub async fn execute_bound_tx(pool: &Pool, statements: Vec<BoundStatement>) -> Result<Vec<u64>> {
+    let mut conn = pool.get().await?;
+    let tx = conn.transaction().await?;
+    let mut affected = vec![];
+    let mut conflict = false;
+    for statement in statements {
+        let params: Vec<Option<String>> = statement.params.iter().map(text_param).collect();
+        let params: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+        let result = async {
+            let inferred = tx.prepare(&statement.sql).await?;
+            let sql = cast_params(&statement.sql, inferred.params());
+            let types = vec![Type::TEXT; params.len()];
+            let stmt = tx.prepare_typed(&sql, &types).await?;
+            tx.execute(&stmt, &params).await
+        };
+        match result.await {
+            Ok(rows) => affected.push(rows),
             Err(e) => {
                 tx.rollback().await?;
                 return Err(anyhow::anyhow!("Error executing query: {:?}", e));
             }
         }
+        // the remaining statements still run to find every conflict
+        conflict |= statement.expect_row && affected.last() != Some(&1);
+    }
+    match conflict {
+        true => tx.rollback().await?,
+        false => tx.commit().await?,
+    }
+    Ok(affected)
+}
+
+fn text_param(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        _ => Some(value.to_string()),
+    }
+}
+
+/// Rewrites each `$n` outside of quotes to `$n::text::type`.
+fn cast_params(sql: &str, types: &[Type]) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut quote: Option<char> = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        result.push(c);
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '$') => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                result.push_str(&digits);
+                let ty = digits
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| types.get(n.checked_sub(1)?));
+                if let Some(ty) = ty {
+                    result.push_str(&format!(
+                        "::text::{}",
+                        quote_qualified(&Dialect::Postgresql, &[ty.schema(), ty.name()])
+                    ));
+                }
+            }
+            _ => {}
+        }
     }
-    tx.commit().await?;
-    Ok(())
+    result
 }
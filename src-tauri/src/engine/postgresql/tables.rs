@@ -1,186 +1,289 @@
 use anyhow::Result;
 use deadpool_postgres::Pool;
 use futures::try_join;
-use serde_json::{json, Value};
+use postgres::types::ToSql;
+use serde_json::Value;
 
-use crate::engine::types::connection::InitiatedConnection;
+use crate::engine::types::catalog::{
+    Column, Constraint, ForeignKey, Index, PrimaryKey, Routine, SchemaSnapshot, Table, Trigger,
+    View,
+};
 
 use super::query::raw_query;
 
-pub async fn get_table_structure(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: String,
-) -> Result<Value> {
-    let (columns, foreign_keys, triggers, indices, pk) = try_join!(
-        get_columns(conn, pool, Some(&table)),
-        get_foreign_keys(conn, pool, &table),
-        get_triggers(conn, pool, Some(&table)),
-        get_indices(conn, pool, &table),
-        get_primary_key(conn, pool, &table),
-    )?;
+// The catalog queries below end with the schema filter so the single table
+// lookups and the schema snapshot can narrow them down differently.
 
-    let result = json!({
-        "table": table,
-        "columns": columns,
-        "foreign_keys": foreign_keys,
-        "indices": indices,
-        "triggers": triggers,
-        "primary_key": pk,
-    });
+const COLUMNS: &str = "SELECT
+        c.table_schema,
+        c.table_name,
+        c.column_name,
+        c.ordinal_position::int AS ordinal_position,
+        c.udt_name AS data_type,
+        format_type(a.atttypid, a.atttypmod) AS column_type,
+        c.is_nullable,
+        c.column_default,
+        c.character_maximum_length::int AS character_maximum_length,
+        c.collation_name,
+        col_description(a.attrelid, a.attnum) AS comment,
+        a.attidentity <> '' OR coalesce(c.column_default LIKE 'nextval(%', false)
+            AS is_auto_increment,
+        CASE a.attidentity WHEN 'a' THEN 'ALWAYS' WHEN 'd' THEN 'BY DEFAULT' END
+            AS identity_generation,
+        CASE a.attgenerated WHEN 's' THEN 'STORED' END AS generated,
+        c.generation_expression,
+        ARRAY(SELECT e.enumlabel::text FROM pg_enum e
+            WHERE e.enumtypid = a.atttypid ORDER BY e.enumsortorder) AS allowed_values
+        FROM information_schema.columns c
+        JOIN pg_namespace n ON n.nspname = c.table_schema
+        JOIN pg_class r ON r.relnamespace = n.oid AND r.relname = c.table_name
+        JOIN pg_attribute a ON a.attrelid = r.oid AND a.attname = c.column_name
+        WHERE c.table_schema = $1";
 
-    Ok(result)
-}
+const PRIMARY_KEYS: &str =
+    "SELECT tc.constraint_name, kcu.table_schema, kcu.table_name, kcu.column_name,
+        kcu.ordinal_position::int AS ordinal_position
+        FROM information_schema.table_constraints tc
+        JOIN information_schema.key_column_usage kcu USING (constraint_schema, constraint_name)
+        WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_schema = $1";
 
-pub async fn get_columns(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: Option<&str>,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT 
-        COLUMN_NAME, 
-        DATA_TYPE, 
-        CASE
-            WHEN character_maximum_length is not null  and udt_name != 'text'
-              THEN CONCAT(udt_name, concat('(', concat(character_maximum_length::varchar(255), ')')))
-            WHEN numeric_precision is not null
-                    THEN CONCAT(udt_name, concat('(', concat(numeric_precision::varchar(255),',',numeric_scale::varchar(255), ')')))
-            WHEN datetime_precision is not null AND udt_name != 'date' THEN
-              CONCAT(udt_name, concat('(', concat(datetime_precision::varchar(255), ')')))
-            ELSE udt_name
-        END as COLUMN_TYPE,
-        IS_NULLABLE,
-        COLUMN_DEFAULT,
-        CHARACTER_MAXIMUM_LENGTH,
-        CHARACTER_OCTET_LENGTH,
-        TABLE_SCHEMA,
-        ORDINAL_POSITION, 
-        TABLE_NAME
-        FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = '{}'",
-        schema
-    );
-    let query = match table {
-        Some(table) => format!(
-            "{} AND TABLE_NAME = '{}' ORDER BY ORDINAL_POSITION;",
-            query, table
-        ),
-        None => format!("{} ORDER BY ORDINAL_POSITION;", query),
+// constraint_column_usage loses the column pairing of composite keys, the
+// referenced column is looked up by position instead
+const FOREIGN_KEYS: &str = "SELECT
+          con.conname AS constraint_name,
+          n.nspname AS table_schema,
+          r.relname AS table_name,
+          a.attname AS column_name,
+          k.ord::int AS ordinal_position,
+          fn.nspname AS referenced_schema,
+          f.relname AS referenced_table_name,
+          fa.attname AS referenced_column_name,
+          CASE con.confupdtype WHEN 'a' THEN 'NO ACTION' WHEN 'r' THEN 'RESTRICT'
+            WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+          END AS update_rule,
+          CASE con.confdeltype WHEN 'a' THEN 'NO ACTION' WHEN 'r' THEN 'RESTRICT'
+            WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+          END AS delete_rule
+        FROM pg_constraint con
+        JOIN pg_class r ON r.oid = con.conrelid
+        JOIN pg_namespace n ON n.oid = r.relnamespace
+        JOIN pg_class f ON f.oid = con.confrelid
+        JOIN pg_namespace fn ON fn.oid = f.relnamespace
+        CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, fattnum, ord)
+        JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+        JOIN pg_attribute fa ON fa.attrelid = con.confrelid AND fa.attnum = k.fattnum
+        WHERE con.contype = 'f' AND n.nspname = $1";
+
+const INDICES: &str = "SELECT
+          i.relname AS index_name,
+          n.nspname AS table_schema,
+          t.relname AS table_name,
+          pg_get_indexdef(ix.indexrelid, k.ord, true) AS column_name,
+          ix.indisunique AS is_unique,
+          ix.indisprimary AS is_primary,
+          am.amname AS index_type,
+          pg_get_indexdef(ix.indexrelid) AS definition
+        FROM pg_index ix
+        JOIN pg_class i ON i.oid = ix.indexrelid
+        JOIN pg_class t ON t.oid = ix.indrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        JOIN pg_am am ON am.oid = i.relam
+        CROSS JOIN LATERAL generate_series(1, ix.indnkeyatts::int) AS k(ord)
+        WHERE n.nspname = $1";
+
+const TRIGGERS: &str =
+    "SELECT trigger_name, event_object_schema AS table_schema, event_object_table,
+        event_manipulation, action_timing, action_statement
+        FROM information_schema.triggers WHERE event_object_schema = $1";
+
+const VIEWS: &str = "SELECT table_schema, table_name, view_definition
+        FROM information_schema.views WHERE table_schema = $1";
+
+const ROUTINES: &str =
+    "SELECT routine_schema, routine_name, routine_type, data_type, routine_definition
+        FROM information_schema.routines WHERE routine_schema = $1";
+
+pub async fn get_columns(pool: &Pool, schema: &str, table: Option<&str>) -> Result<Vec<Column>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!(
+                "{} AND c.table_name = $2 ORDER BY c.ordinal_position;",
+                COLUMNS
+            );
+            raw_query(pool.clone(), &query, &[&schema, &table]).await?
+        }
+        None => {
+            let query = format!("{} ORDER BY c.table_name, c.ordinal_position;", COLUMNS);
+            raw_query(pool.clone(), &query, &[&schema]).await?
+        }
     };
-    raw_query(pool.clone(), &query).await
+    Ok(rows.iter().map(Column::from_row).collect())
 }
 
-pub async fn get_primary_key(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
+pub async fn get_primary_key(pool: &Pool, schema: &str, table: &str) -> Result<Vec<PrimaryKey>> {
     let query = format!(
-    "SELECT c.column_name, c.table_name, tc.constraint_name, c.table_schema, c.ordinal_position
-        FROM information_schema.table_constraints tc 
-        JOIN information_schema.constraint_column_usage AS ccu USING (constraint_schema, constraint_name) 
-        JOIN information_schema.columns AS c ON c.table_schema = tc.constraint_schema
-          AND tc.table_name = c.table_name AND ccu.column_name = c.column_name
-        WHERE constraint_type = 'PRIMARY KEY' and c.table_schema = '{}'",
-        schema
+        "{} AND tc.table_name = $2 ORDER BY kcu.ordinal_position;",
+        PRIMARY_KEYS
     );
-    let query = format!("{} AND c.table_name = '{}'", query, table);
-    raw_query(pool.clone(), &query).await
+    let rows = raw_query(pool.clone(), &query, &[&schema, &table]).await?;
+    Ok(rows.iter().map(PrimaryKey::from_row).collect())
 }
 
-pub async fn get_foreign_keys(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
+pub async fn get_foreign_keys(pool: &Pool, schema: &str, table: &str) -> Result<Vec<ForeignKey>> {
     let query = format!(
-        "SELECT
-          tc.constraint_name,
-          kcu.column_name as column_name,
-          ccu.table_name AS referenced_table_name,
-          ccu.column_name AS referenced_column_name,
-          rc.update_rule as update_rule,
-          rc.delete_rule as delete_rule
-        FROM
-          information_schema.table_constraints tc
-          JOIN information_schema.key_column_usage kcu ON tc.constraint_name = kcu.constraint_name
-          AND tc.table_schema = kcu.table_schema
-          JOIN information_schema.constraint_column_usage ccu ON ccu.constraint_name = tc.constraint_name
-          AND ccu.table_schema = tc.table_schema
-          JOIN information_schema.referential_constraints rc on tc.constraint_name = rc.constraint_name
-          AND tc.table_schema = rc.constraint_schema
-        WHERE
-          tc.constraint_type = 'FOREIGN KEY'
-          AND tc.table_schema = '{}'",
-        schema
+        "{} AND r.relname = $2 ORDER BY con.conname, k.ord;",
+        FOREIGN_KEYS
     );
-    let query = format!("{} AND tc.table_name = '{}'", query, table);
-    raw_query(pool.clone(), &query).await
+    let rows = raw_query(pool.clone(), &query, &[&schema, &table]).await?;
+    Ok(rows.iter().map(ForeignKey::from_row).collect())
 }
 
-pub async fn get_functions(conn: &InitiatedConnection, pool: &Pool) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT routine_name, routine_definition
-        FROM information_schema.routines
-        WHERE routine_type = 'FUNCTION' AND routine_schema = '{}';",
-        schema
-    );
-    raw_query(pool.clone(), &query).await
+async fn get_routines(pool: &Pool, schema: &str, routine_type: &str) -> Result<Vec<Routine>> {
+    let query = format!("{} AND routine_type = $2 ORDER BY routine_name;", ROUTINES);
+    let rows = raw_query(pool.clone(), &query, &[&schema, &routine_type]).await?;
+    Ok(rows.iter().map(Routine::from_row).collect())
 }
 
-pub async fn get_procedures(conn: &InitiatedConnection, pool: &Pool) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!("SELECT routine_name, routine_definition FROM information_schema.routines WHERE routine_type = 'PROCEDURE' AND routine_schema = '{}';", schema);
-    raw_query(pool.clone(), &query).await
+pub async fn get_functions(pool: &Pool, schema: &str) -> Result<Vec<Routine>> {
+    get_routines(pool, schema, "FUNCTION").await
 }
 
-pub async fn get_indices(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: &str,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT indexname, indexdef FROM pg_indexes WHERE schemaname = '{}'",
-        schema
-    );
-    let query = format!("{} and tablename = '{}';", query, table);
-    raw_query(pool.clone(), &query).await
+pub async fn get_procedures(pool: &Pool, schema: &str) -> Result<Vec<Routine>> {
+    get_routines(pool, schema, "PROCEDURE").await
 }
 
-pub async fn get_triggers(
-    conn: &InitiatedConnection,
-    pool: &Pool,
-    table: Option<&str>,
-) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT trigger_name, event_manipulation, action_timing,
-            event_object_table, action_statement, action_condition, created
-            FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = '{}'",
-        schema
-    );
-    let query = match table {
-        Some(table) => format!("{} AND EVENT_OBJECT_TABLE = '{}';", query, table),
-        None => format!("{};", query),
+pub async fn get_indices(pool: &Pool, schema: &str, table: &str) -> Result<Vec<Index>> {
+    let query = format!("{} AND t.relname = $2 ORDER BY i.relname, k.ord;", INDICES);
+    let rows = raw_query(pool.clone(), &query, &[&schema, &table]).await?;
+    Ok(Index::from_rows(&rows))
+}
+
+/// Unique and check constraints, checks list every column they refer to.
+pub async fn get_constraints(pool: &Pool, schema: &str, table: &str) -> Result<Vec<Constraint>> {
+    let query = "SELECT c.conname AS constraint_name, n.nspname AS table_schema,
+        r.relname AS table_name,
+        CASE c.contype WHEN 'u' THEN 'UNIQUE' ELSE 'CHECK' END AS constraint_type,
+        a.attname AS column_name,
+        CASE c.contype WHEN 'c' THEN pg_get_expr(c.conbin, c.conrelid) END AS check_clause
+        FROM pg_constraint c
+        JOIN pg_class r ON r.oid = c.conrelid
+        JOIN pg_namespace n ON n.oid = r.relnamespace
+        LEFT JOIN LATERAL unnest(c.conkey) WITH ORDINALITY k(attnum, position) ON TRUE
+        LEFT JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+        WHERE c.contype IN ('u', 'c') AND n.nspname = $1 AND r.relname = $2
+        ORDER BY c.conname, k.position;";
+    let rows = raw_query(pool.clone(), query, &[&schema, &table]).await?;
+    Ok(Constraint::from_rows(&rows))
+}
+
+/// Planner estimate of the number of rows, `None` when the table was never analyzed.
+pub async fn get_row_estimate(pool: &Pool, schema: &str, table: &str) -> Result<Option<f64>> {
+    let query = "SELECT c.reltuples::float8 AS estimate FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2;";
+    let rows = raw_query(pool.clone(), query, &[&schema, &table]).await?;
+    Ok(rows
+        .first()
+        .and_then(|r| r["estimate"].as_f64())
+        .filter(|e| *e >= 0.0))
+}
+
+pub async fn get_triggers(pool: &Pool, schema: &str, table: Option<&str>) -> Result<Vec<Trigger>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND event_object_table = $2;", TRIGGERS);
+            raw_query(pool.clone(), &query, &[&schema, &table]).await?
+        }
+        None => raw_query(pool.clone(), &format!("{};", TRIGGERS), &[&schema]).await?,
     };
-    raw_query(pool.clone(), &query).await
+    Ok(rows.iter().map(Trigger::from_row).collect())
 }
 
 pub async fn get_schemas(pool: &Pool) -> Result<Vec<Value>> {
-    let query = "SELECT schema_name schema FROM information_schema.schemata;".to_string();
-    raw_query(pool.clone(), &query).await
+    let query = "SELECT schema_name schema FROM information_schema.schemata;";
+    raw_query(pool.clone(), query, &[]).await
 }
 
-pub async fn get_views(conn: &InitiatedConnection, pool: &Pool) -> Result<Vec<Value>> {
-    let schema = conn.get_schema();
-    let query = format!(
-        "SELECT table_name FROM INFORMATION_SCHEMA.views WHERE table_schema = '{}'",
-        schema
+pub async fn get_views(pool: &Pool, schema: &str) -> Result<Vec<View>> {
+    let rows = raw_query(pool.clone(), VIEWS, &[&schema]).await?;
+    Ok(rows.iter().map(View::from_row).collect())
+}
+
+/// One page of the catalog of `schema`, the tables after `after` by name
+/// and everything defined on them. Routines come with the first page only.
+pub async fn get_schema_snapshot(
+    pool: &Pool,
+    schema: &str,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<SchemaSnapshot> {
+    let query = "SELECT n.nspname AS table_schema, c.relname AS table_name,
+        CASE WHEN c.relkind IN ('v', 'm') THEN 'View' ELSE 'Table' END AS table_type,
+        obj_description(c.oid, 'pg_class') AS comment
+        FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'v', 'm', 'f') AND c.relname > $2
+        ORDER BY c.relname LIMIT $3;";
+    let rows = raw_query(
+        pool.clone(),
+        query,
+        &[&schema, &after.unwrap_or_default(), &(limit as i64)],
+    )
+    .await?;
+    let tables: Vec<Table> = rows.iter().map(Table::from_row).collect();
+    let names: Vec<String> = tables.iter().map(|t| t.table_name.clone()).collect();
+
+    // sql_identifier columns have no array type, the names are compared as text
+    let columns = format!(
+        "{} AND c.table_name::text = ANY($2) ORDER BY c.table_name, c.ordinal_position;",
+        COLUMNS
+    );
+    let primary_keys = format!(
+        "{} AND tc.table_name::text = ANY($2) ORDER BY kcu.table_name, kcu.ordinal_position;",
+        PRIMARY_KEYS
+    );
+    let foreign_keys = format!(
+        "{} AND r.relname::text = ANY($2) ORDER BY r.relname, con.conname, k.ord;",
+        FOREIGN_KEYS
+    );
+    let indices = format!(
+        "{} AND t.relname::text = ANY($2) ORDER BY t.relname, i.relname, k.ord;",
+        INDICES
     );
-    raw_query(pool.clone(), &query).await
+    let triggers = format!("{} AND event_object_table::text = ANY($2);", TRIGGERS);
+    let views = format!("{} AND table_name::text = ANY($2);", VIEWS);
+    let routines = format!(
+        "{} AND routine_type IN ('FUNCTION', 'PROCEDURE') ORDER BY routine_name;",
+        ROUTINES
+    );
+    let routines = async {
+        match after {
+            None => raw_query(pool.clone(), &routines, &[&schema]).await,
+            Some(_) => Ok(vec![]),
+        }
+    };
+    let params: &[&(dyn ToSql + Sync)] = &[&schema, &names];
+    let (columns, primary_keys, foreign_keys, indices, triggers, views, routines) = try_join!(
+        raw_query(pool.clone(), &columns, params),
+        raw_query(pool.clone(), &primary_keys, params),
+        raw_query(pool.clone(), &foreign_keys, params),
+        raw_query(pool.clone(), &indices, params),
+        raw_query(pool.clone(), &triggers, params),
+        raw_query(pool.clone(), &views, params),
+        routines,
+    )?;
+
+    Ok(SchemaSnapshot {
+        schema: schema.to_string(),
+        next: (tables.len() == limit)
+            .then(|| names.last().cloned())
+            .flatten(),
+        tables,
+        columns: columns.iter().map(Column::from_row).collect(),
+        primary_keys: primary_keys.iter().map(PrimaryKey::from_row).collect(),
+        foreign_keys: foreign_keys.iter().map(ForeignKey::from_row).collect(),
+        indices: Index::from_rows(&indices),
+        triggers: triggers.iter().map(Trigger::from_row).collect(),
+        views: views.iter().map(View::from_row).collect(),
+        routines: routines.iter().map(Routine::from_row).collect(),
+    })
 }
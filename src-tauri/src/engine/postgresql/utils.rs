@@ -1,10 +1,15 @@
 use anyhow::{anyhow, Result};
 use postgres::{
-    types::{FromSql, Type},
+    types::{FromSql, Kind, Type},
     Column, Row,
 };
 use serde_json::{json, Value};
 
+use crate::engine::types::{
+    result::{CellType, ResultColumn},
+    value::{i64_to_json, timestamptz_to_json},
+};
+
 pub fn row_to_object(row: Row) -> Result<Value> {
     let mut object = json!({});
     for (i, column) in row.columns().iter().enumerate() {
@@ -15,6 +20,42 @@ pub fn row_to_object(row: Row) -> Result<Value> {
     Ok(object)
 }
 
+pub fn result_columns(columns: &[Column]) -> Vec<ResultColumn> {
+    columns
+        .iter()
+        .map(|column| ResultColumn {
+            name: column.name().to_string(),
+            db_type: column.type_().name().to_string(),
+            cell_type: cell_type(column.type_()),
+            origin: None,
+            editable: false,
+        })
+        .collect()
+}
+
+fn cell_type(ty: &Type) -> CellType {
+    match *ty {
+        Type::BOOL => CellType::Bool,
+        Type::INT2 | Type::INT4 | Type::OID => CellType::Integer,
+        Type::INT8 => CellType::BigInt,
+        Type::FLOAT4 | Type::FLOAT8 => CellType::Float,
+        Type::NUMERIC => CellType::Decimal,
+        Type::TEXT | Type::VARCHAR | Type::NAME | Type::CHAR | Type::BPCHAR | Type::UNKNOWN => {
+            CellType::Text
+        }
+        Type::JSON | Type::JSONB => CellType::Json,
+        Type::DATE => CellType::Date,
+        Type::TIME => CellType::Time,
+        Type::TIMESTAMP => CellType::Timestamp,
+        Type::TIMESTAMPTZ => CellType::TimestampTz,
+        Type::BYTEA => CellType::Binary,
+        _ => match ty.kind() {
+            Kind::Array(_) => CellType::Array,
+            _ => CellType::Other,
+        },
+    }
+}
+
 fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
     let f64_to_json_number = |raw_val: f64| -> Result<Value> {
         let temp =
@@ -33,11 +74,19 @@ fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
         Type::TIMESTAMP => get_basic(row, column, column_i, |a: chrono::NaiveDateTime| {
             Ok(Value::String(a.to_string()))
         })?,
+        // postgres only stores the instant, it is sent with the +00:00 offset it was normalized to
         Type::TIMESTAMPTZ => {
             get_basic(row, column, column_i, |a: chrono::DateTime<chrono::Utc>| {
-                Ok(Value::String(a.to_string()))
+                Ok(timestamptz_to_json(a))
             })?
         }
+        Type::DATE => get_basic(row, column, column_i, |a: chrono::NaiveDate| {
+            Ok(Value::String(a.to_string()))
+        })?,
+        Type::TIME => get_basic(row, column, column_i, |a: chrono::NaiveTime| {
+            Ok(Value::String(a.to_string()))
+        })?,
+        Type::NUMERIC => get_basic(row, column, column_i, |a: Numeric| Ok(Value::String(a.0)))?,
         Type::BOOL => get_basic(row, column, column_i, |a: bool| Ok(Value::Bool(a)))?,
         Type::INT2 => get_basic(row, column, column_i, |a: i16| {
             Ok(Value::Number(serde_json::Number::from(a)))
@@ -45,13 +94,14 @@ fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
         Type::INT4 => get_basic(row, column, column_i, |a: i32| {
             Ok(Value::Number(serde_json::Number::from(a)))
         })?,
-        Type::INT8 => get_basic(row, column, column_i, |a: i64| {
-            Ok(Value::Number(serde_json::Number::from(a)))
-        })?,
+        Type::INT8 => get_basic(row, column, column_i, |a: i64| Ok(i64_to_json(a)))?,
         Type::TEXT | Type::VARCHAR | Type::NAME | Type::CHAR | Type::UNKNOWN => {
             get_basic(row, column, column_i, |a: String| Ok(Value::String(a)))?
         }
         Type::JSON | Type::JSONB => get_basic(row, column, column_i, |a: Value| Ok(a))?,
+        Type::BYTEA => get_basic(row, column, column_i, |a: Vec<u8>| {
+            Ok(Value::String(bytes_to_hex(&a)))
+        })?,
         Type::FLOAT4 => get_basic(row, column, column_i, |a: f32| f64_to_json_number(a.into()))?,
         Type::FLOAT8 => get_basic(row, column, column_i, |a: f64| f64_to_json_number(a))?,
         // these types require a custom StringCollector struct as an intermediary (see struct at bottom)
@@ -64,9 +114,7 @@ fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
         Type::INT4_ARRAY => get_array(row, column, column_i, |a: i32| {
             Ok(Value::Number(serde_json::Number::from(a)))
         })?,
-        Type::INT8_ARRAY => get_array(row, column, column_i, |a: i64| {
-            Ok(Value::Number(serde_json::Number::from(a)))
-        })?,
+        Type::INT8_ARRAY => get_array(row, column, column_i, |a: i64| Ok(i64_to_json(a)))?,
         Type::TEXT_ARRAY | Type::VARCHAR_ARRAY => {
             get_array(row, column, column_i, |a: String| Ok(Value::String(a)))?
         }
@@ -77,7 +125,7 @@ fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
         Type::FLOAT8_ARRAY => get_array(row, column, column_i, |a: f64| f64_to_json_number(a))?,
 
         _ => {
-            let val: Option<GenericEnum> = row.get(1);
+            let val: Option<GenericEnum> = row.try_get(column_i)?;
             if let Some(_i) = val {
                 return Ok(Value::String(_i.0));
             }
@@ -85,6 +133,15 @@ fn convert_value(row: &Row, column: &Column, column_i: usize) -> Result<Value> {
         }
     })
 }
+
+/// Postgres' hex output format of `bytea`, e.g. `\xdeadbeef`.
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\\x{}", hex)
+}
+
+/// Any other type as its wire value, the binary format of types without a
+/// decoder isn't always text so those are shown as hex.
 #[derive(Debug)]
 struct GenericEnum(String);
 
@@ -93,15 +150,79 @@ impl FromSql<'_> for GenericEnum {
         _: &Type,
         raw: &[u8],
     ) -> Result<GenericEnum, Box<dyn std::error::Error + Sync + Send>> {
-        let result = std::str::from_utf8(raw).expect("Failed to convert to utf8 string");
-        let val = GenericEnum(result.to_owned());
-        Ok(val)
+        let val = match std::str::from_utf8(raw) {
+            Ok(text) => text.to_owned(),
+            Err(_) => bytes_to_hex(raw),
+        };
+        Ok(GenericEnum(val))
     }
     fn accepts(_ty: &Type) -> bool {
         true
     }
 }
 
+/// Exact text form of a NUMERIC, decoded from the binary wire format
+/// (base 10000 digits, a weight and a display scale).
+#[derive(Debug)]
+struct Numeric(String);
+
+impl FromSql<'_> for Numeric {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Numeric, Box<dyn std::error::Error + Sync + Send>> {
+        let read = |i: usize| -> Result<i16, Box<dyn std::error::Error + Sync + Send>> {
+            raw.get(i * 2..i * 2 + 2)
+                .map(|b| i16::from_be_bytes([b[0], b[1]]))
+                .ok_or_else(|| "invalid numeric".into())
+        };
+        let ndigits = read(0)? as usize;
+        let weight = read(1)? as i64;
+        let sign = read(2)? as u16;
+        let dscale = read(3)? as usize;
+        let digits = (0..ndigits)
+            .map(|i| read(4 + i))
+            .collect::<Result<Vec<_>, _>>()?;
+        match sign {
+            0xC000 => return Ok(Numeric("NaN".to_string())),
+            0xD000 => return Ok(Numeric("Infinity".to_string())),
+            0xF000 => return Ok(Numeric("-Infinity".to_string())),
+            _ => {}
+        }
+        let digit = |i: i64| -> i16 {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| digits.get(i).copied())
+                .unwrap_or(0)
+        };
+        let mut result = String::new();
+        if sign == 0x4000 {
+            result.push('-');
+        }
+        if weight < 0 {
+            result.push('0');
+        } else {
+            result.push_str(&digit(0).to_string());
+            for i in 1..=weight {
+                result.push_str(&format!("{:04}", digit(i)));
+            }
+        }
+        if dscale > 0 {
+            let mut fraction = String::new();
+            let mut i = weight + 1;
+            while fraction.len() < dscale {
+                fraction.push_str(&format!("{:04}", digit(i)));
+                i += 1;
+            }
+            fraction.truncate(dscale);
+            result.push('.');
+            result.push_str(&fraction);
+        }
+        Ok(Numeric(result))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
 fn get_basic<'a, T: FromSql<'a>>(
     row: &'a Row,
     _column: &Column,
@@ -129,3 +250,43 @@ fn get_array<'a, T: FromSql<'a>>(
         None => Value::Null,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use postgres::types::{FromSql, Type};
+
+    use super::{GenericEnum, Numeric};
+
+    fn numeric(weight: i16, sign: u16, dscale: i16, digits: &[i16]) -> String {
+        let mut raw = vec![];
+        for word in [digits.len() as i16, weight, sign as i16, dscale]
+            .iter()
+            .chain(digits)
+        {
+            raw.extend(word.to_be_bytes());
+        }
+        Numeric::from_sql(&Type::NUMERIC, &raw).unwrap().0
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(numeric(1, 0, 1, &[1234, 5678, 9000]), "12345678.9");
+        assert_eq!(numeric(0, 0x4000, 1, &[12, 5000]), "-12.5");
+        assert_eq!(numeric(-1, 0, 4, &[1]), "0.0001");
+        assert_eq!(numeric(-2, 0x4000, 5, &[1000]), "-0.00001");
+        assert_eq!(numeric(0, 0, 3, &[1, 5000]), "1.500");
+        assert_eq!(numeric(0, 0, 2, &[100]), "100.00");
+        assert_eq!(numeric(1, 0, 0, &[1]), "10000");
+        assert_eq!(numeric(0, 0, 2, &[]), "0.00");
+        assert_eq!(numeric(0, 0xC000, 0, &[]), "NaN");
+        assert_eq!(numeric(0, 0xF000, 0, &[]), "-Infinity");
+    }
+
+    #[test]
+    fn test_generic_binary() {
+        let value = GenericEnum::from_sql(&Type::UNKNOWN, &[0xde, 0xad, 0xbe, 0xef]).unwrap();
+        assert_eq!(value.0, "\\xdeadbeef");
+        let value = GenericEnum::from_sql(&Type::UNKNOWN, b"happy").unwrap();
+        assert_eq!(value.0, "happy");
+    }
+}
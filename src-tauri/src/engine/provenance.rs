@@ -0,0 +1,325 @@
+use anyhow::Result;
+use sqlparser::ast::{Expr, GroupByExpr, Select, SelectItem, SetExpr, Statement, TableFactor};
+use sqlparser::parser::Parser;
+
+use super::edit::key_columns;
+use super::types::config::Dialect;
+use super::types::connection::InitiatedConnection;
+use super::types::result::{ColumnOrigin, ResultSet, RowKey};
+
+/// Fills in the origin of the result columns and marks the ones that can be
+/// edited, those of tables whose key is also part of the result.
+///
+/// Postgresql reports the origin of each column with the result. sqlx and
+/// rusqlite don't expose MySQL's `org_table`/`org_name` or SQLite's column
+/// metadata, so their origins are read from the query: only a plain `SELECT`
+/// of table columns, without grouping or `DISTINCT`, is editable.
+///
+/// Callers check [`single_table_select`] first, other results are never
+/// editable and aren't worth the catalog lookups.
+pub async fn resolve_provenance(
+    conn: &InitiatedConnection,
+    sql: &str,
+    result: &mut ResultSet,
+) -> Result<()> {
+    if result.columns.is_empty() {
+        return Ok(());
+    }
+    if conn.config.dialect != Dialect::Postgresql {
+        let origins = parsed_origins(conn, sql, result.columns.len()).await?;
+        for (column, origin) in result.columns.iter_mut().zip(origins) {
+            column.origin = origin;
+        }
+    }
+    // rows are objects, a value of a repeated name may be from either column
+    let unique: Vec<bool> = result
+        .columns
+        .iter()
+        .map(|c| result.columns.iter().filter(|o| o.name == c.name).count() == 1)
+        .collect();
+    let mut tables: Vec<(String, String)> = vec![];
+    for (column, unique) in result.columns.iter().zip(&unique) {
+        if let Some(origin) = &column.origin {
+            let table = (origin.schema.clone(), origin.table.clone());
+            if *unique && !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+    }
+    for (schema, table) in tables {
+        let Ok(key) = key_columns(conn, &schema, &table).await else {
+            continue;
+        };
+        let of_table = |origin: &ColumnOrigin| origin.schema == schema && origin.table == table;
+        let positions: Option<Vec<usize>> = key
+            .iter()
+            .map(|k| {
+                result.columns.iter().zip(&unique).position(|(c, unique)| {
+                    *unique
+                        && c.origin
+                            .as_ref()
+                            .is_some_and(|o| of_table(o) && &o.column == k)
+                })
+            })
+            .collect();
+        let Some(positions) = positions else {
+            continue;
+        };
+        let editable: Vec<bool> = result
+            .columns
+            .iter()
+            .zip(&unique)
+            .map(|(c, unique)| *unique && c.origin.as_ref().is_some_and(of_table))
+            .collect();
+        for (column, editable) in result.columns.iter_mut().zip(editable) {
+            column.editable |= editable;
+        }
+        result.row_keys.push(RowKey {
+            schema,
+            table,
+            columns: positions,
+        });
+    }
+    Ok(())
+}
+
+/// Whether `sql` is one `SELECT` of a single table, without joins, grouping
+/// or `DISTINCT`. Only parses the query, no catalog lookups.
+pub fn single_table_select(dialect: &Dialect, sql: &str) -> bool {
+    let Ok(statements) = Parser::parse_sql(dialect.parser_dialect().as_ref(), sql) else {
+        return false;
+    };
+    let [Statement::Query(query)] = statements.as_slice() else {
+        return false;
+    };
+    // a CTE may shadow a table name
+    if query.with.is_some() {
+        return false;
+    }
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return false;
+    };
+    match select.from.as_slice() {
+        [table] => {
+            table.joins.is_empty()
+                && matches!(table.relation, TableFactor::Table { .. })
+                && !aggregated(select)
+        }
+        _ => false,
+    }
+}
+
+fn aggregated(select: &Select) -> bool {
+    let grouped = match &select.group_by {
+        GroupByExpr::Expressions(expressions, _) => !expressions.is_empty(),
+        GroupByExpr::All(_) => true,
+    };
+    grouped || select.distinct.is_some() || select.having.is_some()
+}
+
+struct Relation {
+    schema: String,
+    table: String,
+    alias: Option<String>,
+    /// Catalog columns, `None` for subqueries and table functions.
+    columns: Option<Vec<String>>,
+}
+
+impl Relation {
+    fn named(&self, qualifier: &str) -> bool {
+        match &self.alias {
+            Some(alias) => alias.eq_ignore_ascii_case(qualifier),
+            None => self.table.eq_ignore_ascii_case(qualifier),
+        }
+    }
+
+    fn origin(&self, column: &str) -> Option<ColumnOrigin> {
+        let column = self
+            .columns
+            .as_ref()?
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(column))?;
+        Some(ColumnOrigin {
+            schema: self.schema.clone(),
+            table: self.table.clone(),
+            column: column.clone(),
+        })
+    }
+}
+
+async fn parsed_origins(
+    conn: &InitiatedConnection,
+    sql: &str,
+    count: usize,
+) -> Result<Vec<Option<ColumnOrigin>>> {
+    let unknown = vec![None; count];
+    let statements = {
+        // the parser dialect can't be held across the catalog lookups
+        let dialect = conn.config.dialect.parser_dialect();
+        Parser::parse_sql(dialect.as_ref(), sql)?
+    };
+    let [Statement::Query(query)] = statements.as_slice() else {
+        return Ok(unknown);
+    };
+    // a CTE may shadow a table name
+    if query.with.is_some() {
+        return Ok(unknown);
+    }
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return Ok(unknown);
+    };
+    if aggregated(select) {
+        return Ok(unknown);
+    }
+
+    let mut relations = vec![];
+    let mut factors = vec![];
+    for table in &select.from {
+        factors.push(&table.relation);
+        factors.extend(table.joins.iter().map(|j| &j.relation));
+    }
+    for factor in factors {
+        let relation = match factor {
+            TableFactor::Table { name, alias, .. } => {
+                let parts: Vec<&str> = name.0.iter().map(|i| i.value.as_str()).collect();
+                let table = parts.last().copied().unwrap_or_default().to_string();
+                let schema = match parts.len() {
+                    0 | 1 => conn.get_schema(),
+                    n => parts[n - 2].to_string(),
+                };
+                let columns: Vec<String> = conn
+                    .get_columns(&schema, Some(&table))
+                    .await?
+                    .into_iter()
+                    .map(|c| c.column_name)
+                    .collect();
+                Relation {
+                    schema,
+                    table,
+                    alias: alias.as_ref().map(|a| a.name.value.clone()),
+                    columns: (!columns.is_empty()).then_some(columns),
+                }
+            }
+            TableFactor::Derived { alias, .. } | TableFactor::Function { alias, .. } => Relation {
+                schema: String::new(),
+                table: String::new(),
+                alias: alias.as_ref().map(|a| a.name.value.clone()),
+                columns: None,
+            },
+            _ => return Ok(unknown),
+        };
+        relations.push(relation);
+    }
+
+    let mut origins = vec![];
+    for item in &select.projection {
+        match item {
+            SelectItem::Wildcard(_) => {
+                for relation in &relations {
+                    let Some(columns) = &relation.columns else {
+                        return Ok(unknown);
+                    };
+                    origins.extend(columns.iter().map(|c| relation.origin(c)));
+                }
+            }
+            SelectItem::QualifiedWildcard(name, _) => {
+                let qualifier = name.0.last().map(|i| i.value.as_str()).unwrap_or_default();
+                let relation = relations.iter().find(|r| r.named(qualifier));
+                let Some((relation, columns)) =
+                    relation.and_then(|r| r.columns.as_ref().map(|c| (r, c)))
+                else {
+                    return Ok(unknown);
+                };
+                origins.extend(columns.iter().map(|c| relation.origin(c)));
+            }
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                origins.push(expression_origin(expr, &relations));
+            }
+        }
+    }
+    // the columns were expanded differently than the server did
+    if origins.len() != count {
+        return Ok(unknown);
+    }
+    Ok(origins)
+}
+
+fn expression_origin(expr: &Expr, relations: &[Relation]) -> Option<ColumnOrigin> {
+    match expr {
+        Expr::Identifier(ident) => {
+            // unqualified columns must belong to a single relation
+            let mut candidates = relations.iter().filter(|r| match &r.columns {
+                Some(columns) => columns.iter().any(|c| c.eq_ignore_ascii_case(&ident.value)),
+                None => true,
+            });
+            let relation = candidates.next()?;
+            if candidates.next().is_some() {
+                return None;
+            }
+            relation.origin(&ident.value)
+        }
+        Expr::CompoundIdentifier(parts) if parts.len() >= 2 => {
+            let qualifier = &parts[parts.len() - 2].value;
+            relations
+                .iter()
+                .find(|r| r.named(qualifier))?
+                .origin(&parts[parts.len() - 1].value)
+        }
+        Expr::Nested(expr) => expression_origin(expr, relations),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlparser::ast::Expr;
+    use sqlparser::dialect::MySqlDialect;
+    use sqlparser::parser::Parser;
+
+    use super::{expression_origin, single_table_select, Relation};
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_expression_origin() {
+        let relation = |table: &str, alias: Option<&str>, columns: &[&str]| Relation {
+            schema: "db".to_string(),
+            table: table.to_string(),
+            alias: alias.map(String::from),
+            columns: Some(columns.iter().map(|c| c.to_string()).collect()),
+        };
+        let relations = vec![
+            relation("users", Some("u"), &["id", "Name"]),
+            relation("orders", None, &["id", "user_id"]),
+        ];
+        let origin = |sql: &str| {
+            let expr: Expr = Parser::new(&MySqlDialect {})
+                .try_with_sql(sql)
+                .unwrap()
+                .parse_expr()
+                .unwrap();
+            expression_origin(&expr, &relations).map(|o| format!("{}.{}", o.table, o.column))
+        };
+        assert_eq!(origin("name"), Some("users.Name".to_string()));
+        assert_eq!(origin("orders.id"), Some("orders.id".to_string()));
+        assert_eq!(origin("(u.id)"), Some("users.id".to_string()));
+        assert_eq!(origin("id"), None);
+        assert_eq!(origin("users.id"), None);
+        assert_eq!(origin("user_id + 1"), None);
+    }
+
+    #[test]
+    fn test_single_table_select() {
+        let single = |sql: &str| single_table_select(&Dialect::MariaDB, sql);
+        assert!(single("SELECT * FROM users WHERE id > 3"));
+        assert!(single("SELECT u.id, u.name FROM db.users u ORDER BY name"));
+        assert!(!single(
+            "SELECT * FROM users u JOIN orders o ON o.user_id = u.id"
+        ));
+        assert!(!single("SELECT * FROM users, orders"));
+        assert!(!single("SELECT name, count(*) FROM users GROUP BY name"));
+        assert!(!single("SELECT DISTINCT name FROM users"));
+        assert!(!single("SELECT * FROM (SELECT 1) t"));
+        assert!(!single("WITH u AS (SELECT 1) SELECT * FROM u"));
+        assert!(!single("UPDATE users SET name = 'a'"));
+    }
+}
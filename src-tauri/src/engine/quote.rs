@@ -0,0 +1,51 @@
+use super::types::config::Dialect;
+
+/// Quotes an identifier so it is taken verbatim, embedded quote characters
+/// are doubled.
+pub fn quote_ident(dialect: &Dialect, ident: &str) -> String {
+    match dialect {
+        Dialect::Mysql | Dialect::MariaDB => format!("`{}`", ident.replace('`', "``")),
+        Dialect::Postgresql | Dialect::Sqlite => format!("\"{}\"", ident.replace('"', "\"\"")),
+    }
+}
+
+/// Quotes every part of a dotted name, e.g. schema and table.
+pub fn quote_qualified(dialect: &Dialect, parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| quote_ident(dialect, part))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Quotes a string literal. Prefer bound parameters, this is for statements
+/// that can't take them.
+pub fn quote_literal(dialect: &Dialect, value: &str) -> String {
+    match dialect {
+        // backslash is an escape character in MySQL strings unless NO_BACKSLASH_ESCAPES is set
+        Dialect::Mysql | Dialect::MariaDB => {
+            format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+        }
+        Dialect::Postgresql | Dialect::Sqlite => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{quote_ident, quote_literal, quote_qualified};
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote_ident(&Dialect::Mysql, "we`ird"), "`we``ird`");
+        assert_eq!(
+            quote_qualified(&Dialect::Postgresql, &["public", "say \"hi\""]),
+            "\"public\".\"say \"\"hi\"\"\""
+        );
+        assert_eq!(quote_literal(&Dialect::Sqlite, "it's"), "'it''s'");
+        assert_eq!(
+            quote_literal(&Dialect::MariaDB, "a\\' OR 1"),
+            "'a\\\\'' OR 1'"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+
+/// Default schema of a connection's sessions (`search_path`, `USE db`).
+/// Switching it keeps the pool, every pooled session applies the new schema
+/// the first time it is handed out after the switch.
+#[derive(Debug)]
+pub struct SessionSchema {
+    name: RwLock<String>,
+    switched: RwLock<Instant>,
+    /// Sessions checked out during the latest switch that were not released
+    /// with the previous schema yet.
+    unreleased: AtomicUsize,
+}
+
+impl SessionSchema {
+    pub fn new(name: String) -> Self {
+        SessionSchema {
+            name: RwLock::new(name),
+            switched: RwLock::new(Instant::now()),
+            unreleased: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get(&self) -> String {
+        self.name.read().expect("Session schema poisoned").clone()
+    }
+
+    /// Switches to `name` while `in_use` sessions are checked out, those are
+    /// handed back with the previous schema.
+    pub fn set(&self, name: String, in_use: usize) {
+        *self.name.write().expect("Session schema poisoned") = name;
+        *self.switched.write().expect("Session schema poisoned") = Instant::now();
+        self.unreleased.store(in_use, Ordering::Relaxed);
+    }
+
+    /// Whether a session checked out during the latest switch may still be
+    /// released with the previous schema.
+    pub fn has_unreleased(&self) -> bool {
+        self.unreleased.load(Ordering::Relaxed) > 0
+    }
+
+    /// Records that a session checked out during the switch was released and
+    /// reset to the current schema.
+    pub fn released(&self) {
+        let _ = self
+            .unreleased
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// Whether a session last handed out at `last_used` predates the latest
+    /// switch and still has the previous schema.
+    pub fn is_stale(&self, last_used: Instant) -> bool {
+        last_used < *self.switched.read().expect("Session schema poisoned")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+
+    use super::SessionSchema;
+
+    #[test]
+    fn test_checkout_across_switch() {
+        let schema = SessionSchema::new("a".to_string());
+        let created = Instant::now();
+        sleep(Duration::from_millis(1));
+        assert!(!schema.has_unreleased());
+        // the session is checked out while the schema is switched
+        schema.set("b".to_string(), 1);
+        sleep(Duration::from_millis(1));
+        let released = Instant::now();
+        // handed out again right away, it was released after the switch
+        assert!(!schema.is_stale(released));
+        // on release it's older than the switch and has to be checked
+        assert!(schema.is_stale(created));
+        assert!(schema.has_unreleased());
+        schema.released();
+        // once it's reset the other releases skip the check
+        assert!(!schema.has_unreleased());
+        assert_eq!(schema.get(), "b");
+    }
+}
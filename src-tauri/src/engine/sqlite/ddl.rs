@@ -0,0 +1,37 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::Pool;
+
+use crate::engine::types::catalog::ObjectKind;
+
+use super::query::raw_query;
+
+/// SQLite keeps the statement every object was created with, tables come
+/// with their indices and triggers.
+pub async fn get_object_ddl(pool: &Pool, object: &str, kind: ObjectKind) -> Result<String> {
+    let object_type = match kind {
+        ObjectKind::Table => "table",
+        ObjectKind::View => "view",
+        ObjectKind::Trigger => "trigger",
+        ObjectKind::Index => "index",
+        _ => {
+            return Err(anyhow!(
+                "{:?} definitions are not available for Sqlite",
+                kind
+            ))
+        }
+    };
+    // auto indices backing constraints have no sql
+    let query = "SELECT sql FROM sqlite_master
+        WHERE sql IS NOT NULL AND (type = ?2 AND name = ?1
+            OR ?2 = 'table' AND type IN ('index', 'trigger') AND tbl_name = ?1)
+        ORDER BY type <> ?2, type, name;";
+    let rows = raw_query(pool, query, &[object, object_type]).await?;
+    let statements: Vec<String> = rows
+        .iter()
+        .filter_map(|r| r["sql"].as_str().map(|sql| format!("{};", sql)))
+        .collect();
+    match statements.is_empty() {
+        true => Err(anyhow!("{:?} {} was not found", kind, object)),
+        false => Ok(statements.join("\n\n")),
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use deadpool_sqlite::Pool;
+use serde_json::Value;
+
+use crate::engine::types::plan::{NodeKind, PlanNode, QueryPlan};
+
+use super::query::execute_query;
+
+/// SQLite only has `EXPLAIN QUERY PLAN`, there is no analyzed variant so the
+/// statement is never executed.
+pub async fn explain_query(pool: &Pool, sql: &str) -> Result<QueryPlan> {
+    let result = execute_query(pool, &format!("EXPLAIN QUERY PLAN {}", sql)).await?;
+    Ok(QueryPlan {
+        analyzed: false,
+        root: parse_plan(&result.rows),
+        planning_time_ms: None,
+        execution_time_ms: None,
+        raw: Value::Array(result.rows),
+    })
+}
+
+fn parse_plan(rows: &[Value]) -> PlanNode {
+    // rows reference their parent by id, the implicit root has id 0
+    let mut children: HashMap<i64, Vec<(i64, PlanNode)>> = HashMap::new();
+    for row in rows {
+        let id = row["id"].as_i64().unwrap_or_default();
+        let parent = row["parent"].as_i64().unwrap_or_default();
+        let detail = row["detail"].as_str().unwrap_or_default();
+        children
+            .entry(parent)
+            .or_default()
+            .push((id, parse_detail(detail)));
+    }
+    PlanNode {
+        operation: "QUERY PLAN".to_string(),
+        children: attach(0, &mut children),
+        ..Default::default()
+    }
+}
+
+fn attach(parent: i64, children: &mut HashMap<i64, Vec<(i64, PlanNode)>>) -> Vec<PlanNode> {
+    children
+        .remove(&parent)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, mut node)| {
+            node.children = attach(id, children);
+            node
+        })
+        .collect()
+}
+
+fn parse_detail(detail: &str) -> PlanNode {
+    let is_scan = detail.starts_with("SCAN ");
+    let is_search = detail.starts_with("SEARCH ");
+    let covering = detail.contains("COVERING INDEX");
+    let kind = match detail {
+        _ if (is_scan || is_search) && covering => NodeKind::IndexOnlyScan,
+        _ if is_search || (is_scan && detail.contains(" USING ")) => NodeKind::IndexScan,
+        "SCAN CONSTANT ROW" => NodeKind::Other,
+        _ if is_scan => NodeKind::FullScan,
+        _ if detail.starts_with("USE TEMP B-TREE FOR ORDER BY")
+            || detail.starts_with("USE TEMP B-TREE FOR LAST") =>
+        {
+            NodeKind::Sort
+        }
+        _ if detail.starts_with("USE TEMP B-TREE")
+            || detail.starts_with("MATERIALIZE")
+            || detail.starts_with("CO-ROUTINE") =>
+        {
+            NodeKind::TempTable
+        }
+        _ if detail.contains("SUBQUERY") => NodeKind::Subquery,
+        _ => NodeKind::Other,
+    };
+    let (relation, index) = match is_scan || is_search {
+        true => {
+            // older versions print `SCAN TABLE t`, newer ones `SCAN t`
+            let mut words = detail.split_whitespace().skip(1);
+            let relation = words
+                .next()
+                .and_then(|w| match w {
+                    "TABLE" => words.next(),
+                    _ => Some(w),
+                })
+                .map(String::from);
+            let index = detail
+                .split_once("INDEX ")
+                .and_then(|(_, rest)| rest.split_whitespace().next())
+                .map(String::from)
+                .or_else(|| {
+                    detail
+                        .contains("INTEGER PRIMARY KEY")
+                        .then(|| "INTEGER PRIMARY KEY".to_string())
+                });
+            (relation, index)
+        }
+        false => (None, None),
+    };
+    PlanNode {
+        kind,
+        operation: detail.to_string(),
+        relation,
+        index,
+        condition: detail
+            .find(" (")
+            .map(|i| detail[i + 1..].to_string())
+            .filter(|_| is_search),
+        ..Default::default()
+    }
+}
@@ -1,3 +1,5 @@
+pub mod ddl;
+pub mod explain;
 pub mod query;
 pub mod tables;
 pub mod utils;
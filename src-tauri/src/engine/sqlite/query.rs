@@ -1,20 +1,30 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::Pool;
+use deadpool_sqlite::{
+    rusqlite::{params_from_iter, types::Value as SqlValue, Transaction},
+    Pool,
+};
 use serde_json::Value;
+use std::time::Instant;
 
-use crate::engine::types::result::{ResultSet, TableMetadata};
+use crate::engine::types::edit::BoundStatement;
+use crate::engine::types::result::{
+    ResultSet, StatementError, StatementResult, TableMetadata, TxResult,
+};
 
-use super::utils::row_to_object;
+use super::utils::{result_columns, row_to_object};
 
-pub async fn raw_query(pool: &Pool, query: &str) -> Result<Vec<Value>> {
+pub async fn raw_query(pool: &Pool, query: &str, params: &[&str]) -> Result<Vec<Value>> {
     let conn = pool.get().await.expect("Failed to get connection");
     let query = query.to_string();
+    let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
     let mut result: Vec<Value> = Vec::new();
     let rows = conn
         .interact(move |conn| {
             let mut stmt = conn.prepare(&query).expect("Failed to prepare statement");
             let columns_count = stmt.column_count();
-            let mut rows = stmt.query([]).expect("Failed to execute query");
+            let mut rows = stmt
+                .query(params_from_iter(params))
+                .expect("Failed to execute query");
             while let Some(row) = rows.next().expect("Failed to get row") {
                 result.push(row_to_object(row, columns_count));
             }
@@ -37,13 +47,14 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
             match stmt {
                 Ok(mut stmt) => {
                     let mut result: Vec<Value> = Vec::new();
+                    let columns = result_columns(&stmt);
                     let columns_count = stmt.column_count();
                     match stmt.query([]) {
                         Ok(mut rows) => {
                             while let Some(row) = rows.next().expect("Failed to get row") {
                                 result.push(row_to_object(row, columns_count));
                             }
-                            Ok(result)
+                            Ok((columns, result))
                         }
                         Err(e) => Err(e),
                     }
@@ -52,7 +63,7 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
             }
         })
         .await;
-    let rows = rows.expect("Failed to execute query")?;
+    let (columns, rows) = rows.expect("Failed to execute query")?;
     let end_time = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .expect("Time went backwards")
@@ -61,13 +72,15 @@ pub async fn execute_query(pool: &Pool, query: &str) -> Result<ResultSet> {
         start_time,
         end_time,
         affected_rows: 0,
+        columns,
         rows,
         table: TableMetadata::default(),
+        row_keys: vec![],
     };
     Ok(set)
 }
 
-pub async fn execute_tx(pool: &Pool, queries: Vec<&str>) -> Result<()> {
+pub async fn execute_tx(pool: &Pool, queries: Vec<&str>, dry_run: bool) -> Result<TxResult> {
     let conn = pool.get().await.expect("Failed to get connection");
     let queries = queries
         .iter()
@@ -75,20 +88,95 @@ pub async fn execute_tx(pool: &Pool, queries: Vec<&str>) -> Result<()> {
         .collect::<Vec<String>>();
     conn.interact(move |conn| {
         let tx = conn.transaction()?;
+        let mut result = TxResult::default();
+        for (index, query) in queries.iter().enumerate() {
+            let start = Instant::now();
+            let last_insert_id = tx.last_insert_rowid();
+            // changes() keeps the count of the last data change, statements
+            // that don't change anything leave total_changes() as it was
+            let total_changes = |tx: &Transaction| {
+                tx.query_row("SELECT total_changes()", [], |r| r.get::<_, i64>(0))
+            };
+            let total_before = total_changes(&tx)?;
+            let outcome = tx.prepare(query).and_then(|mut stmt| {
+                let column_count = stmt.column_count();
+                let mut rows = vec![];
+                let mut it = stmt.raw_query();
+                while let Some(row) = it.next()? {
+                    rows.push(row_to_object(row, column_count));
+                }
+                Ok(rows)
+            });
+            match outcome {
+                Ok(rows) => result.statements.push(StatementResult {
+                    affected_rows: match total_changes(&tx)? == total_before {
+                        true => 0,
+                        false => tx.changes(),
+                    },
+                    // the rowid of the last insert outlives it
+                    last_insert_id: Some(tx.last_insert_rowid()).filter(|id| *id != last_insert_id),
+                    rows,
+                    duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+                }),
+                Err(e) => {
+                    result.error = Some(StatementError {
+                        index,
+                        code: e.sqlite_error().map(|e| e.extended_code.to_string()),
+                        message: e.to_string(),
+                    });
+                    tx.rollback()?;
+                    return Ok(result);
+                }
+            }
+        }
+        match dry_run {
+            true => tx.rollback()?,
+            false => tx.commit()?,
+        }
+        result.committed = !dry_run;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| anyhow!(e.to_string()))?
+}
 
-        for query in queries {
-            match tx.execute(&query, []) {
-                Ok(_) => continue,
+pub async fn execute_bound_tx(pool: &Pool, statements: Vec<BoundStatement>) -> Result<Vec<u64>> {
+    let conn = pool.get().await.expect("Failed to get connection");
+    conn.interact(move |conn| {
+        let tx = conn.transaction()?;
+        let mut affected = vec![];
+        let mut conflict = false;
+        for statement in statements {
+            let params = statement.params.iter().map(sqlite_param);
+            match tx.execute(&statement.sql, params_from_iter(params)) {
+                Ok(rows) => affected.push(rows as u64),
                 Err(e) => {
-                    // Attempt to rollback the transaction
                     let _ = tx.rollback();
                     return Err(anyhow!("Query failed: {}", e));
                 }
             }
+            // the remaining statements still run to find every conflict
+            conflict |= statement.expect_row && affected.last() != Some(&1);
         }
-
-        Ok(tx.commit()?)
+        match conflict {
+            true => tx.rollback()?,
+            false => tx.commit()?,
+        }
+        Ok(affected)
     })
     .await
     .map_err(|e| anyhow!(e.to_string()))?
 }
+
+fn sqlite_param(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        _ => SqlValue::Text(value.to_string()),
+    }
+}
@@ -1,111 +1,437 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use deadpool_sqlite::Pool;
 use futures::try_join;
-use serde_json::{json, Value};
+use serde_json::Value;
+use sqlparser::{
+    ast::{ColumnOption, CreateTable, Statement, TableConstraint},
+    dialect::SQLiteDialect,
+    parser::Parser,
+};
+
+use crate::engine::{
+    quote::quote_ident,
+    types::{
+        catalog::{
+            Column, Constraint, ConstraintType, ForeignKey, Index, PrimaryKey, Routine,
+            SchemaSnapshot, Table, Trigger, View,
+        },
+        config::Dialect,
+    },
+};
 
 use super::query::raw_query;
 
-pub async fn get_table_structure(pool: &Pool, table: String) -> Result<Value> {
-    let (columns, foreign_keys, triggers, indices, pk) = try_join!(
-        get_columns(pool, Some(&table)),
-        get_foreign_keys(pool, &table),
-        get_triggers(pool, Some(&table)),
-        get_indices(pool, &table),
-        get_primary_key(pool, &table),
-    )?;
+// The pragma functions take the table from the join with pragma_table_list
+// and the schema bound as `?1`, so every query covers all tables of the
+// schema in one statement and is narrowed down by appending a filter on
+// `t.name`. The sql of the tables is read from the schema's own sqlite_master,
+// see `in_schema`.
 
-    let result = json!({
-        "table": table,
-        "columns": columns,
-        "foreign_keys": foreign_keys,
-        "indices": indices,
-        "triggers": triggers,
-        "primary_key": pk,
-    });
+// `hidden` is 1 for hidden columns of virtual tables, 2 and 3 for virtual
+// and stored generated columns. The expressions of the latter are only part
+// of the table sql. A lone `INTEGER PRIMARY KEY` of a rowid table aliases
+// the rowid and is filled on insert.
+const COLUMNS: &str =
+    "SELECT t.schema AS table_schema, t.name AS table_name, p.name AS column_name,
+        p.cid + 1 AS ordinal_position, p.type AS column_type, NOT p.\"notnull\" AS is_nullable,
+        p.dflt_value AS column_default, p.hidden = 1 AS is_hidden,
+        CASE p.hidden WHEN 2 THEN 'VIRTUAL' WHEN 3 THEN 'STORED' END AS generated,
+        CASE WHEN p.hidden IN (2, 3) THEN m.sql END AS table_sql,
+        upper(p.type) = 'INTEGER' AND p.pk = 1
+            AND (SELECT count(*) FROM pragma_table_info(t.name, ?1) k WHERE k.pk > 0) = 1
+            AND NOT t.wr AS is_auto_increment
+        FROM pragma_table_list t
+        JOIN sqlite_master m ON m.name = t.name
+        JOIN pragma_table_xinfo(t.name, ?1) p
+        WHERE t.schema = ?1 AND t.name NOT LIKE 'sqlite_%'";
+
+const PRIMARY_KEYS: &str = "SELECT t.schema AS table_schema, t.name AS table_name,
+        p.name AS column_name, p.pk AS ordinal_position
+        FROM pragma_table_list t JOIN pragma_table_info(t.name, ?1) p
+        WHERE t.schema = ?1 AND t.type <> 'view' AND p.pk > 0";
+
+// SQLite foreign keys are unnamed, the id is unique per table
+const FOREIGN_KEYS: &str = "SELECT CAST(f.id AS TEXT) AS constraint_name,
+        t.schema AS table_schema, t.name AS table_name, f.\"from\" AS column_name,
+        f.seq + 1 AS ordinal_position, f.\"table\" AS referenced_table_name,
+        f.\"to\" AS referenced_column_name, f.on_update AS update_rule, f.on_delete AS delete_rule
+        FROM pragma_table_list t JOIN pragma_foreign_key_list(t.name, ?1) f
+        WHERE t.schema = ?1 AND t.type <> 'view'";
+
+// auto indices backing constraints have no sql, `key` leaves out the
+// primary key columns every index row carries, expressions have no name
+const INDICES: &str = "SELECT il.name AS index_name, t.schema AS table_schema,
+        t.name AS table_name, ii.name AS column_name, il.\"unique\" AS is_unique,
+        il.origin = 'pk' AS is_primary, CASE WHEN il.partial THEN 'partial' END AS index_type,
+        im.sql AS definition
+        FROM pragma_table_list t
+        JOIN pragma_index_list(t.name, ?1) il
+        JOIN pragma_index_xinfo(il.name, ?1) ii
+        LEFT JOIN sqlite_master im ON im.type = 'index' AND im.name = il.name
+        WHERE t.schema = ?1 AND t.type <> 'view' AND ii.key";
+
+const TRIGGERS: &str = "SELECT m.name AS trigger_name, ?1 AS table_schema,
+        m.tbl_name AS event_object_table, m.sql AS action_statement
+        FROM sqlite_master m WHERE m.type = 'trigger'";
 
-    Ok(result)
+// table_list types shadow tables of virtual tables, the module is only
+// part of the sql
+const TABLES: &str = "SELECT t.schema AS table_schema, t.name AS table_name,
+        CASE t.type WHEN 'view' THEN 'View' ELSE 'Table' END AS table_type,
+        t.type = 'shadow' AS shadow, t.wr AS without_rowid, t.strict,
+        CASE t.type WHEN 'virtual' THEN m.sql END AS table_sql
+        FROM pragma_table_list t JOIN sqlite_master m ON m.name = t.name
+        WHERE t.schema = ?1 AND t.name NOT LIKE 'sqlite_%'";
+
+// every overload and encoding has its own row
+const FUNCTIONS: &str = "SELECT DISTINCT ?1 AS routine_schema, name AS routine_name,
+        'FUNCTION' AS routine_type
+        FROM pragma_function_list ORDER BY name;";
+
+const VIEWS: &str = "SELECT ?1 AS table_schema, m.name AS table_name,
+        m.sql AS view_definition
+        FROM sqlite_master m WHERE m.type = 'view'";
+
+/// Reads the sql of the objects from the sqlite_master of `schema`, the
+/// unqualified table only has those of `main`.
+fn in_schema(query: &str, schema: &str) -> String {
+    let master = format!("{}.sqlite_master", quote_ident(&Dialect::Sqlite, schema));
+    query.replace("sqlite_master", &master)
 }
 
-pub async fn get_columns(pool: &Pool, table: Option<&str>) -> Result<Vec<Value>> {
-    let mut columns: Vec<Value> = vec![];
-    let query = "SELECT tbl_name FROM sqlite_master WHERE type IN ('table', 'view') AND tbl_name NOT LIKE '%sqlite%'";
-    let query = match table {
-        Some(table) => format!("{} AND tbl_name = '{}';", query, table),
-        None => format!("{};", query),
+pub async fn get_columns(pool: &Pool, schema: &str, table: Option<&str>) -> Result<Vec<Column>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND t.name = ?2 ORDER BY p.cid;", COLUMNS);
+            raw_query(pool, &in_schema(&query, schema), &[schema, table]).await?
+        }
+        None => {
+            let query = format!("{} ORDER BY t.name, p.cid;", COLUMNS);
+            raw_query(pool, &in_schema(&query, schema), &[schema]).await?
+        }
     };
-    let tables = raw_query(pool, &query).await?;
-    for table in tables {
-        let table = table["tbl_name"]
-            .as_str()
-            .expect("Failed to get table name");
-        let mut table_columns = get_table_columns(pool, table).await?;
-        columns.append(&mut table_columns);
-    }
-    Ok(columns)
-}
-
-async fn get_table_columns(pool: &Pool, table: &str) -> Result<Vec<Value>> {
-    let query = format!("PRAGMA table_info('{}');", table);
-    let columns = raw_query(pool, &query).await?;
-    let mut res = vec![];
-    columns.iter().for_each(|column| {
-        let column = column.as_object().expect("Failed to get column info");
-        res.push(json!({
-            "column_name": column["name"],
-            "column_type": column["type"],
-            "is_nullable": column["notnull"] == json!(0),
-            "column_default": column["dflt_value"],
-            "table_name": table.to_string(),
-            "primary_key": column["pk"],
-        }));
+    Ok(columns_from_rows(&rows))
+}
+
+fn columns_from_rows(rows: &[Value]) -> Vec<Column> {
+    let mut expressions: HashMap<String, HashMap<String, String>> = HashMap::new();
+    rows.iter()
+        .map(|row| {
+            let mut column = Column::from_row(row);
+            if let Some(sql) = row["table_sql"].as_str() {
+                column.generation_expression = expressions
+                    .entry(column.table_name.clone())
+                    .or_insert_with(|| generation_expressions(sql))
+                    .get(&column.column_name)
+                    .cloned();
+            }
+            column
+        })
+        .collect()
+}
+
+fn parse_create(sql: &str) -> Option<Statement> {
+    Parser::parse_sql(&SQLiteDialect {}, sql).ok()?.pop()
+}
+
+/// Expressions of the generated columns by column name.
+fn generation_expressions(sql: &str) -> HashMap<String, String> {
+    let Some(Statement::CreateTable(CreateTable { columns, .. })) = parse_create(sql) else {
+        return HashMap::new();
+    };
+    columns
+        .into_iter()
+        .filter_map(|c| {
+            let expr = c.options.into_iter().find_map(|o| match o.option {
+                ColumnOption::Generated {
+                    generation_expr, ..
+                } => generation_expr,
+                _ => None,
+            })?;
+            Some((c.name.value, expr.to_string()))
+        })
+        .collect()
+}
+
+/// Table and column check constraints, unnamed ones are named by their
+/// position in the statement.
+fn check_constraints(schema: &str, table: &str, sql: &str) -> Vec<Constraint> {
+    let Some(Statement::CreateTable(create)) = parse_create(sql) else {
+        return vec![];
+    };
+    let column_checks = create.columns.into_iter().flat_map(|c| {
+        c.options.into_iter().filter_map(move |o| match o.option {
+            ColumnOption::Check(expr) => Some((o.name, vec![c.name.value.clone()], expr)),
+            _ => None,
+        })
+    });
+    let table_checks = create.constraints.into_iter().filter_map(|c| match c {
+        TableConstraint::Check { name, expr } => Some((name, vec![], *expr)),
+        _ => None,
     });
-    Ok(res)
+    column_checks
+        .chain(table_checks)
+        .enumerate()
+        .map(|(i, (name, columns, expr))| Constraint {
+            constraint_name: name.map(|n| n.value).unwrap_or_else(|| (i + 1).to_string()),
+            table_schema: schema.to_string(),
+            table_name: table.to_string(),
+            constraint_type: ConstraintType::Check,
+            columns,
+            check_clause: Some(expr.to_string()),
+        })
+        .collect()
 }
 
-pub async fn get_primary_key(pool: &Pool, table: &str) -> Result<Vec<Value>> {
-    let columns = get_table_columns(pool, table).await?;
-    let pks = columns
-        .iter()
-        .filter(|c| c["primary_key"] == json!(1)).cloned()
-        .collect::<Vec<_>>();
-    Ok(pks)
+/// Module of a `CREATE VIRTUAL TABLE` statement.
+fn virtual_module(sql: Option<&str>) -> Option<String> {
+    match parse_create(sql?)? {
+        Statement::CreateVirtualTable { module_name, .. } => Some(module_name.value),
+        _ => None,
+    }
 }
 
-pub async fn get_foreign_keys(pool: &Pool, table: &str) -> Result<Vec<Value>> {
-    let query = format!("PRAGMA foreign_key_list('{}');", table);
-    let fks = raw_query(pool, &query).await?;
-    let fks = fks
+fn tables_from_rows(rows: &[Value]) -> Vec<Table> {
+    rows.iter()
+        .map(|row| Table {
+            virtual_module: virtual_module(row["table_sql"].as_str()),
+            ..Table::from_row(row)
+        })
+        .collect()
+}
+
+/// Built-in and application defined functions, SQLite has no stored routines.
+pub async fn get_functions(pool: &Pool, schema: &str) -> Result<Vec<Routine>> {
+    let rows = raw_query(pool, FUNCTIONS, &[schema]).await?;
+    Ok(rows.iter().map(Routine::from_row).collect())
+}
+
+/// Modules virtual tables can be created with, without the eponymous ones
+/// backing the pragma functions.
+pub async fn get_modules(pool: &Pool) -> Result<Vec<String>> {
+    let query = "SELECT name FROM pragma_module_list
+        WHERE name NOT LIKE 'pragma_%' ORDER BY name;";
+    let rows = raw_query(pool, query, &[]).await?;
+    Ok(rows
         .iter()
-        .map(|fk| {
-            json!({
-                "constraint_name": fk["id"],
-                "constraint_type": "FOREIGN KEY",
-                "table_name": table,
-                "column_name": fk["from"],
-                "referenced_table_name": fk["table"],
-                "referenced_column_name": fk["to"],
-            })
+        .filter_map(|r| r["name"].as_str().map(String::from))
+        .collect())
+}
+
+pub async fn get_primary_key(pool: &Pool, schema: &str, table: &str) -> Result<Vec<PrimaryKey>> {
+    let query = format!("{} AND t.name = ?2 ORDER BY p.pk;", PRIMARY_KEYS);
+    let rows = raw_query(pool, &query, &[schema, table]).await?;
+    Ok(rows.iter().map(PrimaryKey::from_row).collect())
+}
+
+pub async fn get_foreign_keys(pool: &Pool, schema: &str, table: &str) -> Result<Vec<ForeignKey>> {
+    let query = format!("{} AND t.name = ?2 ORDER BY f.id, f.seq;", FOREIGN_KEYS);
+    let rows = raw_query(pool, &query, &[schema, table]).await?;
+    Ok(rows.iter().map(ForeignKey::from_row).collect())
+}
+
+pub async fn get_indices(pool: &Pool, schema: &str, table: &str) -> Result<Vec<Index>> {
+    let query = format!("{} AND t.name = ?2 ORDER BY il.name, ii.seqno;", INDICES);
+    let rows = raw_query(pool, &in_schema(&query, schema), &[schema, table]).await?;
+    Ok(Index::from_rows(&rows))
+}
+
+/// Unique constraints are read from their auto indices, checks from the
+/// table sql.
+pub async fn get_constraints(pool: &Pool, schema: &str, table: &str) -> Result<Vec<Constraint>> {
+    let query = "SELECT il.name AS constraint_name, t.schema AS table_schema,
+        t.name AS table_name, 'UNIQUE' AS constraint_type, ii.name AS column_name
+        FROM pragma_table_list t
+        JOIN pragma_index_list(t.name, ?1) il
+        JOIN pragma_index_xinfo(il.name, ?1) ii
+        WHERE t.schema = ?1 AND t.type = 'table' AND il.origin = 'u' AND ii.key AND t.name = ?2
+        ORDER BY il.name, ii.seqno;";
+    let sql = in_schema(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1;",
+        schema,
+    );
+    let (uniques_params, sql_params): (&[&str], &[&str]) = (&[schema, table], &[table]);
+    let (uniques, sql) = try_join!(
+        raw_query(pool, query, uniques_params),
+        raw_query(pool, &sql, sql_params)
+    )?;
+    let mut constraints = Constraint::from_rows(&uniques);
+    if let Some(sql) = sql.first().and_then(|r| r["sql"].as_str()) {
+        constraints.extend(check_constraints(schema, table, sql));
+    }
+    Ok(constraints)
+}
+
+/// Row count `ANALYZE` stored in `sqlite_stat1`, the first number of a stat
+/// is the rows of the table. `None` until the schema has been analyzed.
+pub async fn get_row_estimate(pool: &Pool, schema: &str, table: &str) -> Result<Option<f64>> {
+    let analyzed = "SELECT 1 FROM pragma_table_list WHERE schema = ?1 AND name = 'sqlite_stat1';";
+    if raw_query(pool, analyzed, &[schema]).await?.is_empty() {
+        return Ok(None);
+    }
+    let query = in_schema(
+        "SELECT max(CAST(s.stat AS INTEGER)) AS estimate FROM sqlite_stat1 s WHERE s.tbl = ?1;",
+        schema,
+    );
+    let rows = raw_query(pool, &query, &[table]).await?;
+    Ok(rows.first().and_then(|r| r["estimate"].as_f64()))
+}
+
+pub async fn get_triggers(pool: &Pool, schema: &str, table: Option<&str>) -> Result<Vec<Trigger>> {
+    let rows = match table {
+        Some(table) => {
+            let query = format!("{} AND m.tbl_name = ?2;", TRIGGERS);
+            raw_query(pool, &in_schema(&query, schema), &[schema, table]).await?
+        }
+        None => {
+            let query = format!("{};", TRIGGERS);
+            raw_query(pool, &in_schema(&query, schema), &[schema]).await?
+        }
+    };
+    Ok(triggers_from_rows(&rows))
+}
+
+fn triggers_from_rows(rows: &[Value]) -> Vec<Trigger> {
+    rows.iter()
+        .map(|row| {
+            let mut trigger = Trigger::from_row(row);
+            let (timing, event) = trigger_event(trigger.action_statement.as_deref());
+            trigger.action_timing = timing;
+            trigger.event_manipulation = event;
+            trigger
         })
-        .collect::<Vec<_>>();
-    Ok(fks)
+        .collect()
 }
 
-pub async fn get_indices(pool: &Pool, table: &str) -> Result<Vec<Value>> {
-    let query = "SELECT * FROM sqlite_master WHERE type = 'index'";
-    let query = format!("{} and tbl_name = '{}';", query, table);
-    raw_query(pool, &query).await
+/// Timing and event of a `CREATE TRIGGER` statement, SQLite only keeps the sql.
+fn trigger_event(sql: Option<&str>) -> (Option<String>, Option<String>) {
+    let words: Vec<String> = sql
+        .unwrap_or_default()
+        .split_whitespace()
+        .take_while(|w| !w.eq_ignore_ascii_case("ON"))
+        .map(|w| w.to_uppercase())
+        .collect();
+    let has = |word: &str| words.iter().any(|w| w == word);
+    let timing = match () {
+        _ if has("INSTEAD") => "INSTEAD OF",
+        _ if has("AFTER") => "AFTER",
+        // BEFORE is the default when no timing is given
+        _ => "BEFORE",
+    };
+    let event = ["INSERT", "UPDATE", "DELETE"]
+        .into_iter()
+        .find(|e| has(e))
+        .map(String::from);
+    (Some(timing.to_string()), event)
 }
 
-pub async fn get_triggers(pool: &Pool, table: Option<&str>) -> Result<Vec<Value>> {
-    let query = "SELECT * FROM sqlite_master WHERE type='trigger'";
-    let query = match table {
-        Some(table) => format!("{} AND tbl_name = '{}';", query, table),
-        None => format!("{};", query),
+pub async fn get_views(pool: &Pool, schema: &str) -> Result<Vec<View>> {
+    let rows = raw_query(pool, &in_schema(&format!("{};", VIEWS), schema), &[schema]).await?;
+    Ok(rows.iter().map(View::from_row).collect())
+}
+
+/// One page of the catalog, the tables after `after` by name and everything
+/// defined on them. The table names of the page are bound as one JSON array.
+pub async fn get_schema_snapshot(
+    pool: &Pool,
+    schema: &str,
+    after: Option<&str>,
+    limit: usize,
+) -> Result<SchemaSnapshot> {
+    let query = format!(
+        "{} AND t.name > ?2 ORDER BY t.name LIMIT {};",
+        TABLES, limit
+    );
+    let params: &[&str] = &[schema, after.unwrap_or_default()];
+    let rows = raw_query(pool, &in_schema(&query, schema), params).await?;
+    let tables = tables_from_rows(&rows);
+    let names: Vec<String> = tables.iter().map(|t| t.table_name.clone()).collect();
+    let names_json = serde_json::to_string(&names)?;
+    let names_param: &[&str] = &[schema, &names_json];
+
+    let in_page = "IN (SELECT value FROM json_each(?2))";
+    let columns = format!("{} AND t.name {} ORDER BY t.name, p.cid;", COLUMNS, in_page);
+    let primary_keys = format!(
+        "{} AND t.name {} ORDER BY t.name, p.pk;",
+        PRIMARY_KEYS, in_page
+    );
+    let foreign_keys = format!(
+        "{} AND t.name {} ORDER BY t.name, f.id, f.seq;",
+        FOREIGN_KEYS, in_page
+    );
+    let indices = format!(
+        "{} AND t.name {} ORDER BY t.name, il.name, ii.seqno;",
+        INDICES, in_page
+    );
+    let triggers = format!("{} AND m.tbl_name {};", TRIGGERS, in_page);
+    let views = format!("{} AND m.name {};", VIEWS, in_page);
+    let (columns, indices, triggers, views) = (
+        in_schema(&columns, schema),
+        in_schema(&indices, schema),
+        in_schema(&triggers, schema),
+        in_schema(&views, schema),
+    );
+    let routines = async {
+        match after {
+            None => get_functions(pool, schema).await,
+            Some(_) => Ok(vec![]),
+        }
     };
-    raw_query(pool, &query).await
+    let (columns, primary_keys, foreign_keys, indices, triggers, views, routines) = try_join!(
+        raw_query(pool, &columns, names_param),
+        raw_query(pool, &primary_keys, names_param),
+        raw_query(pool, &foreign_keys, names_param),
+        raw_query(pool, &indices, names_param),
+        raw_query(pool, &triggers, names_param),
+        raw_query(pool, &views, names_param),
+        routines,
+    )?;
+
+    Ok(SchemaSnapshot {
+        schema: schema.to_string(),
+        next: (tables.len() == limit)
+            .then(|| names.last().cloned())
+            .flatten(),
+        tables,
+        columns: columns_from_rows(&columns),
+        primary_keys: primary_keys.iter().map(PrimaryKey::from_row).collect(),
+        foreign_keys: foreign_keys.iter().map(ForeignKey::from_row).collect(),
+        indices: Index::from_rows(&indices),
+        triggers: triggers_from_rows(&triggers),
+        views: views.iter().map(View::from_row).collect(),
+        routines,
+    })
 }
 
-pub async fn get_views(pool: &Pool) -> Result<Vec<Value>> {
-    let query = "SELECT * FROM sqlite_master WHERE type='view'";
-    raw_query(pool, query).await
+#[cfg(test)]
+mod test {
+    use super::{check_constraints, generation_expressions, virtual_module};
+
+    #[test]
+    fn test_parse_create() {
+        let expressions = generation_expressions(
+            "CREATE TABLE a (x INT, y INT GENERATED ALWAYS AS (x * 2) VIRTUAL, \"z\" TEXT AS (upper(x)) STORED)",
+        );
+        assert_eq!(expressions.len(), 2);
+        assert_eq!(expressions["y"], "x * 2");
+        assert_eq!(expressions["z"], "upper(x)");
+        assert_eq!(
+            virtual_module(Some("CREATE VIRTUAL TABLE docs USING fts5(title, body)")).as_deref(),
+            Some("fts5")
+        );
+        assert_eq!(virtual_module(Some("CREATE TABLE a (x)")), None);
+
+        let checks = check_constraints(
+            "main",
+            "a",
+            "CREATE TABLE a (x INT CHECK (x > 0), y INT, CONSTRAINT xy CHECK (x < y))",
+        );
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].constraint_name, "1");
+        assert_eq!(checks[0].columns, vec!["x"]);
+        assert_eq!(checks[1].constraint_name, "xy");
+        assert_eq!(checks[1].check_clause.as_deref(), Some("x < y"));
+    }
 }
@@ -1,11 +1,19 @@
-use rusqlite::{types::ValueRef, Row};
+use rusqlite::{types::ValueRef, Row, Statement};
 
 use serde_json::json;
 
+use crate::engine::types::{
+    result::{CellType, ResultColumn},
+    value::i64_to_json,
+};
+
 fn convert_value(value: ValueRef) -> serde_json::Value {
     match value.data_type() {
         rusqlite::types::Type::Real => json!(value.as_f64_or_null().expect("Failed to get f64")),
-        rusqlite::types::Type::Integer => json!(value.as_i64_or_null().expect("Failed to get i64")),
+        rusqlite::types::Type::Integer => value
+            .as_i64_or_null()
+            .expect("Failed to get i64")
+            .map_or(serde_json::Value::Null, i64_to_json),
         rusqlite::types::Type::Text => json!(value
             .as_str_or_null()
             .expect("Failed to get text")
@@ -30,3 +38,74 @@ pub fn row_to_object(row: &Row, column_count: usize) -> serde_json::Value {
     object
 }
 
+pub fn result_columns(stmt: &Statement) -> Vec<ResultColumn> {
+    stmt.columns()
+        .iter()
+        .map(|column| {
+            let db_type = column.decl_type().unwrap_or("").to_string();
+            ResultColumn {
+                name: column.name().to_string(),
+                cell_type: cell_type(&db_type),
+                db_type,
+                origin: None,
+                editable: false,
+            }
+        })
+        .collect()
+}
+
+// follows the column affinity rules, https://www.sqlite.org/datatype3.html#determination_of_column_affinity
+// expressions have no declared type, their values can be of any class
+fn cell_type(decl_type: &str) -> CellType {
+    let decl_type = decl_type.to_uppercase();
+    if decl_type.contains("INT") {
+        CellType::BigInt
+    } else if ["CHAR", "CLOB", "TEXT"]
+        .iter()
+        .any(|t| decl_type.contains(t))
+    {
+        CellType::Text
+    } else if decl_type.contains("BLOB") {
+        CellType::Binary
+    } else if ["REAL", "FLOA", "DOUB"]
+        .iter()
+        .any(|t| decl_type.contains(t))
+    {
+        CellType::Float
+    } else {
+        CellType::Other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rusqlite::Connection;
+
+    use super::result_columns;
+    use crate::engine::types::result::CellType;
+
+    #[test]
+    fn test_result_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (id INTEGER, name TEXT, data BLOB, v)")
+            .unwrap();
+        let stmt = conn
+            .prepare("SELECT id, name, data, v, count(*), id + 1 FROM t")
+            .unwrap();
+        let types: Vec<CellType> = result_columns(&stmt)
+            .into_iter()
+            .map(|c| c.cell_type)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                CellType::BigInt,
+                CellType::Text,
+                CellType::Binary,
+                CellType::Other,
+                CellType::Other,
+                CellType::Other,
+            ]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use super::plan::QueryPlan;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum IssueKind {
+    FullScan,
+    Filesort,
+    TempTable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanIssue {
+    pub kind: IssueKind,
+    pub relation: Option<String>,
+    pub estimated_rows: Option<f64>,
+    pub message: String,
+}
+
+/// Where the suggested columns were found in the statement.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum IndexReason {
+    Filter,
+    Join,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexSuggestion {
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub reason: IndexReason,
+    pub statement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexAdvice {
+    pub plan: QueryPlan,
+    pub issues: Vec<PlanIssue>,
+    pub suggestions: Vec<IndexSuggestion>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// A column as the table designer describes it, `column_type` and
+/// `column_default` are SQL (string defaults keep their quotes).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ColumnDefinition {
+    pub column_name: String,
+    pub column_type: String,
+    pub is_nullable: bool,
+    pub column_default: Option<String>,
+    #[serde(default)]
+    pub is_auto_increment: bool,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// One step of a table designer change set, applied in order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TableChange {
+    AddColumn {
+        column: ColumnDefinition,
+    },
+    DropColumn {
+        column_name: String,
+    },
+    RenameColumn {
+        column_name: String,
+        new_name: String,
+    },
+    ChangeType {
+        column_name: String,
+        column_type: String,
+    },
+    SetNullable {
+        column_name: String,
+        is_nullable: bool,
+    },
+    SetDefault {
+        column_name: String,
+        column_default: String,
+    },
+    DropDefault {
+        column_name: String,
+    },
+    AddIndex {
+        index_name: String,
+        columns: Vec<String>,
+        is_unique: bool,
+    },
+    DropIndex {
+        index_name: String,
+    },
+    AddForeignKey {
+        constraint_name: String,
+        columns: Vec<String>,
+        referenced_table_name: String,
+        referenced_columns: Vec<String>,
+        update_rule: Option<String>,
+        delete_rule: Option<String>,
+    },
+    DropForeignKey {
+        constraint_name: String,
+    },
+    AddUnique {
+        constraint_name: String,
+        columns: Vec<String>,
+    },
+    AddCheck {
+        constraint_name: String,
+        check_clause: String,
+    },
+    /// Drops a unique or check constraint.
+    DropConstraint {
+        constraint_name: String,
+    },
+}
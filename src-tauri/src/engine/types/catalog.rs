@@ -0,0 +1,817 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::value::json_to_f64;
+
+// Every dialect selects its catalog rows with the lower case aliases read
+// below, the structs are then built the same way for all of them.
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    match &row[key] {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        v => Some(v.to_string()),
+    }
+}
+
+fn int(row: &Value, key: &str) -> Option<i64> {
+    json_to_f64(&row[key]).map(|v| v as i64)
+}
+
+fn texts(row: &Value, key: &str) -> Vec<String> {
+    match &row[key] {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|i| i.as_str().map(String::from))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Catalog flags arrive as booleans (Postgresql), 0/1 (MySQL, SQLite) or
+/// `YES`/`NO` strings (information_schema).
+fn flag(row: &Value, key: &str) -> bool {
+    match &row[key] {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => matches!(s.to_ascii_uppercase().as_str(), "YES" | "TRUE" | "T" | "1"),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Column {
+    pub table_schema: String,
+    pub table_name: String,
+    pub column_name: String,
+    /// 1 based position in the table.
+    pub ordinal_position: i64,
+    /// Base type without modifiers, e.g. `varchar`.
+    pub data_type: String,
+    /// Full type as written in DDL, e.g. `varchar(255)`.
+    pub column_type: String,
+    pub is_nullable: bool,
+    pub column_default: Option<String>,
+    pub character_maximum_length: Option<i64>,
+    pub collation_name: Option<String>,
+    pub comment: Option<String>,
+    /// `VIRTUAL` or `STORED` for generated columns.
+    pub generated: Option<String>,
+    pub generation_expression: Option<String>,
+    /// Hidden column of a SQLite virtual table, e.g. `rank` of fts5, left out
+    /// of `SELECT *`.
+    pub is_hidden: bool,
+    /// Filled by the database on insert: `AUTO_INCREMENT`, identity and
+    /// `serial` columns and SQLite rowid aliases.
+    pub is_auto_increment: bool,
+    /// `ALWAYS` or `BY DEFAULT` for Postgresql identity columns, the former
+    /// rejects explicit values.
+    pub identity_generation: Option<String>,
+    pub default_kind: Option<DefaultKind>,
+    /// Labels of enum columns, and of MySQL `set` columns which take any
+    /// combination of them.
+    pub allowed_values: Vec<String>,
+}
+
+/// Whether a column default is a plain value or computed on insert, e.g.
+/// `now()` or `nextval(...)`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DefaultKind {
+    Literal,
+    Expression,
+}
+
+impl DefaultKind {
+    /// Classifies a default as the catalog prints it, Postgresql casts of a
+    /// literal (`'a'::text`) are still literals.
+    pub fn of(default: &str) -> Self {
+        let value = match default.split_once("::") {
+            Some((value, _)) if !value.contains('(') => value,
+            _ => default,
+        }
+        .trim();
+        let keyword = matches!(
+            value.to_ascii_uppercase().as_str(),
+            "NULL" | "TRUE" | "FALSE"
+        );
+        if value.starts_with('\'') || keyword || value.parse::<f64>().is_ok() {
+            DefaultKind::Literal
+        } else {
+            DefaultKind::Expression
+        }
+    }
+}
+
+/// Values of a MySQL `enum(...)` or `set(...)` column type.
+fn type_values(column_type: &str) -> Vec<String> {
+    let lower = column_type.to_ascii_lowercase();
+    if !(lower.starts_with("enum(") || lower.starts_with("set(")) {
+        return vec![];
+    }
+    let mut values = vec![];
+    let mut value: Option<String> = None;
+    let mut chars = column_type.chars().skip_while(|c| *c != '(').peekable();
+    while let Some(c) = chars.next() {
+        match (c, value.as_mut()) {
+            ('\'', None) => value = Some(String::new()),
+            // quotes are doubled inside a value
+            ('\'', Some(v)) if chars.peek() == Some(&'\'') => {
+                v.push('\'');
+                chars.next();
+            }
+            ('\'', Some(_)) => values.extend(value.take()),
+            (c, Some(v)) => v.push(c),
+            _ => {}
+        }
+    }
+    values
+}
+
+impl Column {
+    pub fn from_row(row: &Value) -> Self {
+        let column_type = text(row, "column_type").unwrap_or_default();
+        let allowed_values = match texts(row, "allowed_values") {
+            values if values.is_empty() => type_values(&column_type),
+            values => values,
+        };
+        Column {
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            column_name: text(row, "column_name").unwrap_or_default(),
+            ordinal_position: int(row, "ordinal_position").unwrap_or_default(),
+            // SQLite only knows the declared type
+            data_type: text(row, "data_type").unwrap_or_else(|| {
+                column_type
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            }),
+            column_type,
+            is_nullable: flag(row, "is_nullable"),
+            column_default: text(row, "column_default"),
+            character_maximum_length: int(row, "character_maximum_length"),
+            collation_name: text(row, "collation_name"),
+            comment: text(row, "comment").filter(|c| !c.is_empty()),
+            generated: text(row, "generated"),
+            generation_expression: text(row, "generation_expression").filter(|e| !e.is_empty()),
+            is_hidden: flag(row, "is_hidden"),
+            is_auto_increment: flag(row, "is_auto_increment"),
+            identity_generation: text(row, "identity_generation"),
+            default_kind: match text(row, "default_kind").as_deref() {
+                Some("Literal") => Some(DefaultKind::Literal),
+                Some("Expression") => Some(DefaultKind::Expression),
+                _ => text(row, "column_default").map(|d| DefaultKind::of(&d)),
+            },
+            allowed_values,
+        }
+    }
+}
+
+/// One entry per key column, in key order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PrimaryKey {
+    pub constraint_name: Option<String>,
+    pub table_schema: String,
+    pub table_name: String,
+    pub column_name: String,
+    /// 1 based position in the key.
+    pub ordinal_position: i64,
+}
+
+impl PrimaryKey {
+    pub fn from_row(row: &Value) -> Self {
+        PrimaryKey {
+            constraint_name: text(row, "constraint_name"),
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            column_name: text(row, "column_name").unwrap_or_default(),
+            ordinal_position: int(row, "ordinal_position").unwrap_or_default(),
+        }
+    }
+}
+
+/// One entry per referencing column, composite keys share `constraint_name`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ForeignKey {
+    pub constraint_name: String,
+    pub table_schema: String,
+    pub table_name: String,
+    pub column_name: String,
+    /// 1 based position in the key.
+    pub ordinal_position: i64,
+    pub referenced_schema: Option<String>,
+    pub referenced_table_name: String,
+    pub referenced_column_name: Option<String>,
+    pub update_rule: Option<String>,
+    pub delete_rule: Option<String>,
+}
+
+impl ForeignKey {
+    pub fn from_row(row: &Value) -> Self {
+        ForeignKey {
+            constraint_name: text(row, "constraint_name").unwrap_or_default(),
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            column_name: text(row, "column_name").unwrap_or_default(),
+            ordinal_position: int(row, "ordinal_position").unwrap_or_default(),
+            referenced_schema: text(row, "referenced_schema"),
+            referenced_table_name: text(row, "referenced_table_name").unwrap_or_default(),
+            referenced_column_name: text(row, "referenced_column_name"),
+            update_rule: text(row, "update_rule"),
+            delete_rule: text(row, "delete_rule"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Index {
+    pub index_name: String,
+    pub table_schema: String,
+    pub table_name: String,
+    /// Key columns in index order, expressions are kept as their SQL text
+    /// where the dialect exposes it.
+    pub columns: Vec<String>,
+    pub is_unique: bool,
+    pub is_primary: bool,
+    pub index_type: Option<String>,
+    pub definition: Option<String>,
+}
+
+impl Index {
+    /// Folds one row per indexed column, ordered by position, into one `Index`
+    /// per table and name (MySQL index names are only unique per table).
+    pub fn from_rows(rows: &[Value]) -> Vec<Self> {
+        let mut indices: Vec<Index> = vec![];
+        for row in rows {
+            let name = text(row, "index_name").unwrap_or_default();
+            let table = text(row, "table_name").unwrap_or_default();
+            let index = match indices
+                .iter_mut()
+                .find(|i| i.index_name == name && i.table_name == table)
+            {
+                Some(index) => index,
+                None => {
+                    indices.push(Index {
+                        index_name: name,
+                        table_schema: text(row, "table_schema").unwrap_or_default(),
+                        table_name: table,
+                        columns: vec![],
+                        is_unique: flag(row, "is_unique"),
+                        is_primary: flag(row, "is_primary"),
+                        index_type: text(row, "index_type"),
+                        definition: text(row, "definition"),
+                    });
+                    indices.last_mut().expect("Index was just pushed")
+                }
+            };
+            if let Some(column) = text(row, "column_name") {
+                index.columns.push(column);
+            }
+        }
+        indices
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum ConstraintType {
+    #[default]
+    Unique,
+    Check,
+}
+
+/// Unique and check constraints, primary and foreign keys have their own
+/// lookups.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Constraint {
+    pub constraint_name: String,
+    pub table_schema: String,
+    pub table_name: String,
+    pub constraint_type: ConstraintType,
+    /// Key columns of unique constraints, the column of column level checks.
+    pub columns: Vec<String>,
+    /// Expression of check constraints.
+    pub check_clause: Option<String>,
+}
+
+impl Constraint {
+    /// Folds one row per constrained column, ordered by position, into one
+    /// `Constraint` per name.
+    pub fn from_rows(rows: &[Value]) -> Vec<Self> {
+        let mut constraints: Vec<Constraint> = vec![];
+        for row in rows {
+            let name = text(row, "constraint_name").unwrap_or_default();
+            let constraint = match constraints.iter_mut().find(|c| c.constraint_name == name) {
+                Some(constraint) => constraint,
+                None => {
+                    constraints.push(Constraint {
+                        constraint_name: name,
+                        table_schema: text(row, "table_schema").unwrap_or_default(),
+                        table_name: text(row, "table_name").unwrap_or_default(),
+                        constraint_type: match text(row, "constraint_type").as_deref() {
+                            Some("CHECK") => ConstraintType::Check,
+                            _ => ConstraintType::Unique,
+                        },
+                        columns: vec![],
+                        check_clause: text(row, "check_clause"),
+                    });
+                    constraints.last_mut().expect("Constraint was just pushed")
+                }
+            };
+            if let Some(column) = text(row, "column_name") {
+                constraint.columns.push(column);
+            }
+        }
+        constraints
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Trigger {
+    pub trigger_name: String,
+    pub table_schema: String,
+    pub event_object_table: String,
+    /// `INSERT`, `UPDATE` or `DELETE`, Postgresql returns one entry per event.
+    pub event_manipulation: Option<String>,
+    /// `BEFORE`, `AFTER` or `INSTEAD OF`.
+    pub action_timing: Option<String>,
+    pub action_statement: Option<String>,
+}
+
+impl Trigger {
+    pub fn from_row(row: &Value) -> Self {
+        Trigger {
+            trigger_name: text(row, "trigger_name").unwrap_or_default(),
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            event_object_table: text(row, "event_object_table").unwrap_or_default(),
+            event_manipulation: text(row, "event_manipulation"),
+            action_timing: text(row, "action_timing"),
+            action_statement: text(row, "action_statement"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct View {
+    pub table_schema: String,
+    pub table_name: String,
+    pub view_definition: Option<String>,
+}
+
+impl View {
+    pub fn from_row(row: &Value) -> Self {
+        View {
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            view_definition: text(row, "view_definition"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum RoutineType {
+    #[default]
+    Function,
+    Procedure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Routine {
+    pub routine_schema: String,
+    pub routine_name: String,
+    pub routine_type: RoutineType,
+    /// Return type of functions.
+    pub data_type: Option<String>,
+    pub routine_definition: Option<String>,
+}
+
+impl Routine {
+    pub fn from_row(row: &Value) -> Self {
+        Routine {
+            routine_schema: text(row, "routine_schema").unwrap_or_default(),
+            routine_name: text(row, "routine_name").unwrap_or_default(),
+            routine_type: match text(row, "routine_type").as_deref() {
+                Some("PROCEDURE") => RoutineType::Procedure,
+                _ => RoutineType::Function,
+            },
+            data_type: text(row, "data_type").filter(|t| !t.is_empty()),
+            routine_definition: text(row, "routine_definition"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum TableType {
+    #[default]
+    Table,
+    View,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Table {
+    pub table_schema: String,
+    pub table_name: String,
+    pub table_type: TableType,
+    pub comment: Option<String>,
+    /// Storage engine, MySQL and MariaDB only.
+    pub engine: Option<String>,
+    pub row_format: Option<String>,
+    /// MariaDB table keeping the history of its rows, see [`SystemTime`].
+    pub system_versioned: bool,
+    /// Module of a SQLite virtual table, e.g. `fts5`.
+    pub virtual_module: Option<String>,
+    /// SQLite table maintained by a virtual table, e.g. `docs_data` of fts5.
+    pub shadow: bool,
+    pub without_rowid: bool,
+    /// SQLite table enforcing its column types.
+    pub strict: bool,
+}
+
+impl Table {
+    pub fn from_row(row: &Value) -> Self {
+        Table {
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            table_type: match text(row, "table_type").as_deref() {
+                Some("View") => TableType::View,
+                _ => TableType::Table,
+            },
+            comment: text(row, "comment").filter(|c| !c.is_empty()),
+            engine: text(row, "engine"),
+            row_format: text(row, "row_format"),
+            system_versioned: flag(row, "system_versioned"),
+            virtual_module: text(row, "virtual_module"),
+            shadow: flag(row, "shadow"),
+            without_rowid: flag(row, "without_rowid"),
+            strict: flag(row, "strict"),
+        }
+    }
+}
+
+/// Object kinds `get_object_ddl` rebuilds the definition of, not every
+/// dialect has all of them.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ObjectKind {
+    Table,
+    View,
+    MaterializedView,
+    Function,
+    Procedure,
+    Trigger,
+    Index,
+    Sequence,
+    Event,
+}
+
+/// Rows of a system-versioned table to read, timestamps are passed as
+/// `YYYY-MM-DD hh:mm:ss[.ffffff]`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SystemTime {
+    /// Current and historical rows.
+    All,
+    AsOf {
+        at: String,
+    },
+    Between {
+        from: String,
+        to: String,
+    },
+}
+
+/// Scheduled event, times are in the event's time zone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Event {
+    pub event_schema: String,
+    pub event_name: String,
+    pub definer: Option<String>,
+    pub time_zone: Option<String>,
+    /// `ONE TIME` or `RECURRING`.
+    pub event_type: Option<String>,
+    /// Run time of a one time event.
+    pub execute_at: Option<String>,
+    /// Schedule of a recurring event, e.g. `1` `DAY`.
+    pub interval_value: Option<String>,
+    pub interval_field: Option<String>,
+    pub starts: Option<String>,
+    pub ends: Option<String>,
+    /// `ENABLED`, `DISABLED` or `SLAVESIDE_DISABLED`.
+    pub status: Option<String>,
+    /// `PRESERVE` keeps the event after it expired.
+    pub on_completion: Option<String>,
+    pub last_executed: Option<String>,
+    pub event_definition: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Event {
+    pub fn from_row(row: &Value) -> Self {
+        Event {
+            event_schema: text(row, "event_schema").unwrap_or_default(),
+            event_name: text(row, "event_name").unwrap_or_default(),
+            definer: text(row, "definer"),
+            time_zone: text(row, "time_zone"),
+            event_type: text(row, "event_type"),
+            execute_at: text(row, "execute_at"),
+            interval_value: text(row, "interval_value"),
+            interval_field: text(row, "interval_field"),
+            starts: text(row, "starts"),
+            ends: text(row, "ends"),
+            status: text(row, "status"),
+            on_completion: text(row, "on_completion"),
+            last_executed: text(row, "last_executed"),
+            event_definition: text(row, "event_definition"),
+            comment: text(row, "comment").filter(|c| !c.is_empty()),
+        }
+    }
+}
+
+/// One entry per partition, or per subpartition when the table has them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Partition {
+    pub table_schema: String,
+    pub table_name: String,
+    pub partition_name: String,
+    pub subpartition_name: Option<String>,
+    /// 1 based position in the table.
+    pub ordinal_position: i64,
+    /// `RANGE`, `LIST`, `HASH`, `KEY`, ... (with `COLUMNS` where used).
+    pub partition_method: Option<String>,
+    pub partition_expression: Option<String>,
+    /// Bound of the partition, e.g. `100` of `VALUES LESS THAN (100)`.
+    pub partition_description: Option<String>,
+    /// Estimate from the table statistics.
+    pub table_rows: Option<i64>,
+    pub comment: Option<String>,
+}
+
+impl Partition {
+    pub fn from_row(row: &Value) -> Self {
+        Partition {
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            partition_name: text(row, "partition_name").unwrap_or_default(),
+            subpartition_name: text(row, "subpartition_name"),
+            ordinal_position: int(row, "ordinal_position").unwrap_or_default(),
+            partition_method: text(row, "partition_method"),
+            partition_expression: text(row, "partition_expression"),
+            partition_description: text(row, "partition_description"),
+            table_rows: int(row, "table_rows"),
+            comment: text(row, "comment").filter(|c| !c.is_empty()),
+        }
+    }
+}
+
+/// A page of a schema's catalog, everything except `routines` belongs to
+/// the tables of the page.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SchemaSnapshot {
+    pub schema: String,
+    pub tables: Vec<Table>,
+    pub columns: Vec<Column>,
+    pub primary_keys: Vec<PrimaryKey>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indices: Vec<Index>,
+    pub triggers: Vec<Trigger>,
+    pub views: Vec<View>,
+    /// Only filled on the first page.
+    pub routines: Vec<Routine>,
+    /// Table name to pass as `after` for the next page, `None` on the last one.
+    pub next: Option<String>,
+}
+
+/// Sequence values are kept as text, bigint sequences exceed the integer
+/// precision of the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Sequence {
+    pub sequence_schema: String,
+    pub sequence_name: String,
+    pub data_type: Option<String>,
+    pub start_value: Option<String>,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub increment: Option<String>,
+    pub cycle: bool,
+    /// Last value handed out, `None` before the first `nextval`.
+    pub last_value: Option<String>,
+    /// Column the sequence belongs to, e.g. the one of a `serial`.
+    pub owned_by: Option<String>,
+}
+
+impl Sequence {
+    pub fn from_row(row: &Value) -> Self {
+        Sequence {
+            sequence_schema: text(row, "sequence_schema").unwrap_or_default(),
+            sequence_name: text(row, "sequence_name").unwrap_or_default(),
+            data_type: text(row, "data_type"),
+            start_value: text(row, "start_value"),
+            min_value: text(row, "min_value"),
+            max_value: text(row, "max_value"),
+            increment: text(row, "increment"),
+            cycle: flag(row, "cycle"),
+            last_value: text(row, "last_value"),
+            owned_by: text(row, "owned_by"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct EnumType {
+    pub type_schema: String,
+    pub type_name: String,
+    /// Labels in sort order.
+    pub labels: Vec<String>,
+}
+
+impl EnumType {
+    pub fn from_row(row: &Value) -> Self {
+        EnumType {
+            type_schema: text(row, "type_schema").unwrap_or_default(),
+            type_name: text(row, "type_name").unwrap_or_default(),
+            labels: texts(row, "labels"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Domain {
+    pub domain_schema: String,
+    pub domain_name: String,
+    /// Base type with modifiers.
+    pub data_type: String,
+    pub is_nullable: bool,
+    pub column_default: Option<String>,
+    /// `CHECK (...)` clauses.
+    pub check_constraints: Vec<String>,
+}
+
+impl Domain {
+    pub fn from_row(row: &Value) -> Self {
+        Domain {
+            domain_schema: text(row, "domain_schema").unwrap_or_default(),
+            domain_name: text(row, "domain_name").unwrap_or_default(),
+            data_type: text(row, "data_type").unwrap_or_default(),
+            is_nullable: flag(row, "is_nullable"),
+            column_default: text(row, "column_default"),
+            check_constraints: texts(row, "check_constraints"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CompositeAttribute {
+    pub attribute_name: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CompositeType {
+    pub type_schema: String,
+    pub type_name: String,
+    pub attributes: Vec<CompositeAttribute>,
+}
+
+impl CompositeType {
+    pub fn from_row(row: &Value) -> Self {
+        CompositeType {
+            type_schema: text(row, "type_schema").unwrap_or_default(),
+            type_name: text(row, "type_name").unwrap_or_default(),
+            attributes: serde_json::from_value(row["attributes"].clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// An available extension, `installed_version` is set once it is created.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Extension {
+    pub extension_name: String,
+    pub default_version: Option<String>,
+    pub installed_version: Option<String>,
+    /// Schema the installed extension lives in.
+    pub extension_schema: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Extension {
+    pub fn from_row(row: &Value) -> Self {
+        Extension {
+            extension_name: text(row, "extension_name").unwrap_or_default(),
+            default_version: text(row, "default_version"),
+            installed_version: text(row, "installed_version"),
+            extension_schema: text(row, "extension_schema"),
+            comment: text(row, "comment"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MaterializedView {
+    pub table_schema: String,
+    pub table_name: String,
+    pub view_definition: Option<String>,
+    /// `false` after `WITH NO DATA` until the first refresh.
+    pub is_populated: bool,
+    /// A plain unique index allows `REFRESH ... CONCURRENTLY`.
+    pub has_unique_index: bool,
+}
+
+impl MaterializedView {
+    pub fn from_row(row: &Value) -> Self {
+        MaterializedView {
+            table_schema: text(row, "table_schema").unwrap_or_default(),
+            table_name: text(row, "table_name").unwrap_or_default(),
+            view_definition: text(row, "view_definition"),
+            is_populated: flag(row, "is_populated"),
+            has_unique_index: flag(row, "has_unique_index"),
+        }
+    }
+}
+
+/// A parent and child table, either by `INHERITS` or as a declarative
+/// partition.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Inheritance {
+    pub parent_schema: String,
+    pub parent_name: String,
+    pub child_schema: String,
+    pub child_name: String,
+    /// `FOR VALUES ...` of a partition, `None` for plain inheritance.
+    pub partition_bound: Option<String>,
+    /// Partition key of a partitioned parent, e.g. `RANGE (created_at)`.
+    pub partition_key: Option<String>,
+}
+
+impl Inheritance {
+    pub fn from_row(row: &Value) -> Self {
+        Inheritance {
+            parent_schema: text(row, "parent_schema").unwrap_or_default(),
+            parent_name: text(row, "parent_name").unwrap_or_default(),
+            child_schema: text(row, "child_schema").unwrap_or_default(),
+            child_name: text(row, "child_name").unwrap_or_default(),
+            partition_bound: text(row, "partition_bound"),
+            partition_key: text(row, "partition_key"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TableStructure {
+    pub table: String,
+    pub columns: Vec<Column>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indices: Vec<Index>,
+    pub triggers: Vec<Trigger>,
+    pub primary_key: Vec<PrimaryKey>,
+    pub constraints: Vec<Constraint>,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{type_values, Column, DefaultKind, Index};
+
+    #[test]
+    fn test_from_rows() {
+        let column = Column::from_row(&json!({
+            "table_name": "t",
+            "column_name": "id",
+            "ordinal_position": "1",
+            "column_type": "VARCHAR(20)",
+            "is_nullable": 0,
+        }));
+        assert_eq!(column.data_type, "varchar");
+        assert_eq!(column.ordinal_position, 1);
+        assert!(!column.is_nullable);
+
+        let indices = Index::from_rows(&[
+            json!({"index_name": "a_b", "column_name": "a", "is_unique": true}),
+            json!({"index_name": "a_b", "column_name": "b", "is_unique": true}),
+            json!({"index_name": "c", "column_name": "c", "is_unique": "NO"}),
+        ]);
+        assert_eq!(indices.len(), 2);
+        assert_eq!(indices[0].columns, vec!["a", "b"]);
+        assert!(indices[0].is_unique && !indices[1].is_unique);
+    }
+
+    #[test]
+    fn test_column_behavior() {
+        assert_eq!(
+            DefaultKind::of("'a'::character varying"),
+            DefaultKind::Literal
+        );
+        assert_eq!(DefaultKind::of("-1.5"), DefaultKind::Literal);
+        assert_eq!(DefaultKind::of("NULL"), DefaultKind::Literal);
+        assert_eq!(DefaultKind::of("now()"), DefaultKind::Expression);
+        assert_eq!(
+            DefaultKind::of("nextval('t_id_seq'::regclass)"),
+            DefaultKind::Expression
+        );
+        assert_eq!(
+            type_values("enum('a','it''s','b,c')"),
+            vec!["a", "it's", "b,c"]
+        );
+        assert!(type_values("varchar(10)").is_empty());
+    }
+}
@@ -15,6 +15,17 @@ pub enum ConnectionPool {
     Sqlite(SqlitePool),
 }
 
+impl ConnectionPool {
+    /// MariaDB shares the MySQL driver, its own variant selects the queries
+    /// that differ between the two.
+    pub fn mysql(dialect: &Dialect, pool: sqlx::MySqlPool) -> Self {
+        match dialect {
+            Dialect::MariaDB => ConnectionPool::MariaDB(pool),
+            _ => ConnectionPool::Mysql(pool),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum Dialect {
     Mysql,
@@ -34,6 +45,17 @@ impl fmt::Display for Dialect {
     }
 }
 
+impl Dialect {
+    /// sqlparser has no MariaDB dialect, its syntax is MySQL's.
+    pub fn parser_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        match self {
+            Dialect::Mysql | Dialect::MariaDB => Box::new(sqlparser::dialect::MySqlDialect {}),
+            Dialect::Postgresql => Box::new(sqlparser::dialect::PostgreSqlDialect {}),
+            Dialect::Sqlite => Box::new(sqlparser::dialect::SQLiteDialect {}),
+        }
+    }
+}
+
 impl FromSql for Dialect {
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         let s: String = String::column_result(value)?;
@@ -84,6 +106,9 @@ pub type Credentials = HashMap<String, String>;
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct Metadata {
     pub sensitive: bool,
+    /// Only queries are run, the sessions are made read-only as well.
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -99,6 +124,15 @@ pub struct ConnectionConfig {
 }
 
 impl ConnectionConfig {
+    /// The read-only flag, or on Postgres the `transaction_read_write`
+    /// credential turned off.
+    pub fn read_only(&self) -> bool {
+        let read_write = self.credentials.get("transaction_read_write");
+        self.metadata.read_only
+            || (self.dialect == Dialect::Postgresql
+                && read_write.is_some_and(|v| ["false", "off", "0"].contains(&v.as_str())))
+    }
+
     pub fn new(
         dialect: Dialect,
         mode: Mode,
@@ -164,6 +198,7 @@ impl ConnectionConfig {
                     "user",
                     "password",
                     "db_name",
+                    "schema",
                     "options",
                     "application_name",
                     "sslmode",
@@ -184,6 +219,11 @@ impl ConnectionConfig {
                     "ssh_key",
                 ];
                 credentials.retain(|k, _| allowed_keys.contains(&k.as_str()));
+                let schema = credentials
+                    .get("schema")
+                    .filter(|s| !s.is_empty())
+                    .cloned()
+                    .unwrap_or("public".to_string());
                 Ok(ConnectionConfig {
                     id: Uuid::new_v4(),
                     dialect,
@@ -191,7 +231,7 @@ impl ConnectionConfig {
                     credentials,
                     name: name.to_string(),
                     color: color.to_string(),
-                    schema: "public".to_string(),
+                    schema,
                     metadata,
                 })
             }
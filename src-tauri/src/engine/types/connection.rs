@@ -1,55 +1,152 @@
+use std::sync::Arc;
+
 use anyhow::Result;
+use futures::try_join;
 use serde_json::Value;
 
-use super::config::{ConnectionConfig, ConnectionPool};
-use super::result::{ResultSet, TableMetadata};
+use super::catalog::{
+    Column, CompositeType, Constraint, Domain, EnumType, Event, Extension, ForeignKey, Index,
+    Inheritance, MaterializedView, ObjectKind, Partition, PrimaryKey, Routine, RoutineType,
+    SchemaSnapshot, Sequence, SystemTime, TableStructure, Trigger, View,
+};
+use super::config::{ConnectionConfig, ConnectionPool, Dialect};
+use super::edit::BoundStatement;
+use super::monitor::{blocking_tree, BlockingNode, Session, TopQuery, TopQueryOrder};
+use super::plan::QueryPlan;
+use super::result::{ResultSet, TableMetadata, TxResult};
 use crate::database::QueryType;
+use crate::engine::cache::{changes_schema, MetadataCache};
+use crate::engine::dry_run;
 use crate::engine::exec;
+use crate::engine::provenance::{resolve_provenance, single_table_select};
+use crate::engine::session::SessionSchema;
+
+/// Tables per snapshot page while warming the metadata cache.
+const WARM_PAGE_SIZE: usize = 1000;
 
 #[derive(Debug, Clone)]
 pub struct InitiatedConnection {
     pub config: ConnectionConfig,
     pub pool: ConnectionPool,
-    pub schema: String,
+    /// Default schema of the pooled sessions, shared by every clone.
+    pub schema: Arc<SessionSchema>,
+    /// Shared by every clone, so all handlers see the same cached catalog.
+    pub cache: Arc<MetadataCache>,
 }
 
 impl InitiatedConnection {
     pub fn get_schema(&self) -> String {
-        self.schema.clone()
+        self.schema.get()
     }
 
+    /// Makes `schema` the default of all pooled sessions without reconnecting.
+    /// A SQLite schema is the database file, switching it needs a new pool.
     pub fn set_schema(mut self, schema: String) -> Self {
-        self.schema = schema.clone();
         match self.config.dialect {
-            super::config::Dialect::Mysql | super::config::Dialect::MariaDB => {
-                self.config.credentials.insert("db_name".to_string(), schema);
+            Dialect::Mysql | Dialect::MariaDB => {
+                self.config
+                    .credentials
+                    .insert("db_name".to_string(), schema.clone());
             }
-            super::config::Dialect::Postgresql => {
-                self.config.credentials.insert("schema".to_string(), schema);
+            Dialect::Postgresql => {
+                self.config
+                    .credentials
+                    .insert("schema".to_string(), schema.clone());
             }
-            super::config::Dialect::Sqlite => {
-                self.config.credentials.insert("path".to_string(), schema);
+            Dialect::Sqlite => {
+                self.config
+                    .credentials
+                    .insert("path".to_string(), schema.clone());
             }
         };
+        self.config.schema = schema.clone();
+        let mut in_use = 0;
+        if let ConnectionPool::Mysql(pool) | ConnectionPool::MariaDB(pool) = &self.pool {
+            pool.set_connect_options(pool.connect_options().as_ref().clone().database(&schema));
+            in_use = (pool.size() as usize).saturating_sub(pool.num_idle());
+        }
+        self.schema.set(schema, in_use);
         self
     }
 
-    pub async fn get_table_structure(&self, table: String) -> Result<Value> {
-        exec::get_table_structure(self, table).await
+    pub async fn get_table_structure(&self, schema: &str, table: String) -> Result<TableStructure> {
+        let (columns, foreign_keys, triggers, indices, primary_key, constraints) = try_join!(
+            self.get_columns(schema, Some(&table)),
+            self.get_foreign_keys(schema, &table),
+            self.get_triggers(schema, Some(&table)),
+            self.get_indices(schema, &table),
+            self.get_primary_key(schema, &table),
+            self.get_constraints(schema, &table),
+        )?;
+        Ok(TableStructure {
+            table,
+            columns,
+            foreign_keys,
+            indices,
+            triggers,
+            primary_key,
+            constraints,
+        })
+    }
+
+    pub async fn get_indices(&self, schema: &str, table: &str) -> Result<Vec<Index>> {
+        if let Some(indices) = self.cache.table(schema, table, |t| t.indices.clone()) {
+            return Ok(indices);
+        }
+        let generation = self.cache.generation();
+        let indices = exec::get_indices(self, schema, table).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.indices = Some(indices.clone())
+        });
+        Ok(indices)
+    }
+
+    pub async fn get_constraints(&self, schema: &str, table: &str) -> Result<Vec<Constraint>> {
+        if let Some(constraints) = self.cache.table(schema, table, |t| t.constraints.clone()) {
+            return Ok(constraints);
+        }
+        let generation = self.cache.generation();
+        let constraints = exec::get_constraints(self, schema, table).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.constraints = Some(constraints.clone())
+        });
+        Ok(constraints)
     }
 
-    pub async fn get_indices(&self, table: &str) -> Result<Vec<Value>> {
-        exec::get_indices(self, table).await
+    pub async fn get_row_estimate(&self, schema: &str, table: &str) -> Result<Option<f64>> {
+        exec::get_row_estimate(self, schema, table).await
     }
 
-    pub async fn get_columns(&self, table: Option<&str>) -> Result<Vec<Value>> {
-        exec::get_columns(self, table).await
+    pub async fn get_columns(&self, schema: &str, table: Option<&str>) -> Result<Vec<Column>> {
+        let Some(table) = table else {
+            let cached = self.cache.schema(schema, |s| {
+                s.tables
+                    .values()
+                    .flat_map(|t| t.columns.clone().unwrap_or_default())
+                    .collect()
+            });
+            return match cached {
+                Some(columns) => Ok(columns),
+                None => exec::get_columns(self, schema, None).await,
+            };
+        };
+        if let Some(columns) = self.cache.table(schema, table, |t| t.columns.clone()) {
+            return Ok(columns);
+        }
+        let generation = self.cache.generation();
+        let columns = exec::get_columns(self, schema, Some(table)).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.columns = Some(columns.clone())
+        });
+        Ok(columns)
     }
 
+    /// Metadata of a table in the default schema, for query results.
     pub async fn get_table_metadata(&self, table: &str) -> Result<TableMetadata> {
-        let foreign_keys = self.get_foreign_keys(table).await?;
-        let primary_key = self.get_primary_key(table).await?;
-        let columns = self.get_columns(Some(table)).await?;
+        let schema = self.get_schema();
+        let foreign_keys = self.get_foreign_keys(&schema, table).await?;
+        let primary_key = self.get_primary_key(&schema, table).await?;
+        let columns = self.get_columns(&schema, Some(table)).await?;
         Ok(TableMetadata {
             table: table.to_string(),
             foreign_keys: Some(foreign_keys),
@@ -58,39 +155,280 @@ impl InitiatedConnection {
         })
     }
 
-    pub async fn get_foreign_keys(&self, table: &str) -> Result<Vec<Value>> {
-        exec::get_foreign_keys(self, table).await
+    pub async fn get_foreign_keys(&self, schema: &str, table: &str) -> Result<Vec<ForeignKey>> {
+        if let Some(keys) = self.cache.table(schema, table, |t| t.foreign_keys.clone()) {
+            return Ok(keys);
+        }
+        let generation = self.cache.generation();
+        let keys = exec::get_foreign_keys(self, schema, table).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.foreign_keys = Some(keys.clone())
+        });
+        Ok(keys)
     }
 
-    pub async fn get_primary_key(&self, table: &str) -> Result<Vec<Value>> {
-        exec::get_primary_key(self, table).await
+    pub async fn get_primary_key(&self, schema: &str, table: &str) -> Result<Vec<PrimaryKey>> {
+        if let Some(key) = self.cache.table(schema, table, |t| t.primary_key.clone()) {
+            return Ok(key);
+        }
+        let generation = self.cache.generation();
+        let key = exec::get_primary_key(self, schema, table).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.primary_key = Some(key.clone())
+        });
+        Ok(key)
     }
 
-    pub async fn get_functions(&self) -> Result<Vec<Value>> {
-        exec::get_functions(self).await
+    fn cached_routines(&self, schema: &str, routine_type: RoutineType) -> Option<Vec<Routine>> {
+        self.cache.schema(schema, |s| {
+            s.routines
+                .iter()
+                .filter(|r| r.routine_type == routine_type)
+                .cloned()
+                .collect()
+        })
+    }
+
+    pub async fn get_functions(&self, schema: &str) -> Result<Vec<Routine>> {
+        match self.cached_routines(schema, RoutineType::Function) {
+            Some(functions) => Ok(functions),
+            None => exec::get_functions(self, schema).await,
+        }
     }
 
-    pub async fn get_procedures(&self) -> Result<Vec<Value>> {
-        exec::get_procedures(self).await
+    pub async fn get_procedures(&self, schema: &str) -> Result<Vec<Routine>> {
+        match self.cached_routines(schema, RoutineType::Procedure) {
+            Some(procedures) => Ok(procedures),
+            None => exec::get_procedures(self, schema).await,
+        }
     }
 
-    pub async fn get_triggers(&self) -> Result<Vec<Value>> {
-        exec::get_triggers(self).await
+    pub async fn get_triggers(&self, schema: &str, table: Option<&str>) -> Result<Vec<Trigger>> {
+        let Some(table) = table else {
+            let cached = self.cache.schema(schema, |s| {
+                s.tables
+                    .values()
+                    .flat_map(|t| t.triggers.clone().unwrap_or_default())
+                    .collect()
+            });
+            return match cached {
+                Some(triggers) => Ok(triggers),
+                None => exec::get_triggers(self, schema, None).await,
+            };
+        };
+        if let Some(triggers) = self.cache.table(schema, table, |t| t.triggers.clone()) {
+            return Ok(triggers);
+        }
+        let generation = self.cache.generation();
+        let triggers = exec::get_triggers(self, schema, Some(table)).await?;
+        self.cache.set_table(schema, table, generation, |t| {
+            t.triggers = Some(triggers.clone())
+        });
+        Ok(triggers)
     }
 
     pub async fn get_schemas(&self) -> Result<Vec<Value>> {
         exec::get_schemas(self).await
     }
 
-    pub async fn get_views(&self) -> Result<Vec<Value>> {
-        exec::get_views(self).await
+    pub async fn get_views(&self, schema: &str) -> Result<Vec<View>> {
+        let cached = self.cache.schema(schema, |s| {
+            s.tables.values().filter_map(|t| t.view.clone()).collect()
+        });
+        match cached {
+            Some(views) => Ok(views),
+            None => exec::get_views(self, schema).await,
+        }
+    }
+
+    pub async fn get_sequences(&self, schema: &str) -> Result<Vec<Sequence>> {
+        exec::get_sequences(self, schema).await
+    }
+
+    pub async fn get_enums(&self, schema: &str) -> Result<Vec<EnumType>> {
+        exec::get_enums(self, schema).await
+    }
+
+    pub async fn get_domains(&self, schema: &str) -> Result<Vec<Domain>> {
+        exec::get_domains(self, schema).await
+    }
+
+    pub async fn get_composite_types(&self, schema: &str) -> Result<Vec<CompositeType>> {
+        exec::get_composite_types(self, schema).await
+    }
+
+    pub async fn get_materialized_views(&self, schema: &str) -> Result<Vec<MaterializedView>> {
+        exec::get_materialized_views(self, schema).await
+    }
+
+    pub async fn refresh_materialized_view(
+        &self,
+        schema: &str,
+        name: &str,
+        concurrently: bool,
+    ) -> Result<()> {
+        exec::refresh_materialized_view(self, schema, name, concurrently).await
+    }
+
+    pub async fn get_extensions(&self) -> Result<Vec<Extension>> {
+        exec::get_extensions(self).await
+    }
+
+    pub async fn get_inheritance(
+        &self,
+        schema: &str,
+        table: Option<&str>,
+    ) -> Result<Vec<Inheritance>> {
+        exec::get_inheritance(self, schema, table).await
+    }
+
+    pub async fn get_events(&self, schema: &str) -> Result<Vec<Event>> {
+        exec::get_events(self, schema).await
+    }
+
+    pub async fn get_partitions(
+        &self,
+        schema: &str,
+        table: Option<&str>,
+    ) -> Result<Vec<Partition>> {
+        exec::get_partitions(self, schema, table).await
+    }
+
+    pub async fn get_modules(&self) -> Result<Vec<String>> {
+        exec::get_modules(self).await
+    }
+
+    pub async fn get_table_history(
+        &self,
+        schema: &str,
+        table: &str,
+        time: &SystemTime,
+        limit: Option<usize>,
+    ) -> Result<ResultSet> {
+        exec::get_table_history(self, schema, table, time, limit).await
+    }
+
+    pub async fn get_object_ddl(
+        &self,
+        schema: &str,
+        object: &str,
+        kind: ObjectKind,
+    ) -> Result<String> {
+        exec::get_object_ddl(self, schema, object, kind).await
+    }
+
+    pub async fn get_schema_snapshot(
+        &self,
+        schema: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<SchemaSnapshot> {
+        if let Some(snapshot) = self.cache.snapshot(schema, after, limit) {
+            return Ok(snapshot);
+        }
+        let generation = self.cache.generation();
+        let snapshot = exec::get_schema_snapshot(self, schema, after, limit).await?;
+        self.cache
+            .add_snapshot(schema, after.is_none(), &snapshot, generation);
+        Ok(snapshot)
+    }
+
+    /// Loads the whole catalog of the current schema into the metadata cache.
+    pub async fn warm_metadata(&self) -> Result<()> {
+        let schema = self.get_schema();
+        let generation = self.cache.generation();
+        let mut after: Option<String> = None;
+        let mut order = vec![];
+        loop {
+            let snapshot =
+                exec::get_schema_snapshot(self, &schema, after.as_deref(), WARM_PAGE_SIZE).await?;
+            self.cache
+                .add_snapshot(&schema, after.is_none(), &snapshot, generation);
+            order.extend(snapshot.tables.iter().map(|t| t.table_name.clone()));
+            match snapshot.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        self.cache.set_complete(&schema, order, generation);
+        Ok(())
+    }
+
+    /// Drops the cached catalog and loads it again.
+    pub async fn refresh_metadata(&self) -> Result<()> {
+        self.cache.invalidate();
+        self.warm_metadata().await
+    }
+
+    /// Runs a single statement. With `editable` the origin of the columns of
+    /// a single table `SELECT` is resolved so the grid can edit its rows.
+    pub async fn execute_query(&self, q: &str, t: QueryType, editable: bool) -> Result<ResultSet> {
+        let mut result = exec::execute_query(self, q, t).await;
+        // a failed statement may still have changed the catalog (MySQL DDL
+        // is not transactional)
+        if changes_schema(&self.config.dialect, q) {
+            self.cache.invalidate();
+        }
+        if let (Ok(set), QueryType::Select, true) = (&mut result, t, editable) {
+            // editability is best effort, the result is returned regardless
+            if single_table_select(&self.config.dialect, q) {
+                let _ = resolve_provenance(self, q, set).await;
+            }
+        }
+        result
+    }
+
+    pub async fn explain_query(&self, sql: &str, analyze: bool) -> Result<QueryPlan> {
+        exec::explain_query(self, sql, analyze).await
+    }
+
+    /// Rows affected by each statement, see [`exec::execute_bound_tx`].
+    pub async fn execute_bound_tx(&self, statements: Vec<BoundStatement>) -> Result<Vec<u64>> {
+        exec::execute_bound_tx(self, statements).await
+    }
+
+    pub async fn execute_tx(&self, queries: Vec<&str>) -> Result<TxResult> {
+        let ddl = queries
+            .iter()
+            .any(|q| changes_schema(&self.config.dialect, q));
+        let result = exec::execute_tx(self, queries, false).await;
+        if ddl {
+            self.cache.invalidate();
+        }
+        result
+    }
+
+    /// Runs data-modifying statements and rolls them back, see [`dry_run`].
+    pub async fn dry_run(&self, queries: Vec<&str>) -> Result<TxResult> {
+        dry_run::dry_run(self, queries).await
+    }
+
+    pub async fn get_top_queries(
+        &self,
+        order: TopQueryOrder,
+        limit: usize,
+    ) -> Result<Vec<TopQuery>> {
+        exec::get_top_queries(self, order, limit).await
+    }
+
+    pub async fn get_sessions(&self) -> Result<Vec<Session>> {
+        exec::get_sessions(self).await
+    }
+
+    pub async fn cancel_session(&self, id: i64) -> Result<()> {
+        exec::cancel_session(self, id).await
     }
 
-    pub async fn execute_query(&self, q: &str, t: QueryType) -> Result<ResultSet> {
-        exec::execute_query(self, q, t).await
+    pub async fn terminate_session(&self, id: i64) -> Result<()> {
+        exec::terminate_session(self, id).await
     }
 
-    pub async fn execute_tx(&self, queries: Vec<&str>) -> Result<()> {
-        exec::execute_tx(self, queries).await
+    pub async fn get_lock_tree(&self) -> Result<Vec<BlockingNode>> {
+        let waits = exec::get_lock_waits(self).await?;
+        if waits.is_empty() {
+            return Ok(vec![]);
+        }
+        let sessions = self.get_sessions().await?;
+        Ok(blocking_tree(&sessions, &waits))
     }
 }
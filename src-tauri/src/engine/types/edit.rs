@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Row edits from the results grid, rows are identified by their primary key
+/// (or a unique key) taken from `row`, the values as they were read.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RowEdits {
+    #[serde(default)]
+    pub updates: Vec<RowUpdate>,
+    #[serde(default)]
+    pub inserts: Vec<Map<String, Value>>,
+    #[serde(default)]
+    pub deletes: Vec<RowDelete>,
+    /// Only updates rows whose changed columns still have the values in
+    /// `row`, other rows are reported as conflicts.
+    #[serde(default)]
+    pub verify: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RowUpdate {
+    pub row: Map<String, Value>,
+    pub values: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RowDelete {
+    pub row: Map<String, Value>,
+}
+
+/// A statement with its parameters, `expect_row` statements must affect
+/// exactly one row or the transaction is rolled back.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BoundStatement {
+    pub sql: String,
+    pub params: Vec<Value>,
+    pub expect_row: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EditKind {
+    Update,
+    Delete,
+}
+
+/// An update or delete that didn't find its row, because it was changed or
+/// removed since it was read.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowConflict {
+    pub kind: EditKind,
+    /// Position in `updates` or `deletes`.
+    pub index: usize,
+    pub key: Map<String, Value>,
+    pub affected_rows: u64,
+}
+
+/// Edits are applied all together or not at all, `applied` is false when
+/// there were conflicts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct RowEditsResult {
+    pub applied: bool,
+    pub affected_rows: u64,
+    pub conflicts: Vec<RowConflict>,
+}
@@ -1,3 +1,10 @@
+pub mod advice;
+pub mod alter;
+pub mod catalog;
 pub mod config;
 pub mod connection;
+pub mod edit;
+pub mod monitor;
+pub mod plan;
 pub mod result;
+pub mod value;
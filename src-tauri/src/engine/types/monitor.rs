@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::value::json_to_f64;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum TopQueryOrder {
+    #[default]
+    TotalTime,
+    Calls,
+    MeanTime,
+    Rows,
+}
+
+impl TopQueryOrder {
+    /// Column alias shared by the per dialect top query statements.
+    pub fn column(&self) -> &'static str {
+        match self {
+            TopQueryOrder::TotalTime => "total_time_ms",
+            TopQueryOrder::Calls => "calls",
+            TopQueryOrder::MeanTime => "mean_time_ms",
+            TopQueryOrder::Rows => "row_count",
+        }
+    }
+}
+
+/// Aggregated statistics of one normalized statement, literals are replaced
+/// by placeholders so `query` can be opened in the editor as is.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopQuery {
+    pub query_id: Option<String>,
+    pub query: String,
+    pub database: Option<String>,
+    pub calls: f64,
+    pub total_time_ms: f64,
+    pub mean_time_ms: f64,
+    pub rows: f64,
+}
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    match &row[key] {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        v => Some(v.to_string()),
+    }
+}
+
+impl TopQuery {
+    pub fn from_row(row: &Value) -> Self {
+        let text = |key: &str| text(row, key);
+        TopQuery {
+            query_id: text("query_id"),
+            query: text("query").unwrap_or_default(),
+            database: text("database"),
+            calls: json_to_f64(&row["calls"]).unwrap_or_default(),
+            total_time_ms: json_to_f64(&row["total_time_ms"]).unwrap_or_default(),
+            mean_time_ms: json_to_f64(&row["mean_time_ms"]).unwrap_or_default(),
+            rows: json_to_f64(&row["row_count"]).unwrap_or_default(),
+        }
+    }
+}
+
+/// A server session, `id` is what cancel and terminate expect (the backend
+/// pid on Postgresql, the processlist id on MySQL).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Session {
+    pub id: i64,
+    pub user: Option<String>,
+    pub database: Option<String>,
+    pub client: Option<String>,
+    pub application: Option<String>,
+    pub state: Option<String>,
+    pub duration_ms: Option<f64>,
+    pub wait_event: Option<String>,
+    pub query: Option<String>,
+}
+
+impl Session {
+    pub fn from_row(row: &Value) -> Self {
+        let text = |key: &str| text(row, key);
+        Session {
+            id: json_to_f64(&row["id"]).unwrap_or_default() as i64,
+            user: text("user_name"),
+            database: text("database"),
+            client: text("client"),
+            application: text("application"),
+            state: text("state"),
+            duration_ms: json_to_f64(&row["duration_ms"]),
+            wait_event: text("wait_event"),
+            query: text("query"),
+        }
+    }
+}
+
+/// Session `waiting` is waiting for a lock session `blocking` holds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockWait {
+    pub waiting: i64,
+    pub blocking: i64,
+    pub relation: Option<String>,
+    pub lock_mode: Option<String>,
+    pub wait_ms: Option<f64>,
+}
+
+impl LockWait {
+    pub fn from_row(row: &Value) -> Self {
+        LockWait {
+            waiting: json_to_f64(&row["waiting"]).unwrap_or_default() as i64,
+            blocking: json_to_f64(&row["blocking"]).unwrap_or_default() as i64,
+            relation: text(row, "relation"),
+            lock_mode: text(row, "lock_mode"),
+            wait_ms: json_to_f64(&row["wait_ms"]),
+        }
+    }
+}
+
+/// A session and the sessions waiting on it. The lock fields describe what
+/// this session waits for on its parent, they are empty for root blockers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlockingNode {
+    pub session: Session,
+    pub relation: Option<String>,
+    pub lock_mode: Option<String>,
+    pub wait_ms: Option<f64>,
+    pub blocking: Vec<BlockingNode>,
+}
+
+/// Builds the blocking chains rooted at the sessions that block others
+/// without waiting themselves.
+pub fn blocking_tree(sessions: &[Session], waits: &[LockWait]) -> Vec<BlockingNode> {
+    let waiting: HashSet<i64> = waits.iter().map(|w| w.waiting).collect();
+    let mut roots: Vec<i64> = vec![];
+    for wait in waits {
+        if !waiting.contains(&wait.blocking) && !roots.contains(&wait.blocking) {
+            roots.push(wait.blocking);
+        }
+    }
+    // a deadlock has no root until the server breaks it, start at any member
+    if roots.is_empty() {
+        roots.extend(waits.first().map(|w| w.blocking));
+    }
+    roots
+        .into_iter()
+        .map(|id| blocking_node(id, None, sessions, waits, &mut vec![id]))
+        .collect()
+}
+
+fn blocking_node(
+    id: i64,
+    wait: Option<&LockWait>,
+    sessions: &[Session],
+    waits: &[LockWait],
+    path: &mut Vec<i64>,
+) -> BlockingNode {
+    let session = sessions
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .unwrap_or(Session {
+            id,
+            ..Default::default()
+        });
+    let mut blocking = vec![];
+    for wait in waits.iter().filter(|w| w.blocking == id) {
+        if path.contains(&wait.waiting) {
+            continue;
+        }
+        path.push(wait.waiting);
+        blocking.push(blocking_node(
+            wait.waiting,
+            Some(wait),
+            sessions,
+            waits,
+            path,
+        ));
+        path.pop();
+    }
+    BlockingNode {
+        session,
+        relation: wait.and_then(|w| w.relation.clone()),
+        lock_mode: wait.and_then(|w| w.lock_mode.clone()),
+        wait_ms: wait.and_then(|w| w.wait_ms),
+        blocking,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{blocking_tree, LockWait, Session};
+
+    fn wait(waiting: i64, blocking: i64) -> LockWait {
+        LockWait {
+            waiting,
+            blocking,
+            relation: Some("accounts".to_string()),
+            lock_mode: Some("RowExclusiveLock".to_string()),
+            wait_ms: Some(1500.0),
+        }
+    }
+
+    #[test]
+    fn test_blocking_tree() {
+        let sessions = vec![Session {
+            id: 1,
+            query: Some("UPDATE accounts SET balance = 0".to_string()),
+            ..Default::default()
+        }];
+        // 1 blocks 2, 2 blocks 3 and 4
+        let tree = blocking_tree(&sessions, &[wait(2, 1), wait(3, 2), wait(4, 2)]);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].session, sessions[0]);
+        assert_eq!(tree[0].wait_ms, None);
+        assert_eq!(tree[0].blocking[0].session.id, 2);
+        assert_eq!(tree[0].blocking[0].blocking.len(), 2);
+
+        // deadlocks terminate
+        let tree = blocking_tree(&sessions, &[wait(1, 2), wait(2, 1)]);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].blocking[0].blocking.len(), 0);
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Dialect independent category of a plan node, the dialect's own label is kept in `operation`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum NodeKind {
+    FullScan,
+    IndexScan,
+    IndexOnlyScan,
+    Join,
+    Sort,
+    Aggregate,
+    TempTable,
+    Subquery,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Buffers {
+    pub shared_hit: u64,
+    pub shared_read: u64,
+    pub shared_dirtied: u64,
+    pub shared_written: u64,
+    pub temp_read: u64,
+    pub temp_written: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PlanNode {
+    pub kind: NodeKind,
+    pub operation: String,
+    pub relation: Option<String>,
+    pub index: Option<String>,
+    pub condition: Option<String>,
+    pub estimated_rows: Option<f64>,
+    pub actual_rows: Option<f64>,
+    pub loops: Option<f64>,
+    pub cost: Option<f64>,
+    pub actual_time_ms: Option<f64>,
+    pub buffers: Option<Buffers>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    /// Depth first iterator over this node and all of its descendants.
+    pub fn walk(&self) -> Vec<&PlanNode> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.walk());
+        }
+        nodes
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryPlan {
+    pub analyzed: bool,
+    pub root: PlanNode,
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    /// The plan exactly as the server returned it.
+    pub raw: Value,
+}
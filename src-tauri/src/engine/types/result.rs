@@ -1,12 +1,66 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::catalog::{Column, ForeignKey, PrimaryKey};
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct TableMetadata {
     pub table: String,
-    pub primary_key: Option<Vec<Value>>,
-    pub foreign_keys: Option<Vec<Value>>,
-    pub columns: Option<Vec<Value>>,
+    pub primary_key: Option<Vec<PrimaryKey>>,
+    pub foreign_keys: Option<Vec<ForeignKey>>,
+    pub columns: Option<Vec<Column>>,
+}
+
+/// Logical type of a result column. Tells the client how the cells of that
+/// column were encoded, e.g. `BigInt` values outside the JS safe integer range
+/// and every `Decimal` arrive as exact strings.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CellType {
+    Bool,
+    Integer,
+    BigInt,
+    Float,
+    Decimal,
+    Text,
+    Json,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Binary,
+    Array,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultColumn {
+    pub name: String,
+    pub db_type: String,
+    pub cell_type: CellType,
+    /// Table column the values are read from as is, `None` for expressions.
+    #[serde(default)]
+    pub origin: Option<ColumnOrigin>,
+    /// Set when the result also has the key of the origin table, so the row
+    /// can be found again to update the value.
+    #[serde(default)]
+    pub editable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ColumnOrigin {
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+}
+
+/// Key of a table the result reads from, `columns` are the positions of the
+/// key columns in the result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowKey {
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +68,52 @@ pub struct ResultSet {
     pub start_time: u64,
     pub end_time: u64,
     pub affected_rows: u64,
+    pub columns: Vec<ResultColumn>,
     pub rows: Vec<Value>,
     pub table: TableMetadata,
+    /// Keys of the tables whose columns are editable.
+    #[serde(default)]
+    pub row_keys: Vec<RowKey>,
+}
+
+/// Outcome of one statement of a transaction, `rows` are the rows returned
+/// by `RETURNING`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StatementResult {
+    pub affected_rows: u64,
+    pub last_insert_id: Option<i64>,
+    pub rows: Vec<Value>,
+    pub duration_ms: f64,
+}
+
+/// The statement a transaction failed on, `code` is the SQLSTATE for
+/// Postgresql and MySQL and the extended result code for SQLite.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StatementError {
+    pub index: usize,
+    pub message: String,
+    pub code: Option<String>,
+}
+
+/// Statements run until the first error, which rolls the transaction back.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TxResult {
+    pub statements: Vec<StatementResult>,
+    pub error: Option<StatementError>,
+    pub committed: bool,
+}
+
+impl TxResult {
+    /// Fails with the statement error, for callers that only need to know
+    /// whether the transaction went through.
+    pub fn into_result(self) -> anyhow::Result<Self> {
+        match &self.error {
+            Some(error) => Err(anyhow::anyhow!(
+                "Statement {} failed: {}",
+                error.index + 1,
+                error.message
+            )),
+            None => Ok(self),
+        }
+    }
 }
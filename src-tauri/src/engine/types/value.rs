@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat, Utc};
+use serde_json::Value;
+
+use super::result::{CellType, ResultColumn};
+
+/// Largest integer the webview can represent without losing precision (2^53 - 1).
+pub const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
+
+pub fn i64_to_json(value: i64) -> Value {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) {
+        Value::from(value)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+pub fn u64_to_json(value: u64) -> Value {
+    if value <= MAX_SAFE_INTEGER as u64 {
+        Value::from(value)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+/// Reads a number back out of a cell, also accepting the exact string form
+/// big integers and decimals are encoded as.
+pub fn json_to_f64(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|v| v.parse().ok()))
+}
+
+pub fn timestamptz_to_json<Tz: chrono::TimeZone>(value: DateTime<Tz>) -> Value
+where
+    Tz::Offset: std::fmt::Display,
+{
+    Value::String(value.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+}
+
+/// Zone `TimestampTz` cells are shifted to before they are shown. Cells are
+/// stored with the offset the server sent, the conversion only happens when a
+/// client asks for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayTimeZone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl FromStr for DisplayTimeZone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "utc" | "z" => Ok(DisplayTimeZone::Utc),
+            "local" => Ok(DisplayTimeZone::Local),
+            _ => FixedOffset::from_str(s)
+                .map(DisplayTimeZone::Fixed)
+                .map_err(|_| anyhow!("Invalid time zone: {}", s)),
+        }
+    }
+}
+
+impl DisplayTimeZone {
+    pub fn convert(&self, value: &str) -> Option<String> {
+        let date_time = DateTime::parse_from_rfc3339(value).ok()?;
+        let converted = match self {
+            DisplayTimeZone::Utc => timestamptz_to_json(date_time.with_timezone(&Utc)),
+            DisplayTimeZone::Local => timestamptz_to_json(date_time.with_timezone(&Local)),
+            DisplayTimeZone::Fixed(offset) => timestamptz_to_json(date_time.with_timezone(offset)),
+        };
+        converted.as_str().map(String::from)
+    }
+
+    /// Rewrites the `TimestampTz` cells of a row in this zone, everything else is left as is.
+    pub fn convert_row(&self, row: &mut Value, columns: &[ResultColumn]) {
+        for column in columns
+            .iter()
+            .filter(|c| c.cell_type == CellType::TimestampTz)
+        {
+            let converted = row[&column.name].as_str().and_then(|v| self.convert(v));
+            if let Some(converted) = converted {
+                row[&column.name] = Value::String(converted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use super::{i64_to_json, u64_to_json, DisplayTimeZone, MAX_SAFE_INTEGER};
+    use crate::engine::types::result::{CellType, ResultColumn};
+
+    #[test]
+    fn test_big_integers_are_exact_strings() {
+        assert_eq!(i64_to_json(MAX_SAFE_INTEGER), json!(MAX_SAFE_INTEGER));
+        assert_eq!(i64_to_json(i64::MIN), json!("-9223372036854775808"));
+        assert_eq!(u64_to_json(u64::MAX), json!("18446744073709551615"));
+    }
+
+    #[test]
+    fn test_convert_row_to_display_zone() {
+        let columns = vec![
+            ResultColumn {
+                name: "created_at".to_string(),
+                db_type: "timestamptz".to_string(),
+                cell_type: CellType::TimestampTz,
+                origin: None,
+                editable: false,
+            },
+            ResultColumn {
+                name: "day".to_string(),
+                db_type: "timestamp".to_string(),
+                cell_type: CellType::Timestamp,
+                origin: None,
+                editable: false,
+            },
+        ];
+        let mut row = json!({
+            "created_at": "2024-03-01T22:30:00.123456+00:00",
+            "day": "2024-03-01 22:30:00",
+        });
+        let tz = DisplayTimeZone::from_str("+02:00").unwrap();
+        tz.convert_row(&mut row, &columns);
+        assert_eq!(row["created_at"], json!("2024-03-02T00:30:00.123456+02:00"));
+        assert_eq!(row["day"], json!("2024-03-01 22:30:00"));
+        assert!(DisplayTimeZone::from_str("mars").is_err());
+    }
+}
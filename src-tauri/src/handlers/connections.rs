@@ -2,7 +2,10 @@ use crate::{
     database::queries,
     engine::{
         init::init_conn,
-        types::config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode},
+        types::{
+            config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode},
+            connection::InitiatedConnection,
+        },
     },
     handlers::task::cancel_task_token,
     state::ServiceAccess,
@@ -12,7 +15,7 @@ use crate::{
     },
 };
 use anyhow::anyhow;
-use log::info;
+use log::{error, info};
 use tauri::{command, AppHandle};
 
 #[command]
@@ -103,7 +106,10 @@ pub async fn init_connection(
     let conn = init_conn(config.clone(), app_handle.clone()).await;
     match conn {
         Ok(c) => match app_handle.connect(&c) {
-            Ok(schema) => Ok(schema),
+            Ok(schema) => {
+                warm_metadata(&c);
+                Ok(schema)
+            }
             Err(_) => {
                 cancel_task_token(app_handle.clone(), vec![config.id.to_string()]).await?;
                 Err(anyhow!("Failed to connect to the database").into())
@@ -133,6 +139,12 @@ pub async fn set_schema(
     info!("Set schema: {conn_id}, {schema}");
     let conn = app_handle.acquire_connection(conn_id.clone());
     let conn = conn.set_schema(schema.clone());
+    // the other dialects switch the schema of their pooled sessions in place
+    if conn.config.dialect != Dialect::Sqlite {
+        app_handle.db(|db| queries::update_connection_schema(db, &conn_id, &schema))?;
+        warm_metadata(&conn);
+        return Ok(app_handle.update_connection(conn)?);
+    }
     cancel_task_token(app_handle.clone(), vec![conn.config.id.to_string()]).await?;
     app_handle.clone().disconnect(&conn.config.id.to_string())?;
     let conn = init_conn(conn.config.clone(), app_handle.clone()).await;
@@ -140,6 +152,7 @@ pub async fn set_schema(
         Ok(c) => match app_handle.connect(&c) {
             Ok(schema) => {
                 app_handle.db(|db| queries::update_connection_schema(db, &conn_id, &schema))?;
+                warm_metadata(&c);
                 Ok(app_handle.update_connection(c)?)
             }
             Err(_) => {
@@ -153,3 +166,14 @@ pub async fn set_schema(
         }
     }
 }
+
+/// Fills the metadata cache without holding up the command, lookups that
+/// come first simply go to the server.
+fn warm_metadata(conn: &InitiatedConnection) {
+    let conn = conn.clone();
+    tokio::spawn(async move {
+        if let Err(e) = conn.warm_metadata().await {
+            error!("Warming metadata of {} failed: {}", conn.config.id, e);
+        }
+    });
+}
@@ -0,0 +1,64 @@
+use crate::{
+    database::{
+        history::{self, HistoryEntry, HistoryFilter, HistoryOutcome, HistoryRetention},
+        QueryType,
+    },
+    state::ServiceAccess,
+    utils::error::{CommandResult, Error},
+};
+use log::{error, info};
+use tauri::{command, AppHandle};
+
+/// Adds a running entry to the history, recording never fails the query.
+pub fn record_start(
+    app_handle: &AppHandle,
+    conn_id: &str,
+    tab_idx: Option<usize>,
+    query: &str,
+    query_type: QueryType,
+) -> Option<i64> {
+    app_handle
+        .db(|db| history::start_query(db, conn_id, tab_idx, query, query_type))
+        .map_err(|e| error!("Failed to record query history: {e}"))
+        .ok()
+}
+
+pub fn record_finish(app_handle: &AppHandle, id: Option<i64>, outcome: HistoryOutcome) {
+    let Some(id) = id else {
+        return;
+    };
+    if let Err(e) = app_handle.db(|db| history::finish_query(db, id, &outcome)) {
+        error!("Failed to record query history: {e}");
+    }
+}
+
+#[command]
+pub fn get_query_history(
+    app_handle: AppHandle,
+    filter: HistoryFilter,
+) -> CommandResult<Vec<HistoryEntry>> {
+    app_handle
+        .db(|db| history::search_history(db, &filter))
+        .map_err(Error::from)
+}
+
+/// Removes the history of `conn_id`, or the whole history.
+#[command]
+pub fn clear_query_history(app_handle: AppHandle, conn_id: Option<String>) -> CommandResult<usize> {
+    info!("Clear query history: {conn_id:?}");
+    app_handle
+        .db(|db| history::clear_history(db, conn_id.as_deref()))
+        .map_err(Error::from)
+}
+
+/// Applies `retention`, the default limits are applied on every start.
+#[command]
+pub fn prune_query_history(
+    app_handle: AppHandle,
+    retention: Option<HistoryRetention>,
+) -> CommandResult<usize> {
+    let retention = retention.unwrap_or_default();
+    app_handle
+        .db(|db| history::prune_history(db, &retention))
+        .map_err(Error::from)
+}
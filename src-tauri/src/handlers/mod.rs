@@ -1,3 +1,5 @@
 pub mod connections;
+pub mod history;
+pub mod monitor;
 pub mod queries;
 pub mod task;
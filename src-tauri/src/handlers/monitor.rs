@@ -0,0 +1,69 @@
+use crate::{
+    engine::types::monitor::{BlockingNode, Session, TopQuery, TopQueryOrder},
+    state::ServiceAccess,
+    utils::error::{CommandResult, Error},
+};
+use log::info;
+use tauri::{command, AppHandle};
+
+const DEFAULT_TOP_QUERIES_LIMIT: usize = 50;
+
+#[command]
+pub async fn get_top_queries(
+    app_handle: AppHandle,
+    conn_id: String,
+    order: Option<TopQueryOrder>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<TopQuery>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let order = order.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_TOP_QUERIES_LIMIT);
+    Ok(connection.get_top_queries(order, limit).await?)
+}
+
+#[command]
+pub async fn get_sessions(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Session>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.get_sessions().await?)
+}
+
+#[command]
+pub async fn cancel_session(
+    app_handle: AppHandle,
+    conn_id: String,
+    session_id: i64,
+) -> CommandResult<()> {
+    info!("Cancel session {session_id} on {conn_id}");
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.cancel_session(session_id).await?)
+}
+
+/// Closes the session, on sensitive connections only when `confirmed` is set.
+#[command]
+pub async fn terminate_session(
+    app_handle: AppHandle,
+    conn_id: String,
+    session_id: i64,
+    confirmed: bool,
+) -> CommandResult<()> {
+    info!("Terminate session {session_id} on {conn_id}");
+    let connection = app_handle.acquire_connection(conn_id);
+    if connection.config.metadata.sensitive && !confirmed {
+        return Err(Error::ConfirmationRequired {
+            action: format!("Terminating session {}", session_id),
+            statements: vec![],
+            token: None,
+        });
+    }
+    Ok(connection.terminate_session(session_id).await?)
+}
+
+/// Sessions that block others, each with the chain of sessions waiting on it.
+#[command]
+pub async fn get_lock_tree(
+    app_handle: AppHandle,
+    conn_id: String,
+) -> CommandResult<Vec<BlockingNode>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.get_lock_tree().await?)
+}
@@ -1,5 +1,24 @@
 use crate::{
-    database::QueryType,
+    database::{history::HistoryOutcome, QueryType},
+    engine::{
+        advisor, alter, edit, guard, quote,
+        types::{
+            advice::IndexAdvice,
+            alter::TableChange,
+            catalog::{
+                Column, CompositeType, Domain, EnumType, Event, Extension, ForeignKey, Inheritance,
+                MaterializedView, ObjectKind, Partition, PrimaryKey, Routine, SchemaSnapshot,
+                Sequence, SystemTime, TableStructure, Trigger, View,
+            },
+            config::Dialect,
+            connection::InitiatedConnection,
+            edit::{RowEdits, RowEditsResult},
+            plan::QueryPlan,
+            result::{ResultColumn, ResultSet, TxResult},
+            value::DisplayTimeZone,
+        },
+    },
+    handlers::history::{record_finish, record_start},
     query::{Events, QueryTask, QueryTaskEnqueueResult, QueryTaskResult, QueryTaskStatus},
     state::{AppState, ServiceAccess},
     utils::{
@@ -87,7 +106,79 @@ pub async fn sql_to_statements(_dialect: String, sql: &str) -> CommandResult<Vec
     Ok(statements.into_iter().map(|s| get_query_type(s)).collect())
 }
 
+/// Quotes a possibly schema qualified object name, the frontend should use this
+/// instead of interpolating names into statements.
+#[command]
+pub fn quote_identifier(dialect: Dialect, parts: Vec<String>) -> String {
+    let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+    quote::quote_qualified(&dialect, &parts)
+}
+
+#[command]
+pub fn quote_literal(dialect: Dialect, value: String) -> String {
+    quote::quote_literal(&dialect, &value)
+}
+
+/// Destructive statements on a sensitive connection are rejected, unless
+/// `confirmation` is the token of the error returned for those statements.
+fn confirm_statements(
+    conn: &InitiatedConnection,
+    statements: &[Statement],
+    confirmation: Option<&str>,
+) -> CommandResult<()> {
+    let destructive: Vec<String> = statements
+        .iter()
+        .filter(|s| guard::is_destructive(s))
+        .map(|s| s.to_string())
+        .collect();
+    confirm_destructive(conn, destructive, confirmation)
+}
+
+fn confirm_destructive(
+    conn: &InitiatedConnection,
+    destructive: Vec<String>,
+    confirmation: Option<&str>,
+) -> CommandResult<()> {
+    if !conn.config.metadata.sensitive || destructive.is_empty() {
+        return Ok(());
+    }
+    let token = md5_hash(&format!("{}\n{}", conn.config.id, destructive.join("\n")));
+    if confirmation == Some(token.as_str()) {
+        return Ok(());
+    }
+    Err(Error::ConfirmationRequired {
+        action: format!("Running {} destructive statement(s)", destructive.len()),
+        statements: destructive,
+        token: Some(token),
+    })
+}
+
+/// Read-only connections reject anything but queries before it's sent, the
+/// sessions are read-only too for what the parser can't tell apart.
+fn check_read_only(conn: &InitiatedConnection, statements: &[Statement]) -> CommandResult<()> {
+    if !conn.config.read_only() {
+        return Ok(());
+    }
+    let rejected = statements
+        .iter()
+        .map(|s| get_query_type(s.clone()))
+        .find(|t| !matches!(t, QueryType::Select | QueryType::Show));
+    match rejected {
+        Some(query_type) => Err(Error::ReadOnly(format!("{} statement", query_type))),
+        None => Ok(()),
+    }
+}
+
+/// Fails on read-only connections, for commands that generate their writes.
+fn check_writable(conn: &InitiatedConnection, action: &str) -> CommandResult<()> {
+    match conn.config.read_only() {
+        true => Err(Error::ReadOnly(action.to_string())),
+        false => Ok(()),
+    }
+}
+
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn enqueue_query(
     app_handle: AppHandle,
     state: State<'_, AppState>,
@@ -96,19 +187,17 @@ pub async fn enqueue_query(
     sql: &str,
     auto_limit: bool,
     table: Option<String>,
+    confirmation: Option<String>,
 ) -> CommandResult<QueryTaskEnqueueResult> {
     info!("Enqueue query on {conn_id}, tab:{tab_idx} - sql:{sql}");
     let conn = app_handle.acquire_connection(conn_id.clone());
-    let statements = Parser::parse_sql(
-        dialect_from_str(conn.config.dialect.to_string())
-            .expect("Failed to get dialect")
-            .as_ref(),
-        sql,
-    )
-    .unwrap_or_default();
+    let statements =
+        Parser::parse_sql(conn.config.dialect.parser_dialect().as_ref(), sql).unwrap_or_default();
     if statements.is_empty() {
         return Err(Error::from(anyhow!("No valid statements found")));
     }
+    check_read_only(&conn, &statements)?;
+    confirm_statements(&conn, &statements, confirmation.as_deref())?;
     let statements: Vec<(String, QueryType, String)> = statements
         .into_iter()
         .map(|s| {
@@ -126,6 +215,8 @@ pub async fn enqueue_query(
             (statement, query_type, md5_hash(&id))
         })
         .collect();
+    // results of a script are not edited in the grid
+    let editable = statements.len() == 1;
     let mut binding = state.cancel_tokens.lock().await;
     for (idx, stmt) in statements.iter().enumerate() {
         let temp_dir = app_handle
@@ -143,10 +234,13 @@ pub async fn enqueue_query(
         );
         binding.insert(stmt.2.clone(), token);
         let handle = app_handle.clone();
+        let history_id = record_start(&app_handle, &conn_id, Some(tab_idx), &stmt.0, stmt.1);
         tokio::spawn(async move {
             tokio::select! {
-                _ = task.cancel_token.cancelled() => {},
-                res = task.conn.execute_query(&task.query, task.query_type) => {
+                _ = task.cancel_token.cancelled() => {
+                    record_finish(&handle, history_id, HistoryOutcome::cancelled());
+                },
+                res = task.conn.execute_query(&task.query, task.query_type, editable) => {
                     match res {
                         Ok(mut result_set) => {
                             if let Some(table) = task.table.clone() {
@@ -154,20 +248,26 @@ pub async fn enqueue_query(
                             }
                             match write_query(&task.id, &result_set, task.query_type, temp_dir) {
                                 Ok(path) => {
+                                    let outcome = HistoryOutcome::completed(result_set.rows.len() as u64, result_set.affected_rows);
+                                    record_finish(&handle, history_id, outcome);
                                     handle
                                         .emit(Events::QueryFinished.as_str(), QueryTaskResult::success(task, result_set, path))
                                         .expect("Failed to emit query_finished event");
                                 },
-                                Err(e) =>
-                                handle
+                                Err(e) => {
+                                    record_finish(&handle, history_id, HistoryOutcome::failed(e.to_string()));
+                                    handle
                                         .emit(Events::QueryFinished.as_str(), QueryTaskResult::error(task, e))
-                                        .expect("Failed to emit query_finished event"),
+                                        .expect("Failed to emit query_finished event")
+                                },
                             }
                         }
-                        Err(e) =>
+                        Err(e) => {
+                            record_finish(&handle, history_id, HistoryOutcome::failed(e.to_string()));
                             handle
                                 .emit(Events::QueryFinished.as_str(), QueryTaskResult::error(task, e))
-                                .expect("Failed to emit query_finished event"),
+                                .expect("Failed to emit query_finished event")
+                        },
                     }
                 }
             }
@@ -186,6 +286,9 @@ pub struct QueryResultParams {
     pub path: String,
     pub page: usize,
     pub page_size: usize,
+    /// Shift `TimestampTz` cells to this zone ("utc", "local" or an offset like "+02:00"),
+    /// when omitted they keep the offset the server sent.
+    pub time_zone: Option<String>,
 }
 
 #[command]
@@ -195,23 +298,108 @@ pub async fn get_schemas(app_handle: AppHandle, conn_id: String) -> CommandResul
 }
 
 #[command]
-pub async fn get_views(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
+pub async fn get_views(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<View>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_views().await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_views(&schema).await?)
 }
 
+/// Runs `queries` in a transaction, a failing statement is reported in the
+/// result with its index rather than as an error. Destructive statements on
+/// sensitive connections need a `confirmation`, see [`confirm_statements`].
 #[command]
 pub async fn execute_tx(
     app_handle: AppHandle,
     conn_id: String,
     queries: Vec<&str>,
-) -> CommandResult<()> {
+    confirmation: Option<String>,
+) -> CommandResult<TxResult> {
     for query in &queries {
         info!("Execute tx on {}, sql:{query}", conn_id.clone());
     }
+    let connection = app_handle.acquire_connection(conn_id.clone());
+    let parsed = {
+        let dialect = connection.config.dialect.parser_dialect();
+        queries
+            .iter()
+            .map(|q| Parser::parse_sql(dialect.as_ref(), q))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|statements| statements.concat())
+    };
+    if connection.config.metadata.sensitive || connection.config.read_only() {
+        let statements = parsed.clone()?;
+        check_read_only(&connection, &statements)?;
+        confirm_statements(&connection, &statements, confirmation.as_deref())?;
+    }
+    // the transaction is one history entry, typed when its statements agree
+    let types: Vec<QueryType> = parsed
+        .iter()
+        .flatten()
+        .map(|s| get_query_type(s.clone()))
+        .collect();
+    let query_type = match types.first() {
+        Some(first) if types.iter().all(|t| t == first) => *first,
+        _ => QueryType::Other,
+    };
+    let history_id = record_start(
+        &app_handle,
+        &conn_id,
+        None,
+        &queries.join(";\n"),
+        query_type,
+    );
+    let result = connection.execute_tx(queries).await;
+    let outcome = match &result {
+        Ok(tx) => match &tx.error {
+            Some(e) => {
+                HistoryOutcome::failed(format!("Statement {} failed: {}", e.index + 1, e.message))
+            }
+            None => HistoryOutcome::completed(
+                tx.statements.iter().map(|s| s.rows.len() as u64).sum(),
+                tx.statements.iter().map(|s| s.affected_rows).sum(),
+            ),
+        },
+        Err(e) => HistoryOutcome::failed(e.to_string()),
+    };
+    record_finish(&app_handle, history_id, outcome);
+    Ok(result?)
+}
+
+/// Runs data-modifying `queries` and rolls them back, reporting the rows each
+/// one would affect.
+#[command]
+pub async fn dry_run(
+    app_handle: AppHandle,
+    conn_id: String,
+    queries: Vec<&str>,
+) -> CommandResult<TxResult> {
+    for query in &queries {
+        info!("Dry run on {}, sql:{query}", conn_id.clone());
+    }
     let connection = app_handle.acquire_connection(conn_id);
-    connection.execute_tx(queries).await?;
-    Ok(())
+    check_writable(&connection, "Dry run")?;
+    Ok(connection.dry_run(queries).await?)
+}
+
+/// Applies grid edits with generated, parameterized statements instead of
+/// SQL built by the frontend.
+#[command]
+pub async fn apply_row_edits(
+    app_handle: AppHandle,
+    conn_id: String,
+    table: String,
+    edits: RowEdits,
+    schema: Option<String>,
+) -> CommandResult<RowEditsResult> {
+    let connection = app_handle.acquire_connection(conn_id);
+    check_writable(&connection, "Editing rows")?;
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    info!("Apply row edits on {schema}.{table}");
+    Ok(edit::apply_row_edits(&connection, &schema, &table, &edits).await?)
 }
 
 #[command]
@@ -219,18 +407,16 @@ pub async fn execute_query(
     app_handle: AppHandle,
     conn_id: String,
     query: String,
+    confirmation: Option<String>,
 ) -> CommandResult<Value> {
     let conn = app_handle.acquire_connection(conn_id);
     info!("Execute query: {query}");
-    let statements = Parser::parse_sql(
-        dialect_from_str(conn.config.dialect.to_string())
-            .expect("Failed to get dialect")
-            .as_ref(),
-        &query,
-    )?;
+    let statements = Parser::parse_sql(conn.config.dialect.parser_dialect().as_ref(), &query)?;
     if statements.is_empty() {
         return Err(Error::from(anyhow!("No valid statements found")));
     }
+    check_read_only(&conn, &statements)?;
+    confirm_statements(&conn, &statements, confirmation.as_deref())?;
     let statements: Vec<(String, QueryType, String)> = statements
         .into_iter()
         .map(|s| {
@@ -239,8 +425,59 @@ pub async fn execute_query(
         })
         .collect();
     let stmt = &statements[0];
-    let result = conn.execute_query(&stmt.0, stmt.1).await?;
-    Ok(json!(result))
+    let conn_id = conn.config.id.to_string();
+    let history_id = record_start(&app_handle, &conn_id, None, &stmt.0, stmt.1);
+    let result = conn
+        .execute_query(&stmt.0, stmt.1, statements.len() == 1)
+        .await;
+    let outcome = match &result {
+        Ok(result) => HistoryOutcome::completed(result.rows.len() as u64, result.affected_rows),
+        Err(e) => HistoryOutcome::failed(e.to_string()),
+    };
+    record_finish(&app_handle, history_id, outcome);
+    Ok(json!(result?))
+}
+
+/// Explains the first statement of `sql`. With `analyze` the statement is
+/// executed (and rolled back) to collect actual row counts and timings.
+#[command]
+pub async fn explain_query(
+    app_handle: AppHandle,
+    conn_id: String,
+    sql: String,
+    analyze: bool,
+) -> CommandResult<QueryPlan> {
+    let conn = app_handle.acquire_connection(conn_id);
+    info!("Explain query: {sql}");
+    let statement = first_statement(&conn, &sql)?;
+    // analyzing runs the statement
+    if analyze {
+        check_read_only(&conn, std::slice::from_ref(&statement))?;
+    }
+    Ok(conn.explain_query(&statement.to_string(), analyze).await?)
+}
+
+/// Flags full scans of large tables, sorts and temporary tables in the plan of
+/// the first statement of `sql` and suggests indices for the filtered and
+/// joined columns no existing index covers.
+#[command]
+pub async fn get_index_advice(
+    app_handle: AppHandle,
+    conn_id: String,
+    sql: String,
+) -> CommandResult<IndexAdvice> {
+    let conn = app_handle.acquire_connection(conn_id);
+    info!("Index advice: {sql}");
+    let statement = first_statement(&conn, &sql)?;
+    Ok(advisor::advise_query(&conn, &statement).await?)
+}
+
+fn first_statement(conn: &InitiatedConnection, sql: &str) -> CommandResult<Statement> {
+    let statements = Parser::parse_sql(conn.config.dialect.parser_dialect().as_ref(), sql)?;
+    Ok(statements
+        .into_iter()
+        .next()
+        .ok_or(anyhow!("No valid statements found"))?)
 }
 
 #[command]
@@ -259,9 +496,29 @@ pub async fn query_results(
 ) -> CommandResult<Value> {
     info!("Query results: {:?}", params);
     let data = paginate_file(&params.path, params.page, params.page_size);
-    match data {
-        Ok(data) => Ok(Value::from(data)),
-        Err(..) => Err(Error::QueryExpired),
+    let data = match data {
+        Ok(data) => data,
+        Err(..) => return Err(Error::QueryExpired),
+    };
+    match params.time_zone {
+        Some(time_zone) => {
+            let time_zone: DisplayTimeZone = time_zone.parse()?;
+            let metadata =
+                read_to_string(params.path + ".metadata").map_err(|_| Error::QueryExpired)?;
+            let metadata: Value = serde_json::from_str(&metadata)?;
+            let columns: Vec<ResultColumn> =
+                serde_json::from_value(metadata["result_columns"].clone()).unwrap_or_default();
+            let data = data
+                .iter()
+                .map(|line| {
+                    let mut row: Value = serde_json::from_str(line)?;
+                    time_zone.convert_row(&mut row, &columns);
+                    Ok(row.to_string())
+                })
+                .collect::<Result<Vec<String>, serde_json::Error>>()?;
+            Ok(Value::from(data))
+        }
+        None => Ok(Value::from(data)),
     }
 }
 
@@ -270,16 +527,95 @@ pub async fn get_table_structure(
     app_handle: AppHandle,
     conn_id: String,
     table: String,
-) -> CommandResult<Value> {
+    schema: Option<String>,
+) -> CommandResult<TableStructure> {
     let connection = app_handle.acquire_connection(conn_id);
-    let result = connection.get_table_structure(table).await?;
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    let result = connection.get_table_structure(&schema, table).await?;
     Ok(result)
 }
 
+/// Applies a table designer change set in one transaction and returns the
+/// statements it ran, `preview` only returns them. MySQL commits each DDL
+/// statement implicitly, a failing statement leaves the earlier ones applied.
+/// Destructive changes on sensitive connections need `confirmation`, like
+/// [`execute_tx`].
+#[command]
+pub async fn alter_table(
+    app_handle: AppHandle,
+    conn_id: String,
+    table: String,
+    changes: Vec<TableChange>,
+    schema: Option<String>,
+    preview: Option<bool>,
+    confirmation: Option<String>,
+) -> CommandResult<Vec<String>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    let structure = connection.get_table_structure(&schema, table).await?;
+    let statements = alter::alter_table(&connection.config.dialect, &schema, &structure, &changes)?;
+    if !preview.unwrap_or_default() && !statements.is_empty() {
+        check_writable(&connection, "Altering a table")?;
+        // generated DDL the parser doesn't know is confirmed as well
+        let destructive = {
+            let dialect = connection.config.dialect.parser_dialect();
+            statements
+                .iter()
+                .filter(|sql| match Parser::parse_sql(dialect.as_ref(), sql) {
+                    Ok(parsed) => parsed.iter().any(guard::is_destructive),
+                    Err(_) => true,
+                })
+                .cloned()
+                .collect()
+        };
+        confirm_destructive(&connection, destructive, confirmation.as_deref())?;
+        for statement in &statements {
+            info!("Alter table, sql:{statement}");
+        }
+        connection
+            .execute_tx(statements.iter().map(String::as_str).collect())
+            .await?
+            .into_result()?;
+    }
+    Ok(statements)
+}
+
+/// Tables per page of `get_schema_snapshot`.
+const SNAPSHOT_PAGE_SIZE: usize = 1000;
+
+/// Catalog of `schema` one page of tables at a time, pass the returned
+/// `next` as `after` until it is empty.
+#[command]
+pub async fn get_schema_snapshot(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: String,
+    after: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<SchemaSnapshot> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let limit = limit.unwrap_or(SNAPSHOT_PAGE_SIZE);
+    Ok(connection
+        .get_schema_snapshot(&schema, after.as_deref(), limit)
+        .await?)
+}
+
+/// Drops the cached catalog of the connection and loads it again.
+#[command]
+pub async fn refresh_metadata(app_handle: AppHandle, conn_id: String) -> CommandResult<()> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.refresh_metadata().await?)
+}
+
 #[command]
-pub async fn get_columns(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
+pub async fn get_columns(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Column>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_columns(None).await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_columns(&schema, None).await?)
 }
 
 #[command]
@@ -287,9 +623,11 @@ pub async fn get_primary_key(
     app_handle: AppHandle,
     conn_id: String,
     table: String,
-) -> CommandResult<Vec<Value>> {
+    schema: Option<String>,
+) -> CommandResult<Vec<PrimaryKey>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_primary_key(&table).await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_primary_key(&schema, &table).await?)
 }
 
 #[command]
@@ -297,27 +635,201 @@ pub async fn get_foreign_keys(
     app_handle: AppHandle,
     conn_id: String,
     table: String,
-) -> CommandResult<Vec<Value>> {
+    schema: Option<String>,
+) -> CommandResult<Vec<ForeignKey>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_foreign_keys(&schema, &table).await?)
+}
+
+#[command]
+pub async fn get_triggers(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Trigger>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_triggers(&schema, None).await?)
+}
+
+#[command]
+pub async fn get_functions(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Routine>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_functions(&schema).await?)
+}
+
+#[command]
+pub async fn get_procedures(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Routine>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_procedures(&schema).await?)
+}
+
+#[command]
+pub async fn get_sequences(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Sequence>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_sequences(&schema).await?)
+}
+
+#[command]
+pub async fn get_enums(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<EnumType>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_foreign_keys(&table).await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_enums(&schema).await?)
 }
 
 #[command]
-pub async fn get_triggers(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
+pub async fn get_domains(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Domain>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_triggers().await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_domains(&schema).await?)
 }
 
 #[command]
-pub async fn get_functions(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
+pub async fn get_composite_types(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<CompositeType>> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_functions().await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_composite_types(&schema).await?)
 }
 
 #[command]
-pub async fn get_procedures(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
+pub async fn get_materialized_views(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<MaterializedView>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_materialized_views(&schema).await?)
+}
+
+#[command]
+pub async fn refresh_materialized_view(
+    app_handle: AppHandle,
+    conn_id: String,
+    name: String,
+    schema: Option<String>,
+    concurrently: Option<bool>,
+) -> CommandResult<()> {
+    let connection = app_handle.acquire_connection(conn_id);
+    check_writable(&connection, "Refreshing a materialized view")?;
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection
+        .refresh_materialized_view(&schema, &name, concurrently.unwrap_or_default())
+        .await?)
+}
+
+#[command]
+pub async fn get_extensions(
+    app_handle: AppHandle,
+    conn_id: String,
+) -> CommandResult<Vec<Extension>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.get_extensions().await?)
+}
+
+/// Parents, children and partitions in `schema`, of `table` when given.
+#[command]
+pub async fn get_inheritance(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+    table: Option<String>,
+) -> CommandResult<Vec<Inheritance>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection
+        .get_inheritance(&schema, table.as_deref())
+        .await?)
+}
+
+#[command]
+pub async fn get_events(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+) -> CommandResult<Vec<Event>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_events(&schema).await?)
+}
+
+#[command]
+pub async fn get_partitions(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+    table: Option<String>,
+) -> CommandResult<Vec<Partition>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_partitions(&schema, table.as_deref()).await?)
+}
+
+/// `CREATE` statement of any object, rebuilt from the catalog where the
+/// database has no `SHOW CREATE`.
+#[command]
+pub async fn get_object_ddl(
+    app_handle: AppHandle,
+    conn_id: String,
+    schema: Option<String>,
+    object: String,
+    kind: ObjectKind,
+) -> CommandResult<String> {
+    let connection = app_handle.acquire_connection(conn_id);
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection.get_object_ddl(&schema, &object, kind).await?)
+}
+
+/// Modules SQLite virtual tables can be created with, e.g. `fts5`.
+#[command]
+pub async fn get_modules(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<String>> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.get_modules().await?)
+}
+
+/// Current and past rows of a MariaDB system-versioned table.
+#[command]
+pub async fn get_table_history(
+    app_handle: AppHandle,
+    conn_id: String,
+    table: String,
+    time: SystemTime,
+    schema: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<ResultSet> {
     let connection = app_handle.acquire_connection(conn_id);
-    Ok(connection.get_procedures().await?)
+    let schema = schema.unwrap_or_else(|| connection.get_schema());
+    Ok(connection
+        .get_table_history(&schema, &table, &time, limit)
+        .await?)
 }
 
 #[command]
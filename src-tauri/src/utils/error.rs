@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{ser::SerializeStruct, Serialize};
 
 // A custom error type that represents all possible in our command
 #[derive(Debug, thiserror::Error)]
@@ -35,6 +35,15 @@ pub enum Error {
     QueryExpired,
     #[error("{0}")]
     TxError(String),
+    /// `statements` can be run by passing `token` back with them.
+    #[error("{action} on a sensitive connection requires confirmation")]
+    ConfirmationRequired {
+        action: String,
+        statements: Vec<String>,
+        token: Option<String>,
+    },
+    #[error("{0} is not allowed on a read-only connection")]
+    ReadOnly(String),
     #[error("Serdejson error: {0}")]
     SerdeJsonError(#[from] serde_json::Error),
     #[error("Tauri Error: {0}")]
@@ -47,7 +56,19 @@ impl Serialize for Error {
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        match self {
+            Error::ConfirmationRequired {
+                statements, token, ..
+            } => {
+                let mut state = serializer.serialize_struct("ConfirmationRequired", 4)?;
+                state.serialize_field("kind", "confirmation_required")?;
+                state.serialize_field("message", &self.to_string())?;
+                state.serialize_field("statements", statements)?;
+                state.serialize_field("token", token)?;
+                state.end()
+            }
+            _ => serializer.serialize_str(self.to_string().as_ref()),
+        }
     }
 }
 
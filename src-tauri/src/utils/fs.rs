@@ -99,6 +99,8 @@ pub fn write_query(
         "end_time": result_set.end_time,
         "count": result_set.rows.len(),
         "affected_rows": result_set.affected_rows,
+        "result_columns": result_set.columns,
+        "row_keys": result_set.row_keys,
         "table": result_set.table.table,
         "foreign_keys": result_set.table.foreign_keys,
         "primary_key": result_set.table.primary_key,
@@ -65,6 +65,7 @@ export const sslModes = [
 
 export type Metadata = {
   sensitive: boolean;
+  read_only?: boolean;
 };
 
 export type Credentials = Record<string, string | number>;
@@ -131,6 +132,54 @@ export type JSONValue =
 
 export type Row = Record<string, JSONValue>;
 
+export type RowEdits = {
+  updates: { row: Row; values: Row }[];
+  inserts: Row[];
+  deletes: { row: Row }[];
+  verify: boolean;
+};
+
+export type StatementResult = {
+  affected_rows: number;
+  last_insert_id: number | null;
+  rows: Row[];
+  duration_ms: number;
+};
+
+export type StatementError = {
+  index: number;
+  message: string;
+  code: string | null;
+};
+
+export type TxResult = {
+  statements: StatementResult[];
+  error: StatementError | null;
+  committed: boolean;
+};
+
+/** Error of statements that need confirming on a sensitive connection, the
+ * call succeeds when repeated with `token` as its `confirmation`. */
+export type ConfirmationRequired = {
+  kind: 'confirmation_required';
+  message: string;
+  statements: string[];
+  token: string | null;
+};
+
+export type RowConflict = {
+  kind: 'Update' | 'Delete';
+  index: number;
+  key: Row;
+  affected_rows: number;
+};
+
+export type RowEditsResult = {
+  applied: boolean;
+  affected_rows: number;
+  conflicts: RowConflict[];
+};
+
 export const QueryType = {
   Alter: 'Alter',
   Create: 'Create',
@@ -148,6 +197,429 @@ export const QueryTypes = Object.keys(QueryType) as QueryType[];
 
 export type QueryType = keyof typeof QueryType;
 
+export type HistoryStatus = 'Running' | 'Completed' | 'Error' | 'Cancelled';
+
+/** Times are milliseconds since the epoch. */
+export type HistoryEntry = {
+  id: number;
+  conn_id: string;
+  tab_idx: number | null;
+  query: string;
+  query_type: QueryType;
+  status: HistoryStatus;
+  error: string | null;
+  row_count: number | null;
+  affected_rows: number | null;
+  started_at: number;
+  finished_at: number | null;
+};
+
+export type HistoryFilter = {
+  search?: string;
+  conn_id?: string;
+  status?: HistoryStatus;
+  from?: number;
+  to?: number;
+  limit?: number;
+  offset?: number;
+};
+
+export type HistoryRetention = {
+  max_entries: number;
+  max_age_days: number;
+};
+
+export type CellType =
+  | 'bool'
+  | 'integer'
+  | 'big_int'
+  | 'float'
+  | 'decimal'
+  | 'text'
+  | 'json'
+  | 'date'
+  | 'time'
+  | 'timestamp'
+  | 'timestamp_tz'
+  | 'binary'
+  | 'array'
+  | 'other';
+
+export type ColumnOrigin = {
+  schema: string;
+  table: string;
+  column: string;
+};
+
+export type ResultColumn = {
+  name: string;
+  db_type: string;
+  cell_type: CellType;
+  origin: ColumnOrigin | null;
+  editable: boolean;
+};
+
+export type RowKey = {
+  schema: string;
+  table: string;
+  columns: number[];
+};
+
+export type PlanNodeKind =
+  | 'FullScan'
+  | 'IndexScan'
+  | 'IndexOnlyScan'
+  | 'Join'
+  | 'Sort'
+  | 'Aggregate'
+  | 'TempTable'
+  | 'Subquery'
+  | 'Other';
+
+export type PlanNode = {
+  kind: PlanNodeKind;
+  operation: string;
+  relation: string | null;
+  index: string | null;
+  condition: string | null;
+  estimated_rows: number | null;
+  actual_rows: number | null;
+  loops: number | null;
+  cost: number | null;
+  actual_time_ms: number | null;
+  buffers: {
+    shared_hit: number;
+    shared_read: number;
+    shared_dirtied: number;
+    shared_written: number;
+    temp_read: number;
+    temp_written: number;
+  } | null;
+  children: PlanNode[];
+};
+
+export type QueryPlan = {
+  analyzed: boolean;
+  root: PlanNode;
+  planning_time_ms: number | null;
+  execution_time_ms: number | null;
+  raw: JSONValue;
+};
+
+export type PlanIssue = {
+  kind: 'FullScan' | 'Filesort' | 'TempTable';
+  relation: string | null;
+  estimated_rows: number | null;
+  message: string;
+};
+
+export type IndexSuggestion = {
+  schema: string;
+  table: string;
+  columns: string[];
+  reason: 'Filter' | 'Join';
+  statement: string;
+};
+
+export type IndexAdvice = {
+  plan: QueryPlan;
+  issues: PlanIssue[];
+  suggestions: IndexSuggestion[];
+};
+
+export type TopQueryOrder = 'TotalTime' | 'Calls' | 'MeanTime' | 'Rows';
+
+export type TopQuery = {
+  query_id: string | null;
+  query: string;
+  database: string | null;
+  calls: number;
+  total_time_ms: number;
+  mean_time_ms: number;
+  rows: number;
+};
+
+export type Session = {
+  id: number;
+  user: string | null;
+  database: string | null;
+  client: string | null;
+  application: string | null;
+  state: string | null;
+  duration_ms: number | null;
+  wait_event: string | null;
+  query: string | null;
+};
+
+export type BlockingNode = {
+  session: Session;
+  relation: string | null;
+  lock_mode: string | null;
+  wait_ms: number | null;
+  blocking: BlockingNode[];
+};
+
+export type CatalogColumn = {
+  table_schema: string;
+  table_name: string;
+  column_name: string;
+  ordinal_position: number;
+  data_type: string;
+  column_type: string;
+  is_nullable: boolean;
+  column_default: string | null;
+  character_maximum_length: number | null;
+  collation_name: string | null;
+  comment: string | null;
+  generated: 'VIRTUAL' | 'STORED' | null;
+  generation_expression: string | null;
+  is_hidden: boolean;
+  is_auto_increment: boolean;
+  identity_generation: 'ALWAYS' | 'BY DEFAULT' | null;
+  default_kind: 'Literal' | 'Expression' | null;
+  allowed_values: string[];
+};
+
+export type PrimaryKey = {
+  constraint_name: string | null;
+  table_schema: string;
+  table_name: string;
+  column_name: string;
+  ordinal_position: number;
+};
+
+export type ForeignKey = {
+  constraint_name: string;
+  table_schema: string;
+  table_name: string;
+  column_name: string;
+  ordinal_position: number;
+  referenced_schema: string | null;
+  referenced_table_name: string;
+  referenced_column_name: string | null;
+  update_rule: string | null;
+  delete_rule: string | null;
+};
+
+export type Index = {
+  index_name: string;
+  table_schema: string;
+  table_name: string;
+  columns: string[];
+  is_unique: boolean;
+  is_primary: boolean;
+  index_type: string | null;
+  definition: string | null;
+};
+
+export type Constraint = {
+  constraint_name: string;
+  table_schema: string;
+  table_name: string;
+  constraint_type: 'Unique' | 'Check';
+  columns: string[];
+  check_clause: string | null;
+};
+
+export type ColumnDefinition = {
+  column_name: string;
+  column_type: string;
+  is_nullable: boolean;
+  column_default: string | null;
+  is_auto_increment?: boolean;
+  comment?: string | null;
+};
+
+export type TableChange =
+  | { kind: 'add_column'; column: ColumnDefinition }
+  | { kind: 'drop_column'; column_name: string }
+  | { kind: 'rename_column'; column_name: string; new_name: string }
+  | { kind: 'change_type'; column_name: string; column_type: string }
+  | { kind: 'set_nullable'; column_name: string; is_nullable: boolean }
+  | { kind: 'set_default'; column_name: string; column_default: string }
+  | { kind: 'drop_default'; column_name: string }
+  | { kind: 'add_index'; index_name: string; columns: string[]; is_unique: boolean }
+  | { kind: 'drop_index'; index_name: string }
+  | {
+      kind: 'add_foreign_key';
+      constraint_name: string;
+      columns: string[];
+      referenced_table_name: string;
+      referenced_columns: string[];
+      update_rule: string | null;
+      delete_rule: string | null;
+    }
+  | { kind: 'drop_foreign_key'; constraint_name: string }
+  | { kind: 'add_unique'; constraint_name: string; columns: string[] }
+  | { kind: 'add_check'; constraint_name: string; check_clause: string }
+  | { kind: 'drop_constraint'; constraint_name: string };
+
+export type TableStructure = {
+  table: string;
+  columns: CatalogColumn[];
+  indices: Index[];
+  primary_key: PrimaryKey[];
+  foreign_keys: ForeignKey[];
+  constraints: Constraint[];
+  triggers: Trigger[];
+};
+
+export type Trigger = {
+  trigger_name: string;
+  table_schema: string;
+  event_object_table: string;
+  event_manipulation: string | null;
+  action_timing: string | null;
+  action_statement: string | null;
+};
+
+export type View = {
+  table_schema: string;
+  table_name: string;
+  view_definition: string | null;
+};
+
+export type Routine = {
+  routine_schema: string;
+  routine_name: string;
+  routine_type: 'Function' | 'Procedure';
+  data_type: string | null;
+  routine_definition: string | null;
+};
+
+export type CatalogTable = {
+  table_schema: string;
+  table_name: string;
+  table_type: 'Table' | 'View';
+  comment: string | null;
+  engine: string | null;
+  row_format: string | null;
+  system_versioned: boolean;
+  virtual_module: string | null;
+  shadow: boolean;
+  without_rowid: boolean;
+  strict: boolean;
+};
+
+export type SchemaSnapshot = {
+  schema: string;
+  tables: CatalogTable[];
+  columns: CatalogColumn[];
+  primary_keys: PrimaryKey[];
+  foreign_keys: ForeignKey[];
+  indices: Index[];
+  triggers: Trigger[];
+  views: View[];
+  routines: Routine[];
+  next: string | null;
+};
+
+export type Sequence = {
+  sequence_schema: string;
+  sequence_name: string;
+  data_type: string | null;
+  start_value: string | null;
+  min_value: string | null;
+  max_value: string | null;
+  increment: string | null;
+  cycle: boolean;
+  last_value: string | null;
+  owned_by: string | null;
+};
+
+export type EnumType = {
+  type_schema: string;
+  type_name: string;
+  labels: string[];
+};
+
+export type Domain = {
+  domain_schema: string;
+  domain_name: string;
+  data_type: string;
+  is_nullable: boolean;
+  column_default: string | null;
+  check_constraints: string[];
+};
+
+export type CompositeType = {
+  type_schema: string;
+  type_name: string;
+  attributes: { attribute_name: string; data_type: string }[];
+};
+
+export type Extension = {
+  extension_name: string;
+  default_version: string | null;
+  installed_version: string | null;
+  extension_schema: string | null;
+  comment: string | null;
+};
+
+export type MaterializedView = {
+  table_schema: string;
+  table_name: string;
+  view_definition: string | null;
+  is_populated: boolean;
+  has_unique_index: boolean;
+};
+
+export type Inheritance = {
+  parent_schema: string;
+  parent_name: string;
+  child_schema: string;
+  child_name: string;
+  partition_bound: string | null;
+  partition_key: string | null;
+};
+
+export type MysqlEvent = {
+  event_schema: string;
+  event_name: string;
+  definer: string | null;
+  time_zone: string | null;
+  event_type: string | null;
+  execute_at: string | null;
+  interval_value: string | null;
+  interval_field: string | null;
+  starts: string | null;
+  ends: string | null;
+  status: string | null;
+  on_completion: string | null;
+  last_executed: string | null;
+  event_definition: string | null;
+  comment: string | null;
+};
+
+export type Partition = {
+  table_schema: string;
+  table_name: string;
+  partition_name: string;
+  subpartition_name: string | null;
+  ordinal_position: number;
+  partition_method: string | null;
+  partition_expression: string | null;
+  partition_description: string | null;
+  table_rows: number | null;
+  comment: string | null;
+};
+
+export type SystemTime =
+  | { kind: 'all' }
+  | { kind: 'as_of'; at: string }
+  | { kind: 'between'; from: string; to: string };
+
+export type ObjectKind =
+  | 'Table'
+  | 'View'
+  | 'MaterializedView'
+  | 'Function'
+  | 'Procedure'
+  | 'Trigger'
+  | 'Index'
+  | 'Sequence'
+  | 'Event';
+
 export type ResultSet = {
   loading: boolean;
   id?: string;
@@ -159,6 +631,8 @@ export type ResultSet = {
   foreign_keys?: Row[];
   primary_key?: Row[];
   columns?: Row[];
+  result_columns?: ResultColumn[];
+  row_keys?: RowKey[];
   start_time?: number;
   end_time?: number;
 } & (
@@ -211,7 +685,9 @@ export type RawQueryResult = Row[];
 export const TableEntity = {
   columns: 'columns',
   indices: 'indices',
+  primary_key: 'primary_key',
   foreign_keys: 'foreign_keys',
+  constraints: 'constraints',
   triggers: 'triggers',
 } as const;
 
@@ -220,7 +696,9 @@ export type TableStrucureEntityType = keyof typeof TableEntity;
 export const TableStrucureEntities = [
   TableEntity.columns,
   TableEntity.indices,
+  TableEntity.primary_key,
   TableEntity.foreign_keys,
+  TableEntity.constraints,
   TableEntity.triggers,
 ] as const;
 
@@ -114,6 +114,7 @@ const schema = z.object({
   color: z.enum(connectionColors),
   metadata: z.object({
     sensitive: z.boolean().default(false),
+    read_only: z.boolean().default(false),
   }),
 });
 
@@ -604,6 +605,28 @@ const AddConnectionForm = (props: AddConnectionFormProps) => {
                 </TooltipContent>
               </Tooltip>
             </Checkbox>
+            <Checkbox
+              checked={data('metadata.read_only')}
+              name="metadata.read_only"
+              onChange={(e) => setFields('metadata.read_only', e, true)}
+              class="flex items-center gap-2"
+            >
+              <Tooltip>
+                <TooltipTrigger as="div" class="flex items-center gap-2">
+                  <CheckboxControl class="rounded-md border-accent" />
+                  <div class="grid gap-1.5 leading-none">
+                    <CheckboxLabel class="text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70">
+                      Read-only
+                    </CheckboxLabel>
+                  </div>
+                </TooltipTrigger>
+                <TooltipContent class="max-w-lg">
+                  Only queries can be run on a read-only database, statements
+                  that change data or the schema are rejected and the sessions
+                  are opened read-only.
+                </TooltipContent>
+              </Tooltip>
+            </Checkbox>
           </div>
           <Show
             when={
@@ -66,7 +66,7 @@ export function ConnectionGrid(props: ConnectionGridProps) {
         config,
       });
       const { triggers, columns, routines, tables, schemas, views } =
-        await fetchSchemaEntities(config.id, config.dialect);
+        await fetchSchemaEntities(config.id, config.dialect, selectedSchema);
       await addConnectionTab({
         id: config.id,
         label: config.name,
@@ -7,7 +7,7 @@ import { DataTab } from './DataTab';
 
 export const Content = () => {
   const {
-    connections: { getConnection, getContent },
+    connections: { getContent, getContentData },
   } = useAppSelector();
 
   const key = () => getContent().key;
@@ -18,7 +18,7 @@ export const Content = () => {
         <QueryTab />
       </Match>
       <Match when={key() === ContentTab.TableStructure}>
-        <TableStructureTab tabIdx={getConnection().idx} />
+        <TableStructureTab structure={getContentData('TableStructure')} />
       </Match>
       <Match when={key() === ContentTab.Data}>
         <DataTab />
@@ -5,7 +5,7 @@ import { HiSolidPlay as Play } from 'solid-icons/hi';
 import { IoCopyOutline as Copy } from 'solid-icons/io';
 import { CgFormatIndentIncrease as EditIcon } from 'solid-icons/cg';
 import { useAppSelector } from 'services/Context';
-import { QueryTaskEnqueueResult } from 'interfaces';
+import { ConfirmationRequired, QueryTaskEnqueueResult } from 'interfaces';
 import { t } from 'utils/i18n';
 import { createStore } from 'solid-js/store';
 import { ActionRowButton } from './components/ActionRowButton';
@@ -54,6 +54,7 @@ export const QueryEditor = () => {
   const [loading, setLoading] = createSignal(false);
   const [tabFocusMode, setTabFocusMode] = createSignal(false);
   const [alertDialogOpen, setAlertDialogOpen] = createSignal(false);
+  const [confirmation, setConfirmation] = createSignal<ConfirmationRequired>();
   const [editor, setEditor] =
     createSignal<monaco.editor.IStandaloneCodeEditor>();
 
@@ -76,18 +77,41 @@ export const QueryEditor = () => {
     return editor()!.getModel()?.getValueInRange(editor()!.getSelection()!);
   };
 
-  const enqueueQuery = async (connId: string, tabIdx: number, sql: string) => {
+  const enqueueQuery = async (
+    connId: string,
+    tabIdx: number,
+    sql: string,
+    confirmed = false,
+    token?: string
+  ) => {
     setLoading(true);
     try {
       const { result_sets } = await invoke<QueryTaskEnqueueResult>(
         'enqueue_query',
-        { connId, sql, autoLimit: data().autoLimit, tabIdx }
+        {
+          connId,
+          sql,
+          autoLimit: data().autoLimit,
+          tabIdx,
+          confirmation: token,
+        }
       );
       updateDataContentTab(
         'result_sets',
         result_sets.map((id) => ({ loading: true, id }))
       );
     } catch (error) {
+      const required = error as ConfirmationRequired;
+      if (required.kind === 'confirmation_required' && !token) {
+        setLoading(false);
+        // the user already confirmed these statements in the dialog
+        if (confirmed && required.token) {
+          return enqueueQuery(connId, tabIdx, sql, true, required.token);
+        }
+        setConfirmation(required);
+        setAlertDialogOpen(true);
+        return;
+      }
       toast.error('Could not enqueue query', {
         description: (error as Error).message || (error as string),
       });
@@ -105,6 +129,7 @@ export const QueryEditor = () => {
   const onExecute = async () => {
     const { sql, conn } = getQuery();
     if (loading() || !sql) return;
+    setConfirmation(undefined);
     if (!conn.connection.metadata.sensitive) {
       return enqueueQuery(conn.id, conn.idx, sql);
     }
@@ -352,6 +377,13 @@ export const QueryEditor = () => {
               can be changed in the options on the settings screen.
               <br />
               Please confirm your action.
+              <Show when={confirmation()}>
+                {(required) => (
+                  <pre class="mt-2 max-h-40 overflow-auto text-xs">
+                    {required().statements.join(';\n')}
+                  </pre>
+                )}
+              </Show>
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
@@ -359,7 +391,9 @@ export const QueryEditor = () => {
             <AlertDialogAction
               onClick={() => {
                 const { sql, conn } = getQuery();
-                enqueueQuery(conn.id, conn.idx, sql);
+                const token = confirmation()?.token ?? undefined;
+                setConfirmation(undefined);
+                enqueueQuery(conn.id, conn.idx, sql, true, token);
               }}
               class="bg-destructive text-destructive-foreground"
             >
@@ -13,14 +13,13 @@ import {
 } from 'ag-grid-community';
 import AgGridSolid, { AgGridSolidRef } from 'ag-grid-solid';
 import { useAppSelector } from 'services/Context';
-import { loadingMessages, Row } from 'interfaces';
+import { loadingMessages, Row, RowEdits, RowEditsResult } from 'interfaces';
 import { Pagination } from './components/Pagination';
 import { NoResults } from './components/NoResults';
 import { Loader } from 'components/ui/loader';
 import { getAnyCase } from 'utils/utils';
 import { save } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
-import { deleteFrom, insert, update } from 'sql-bricks';
 import { t } from 'utils/i18n';
 import { Drawer } from './Table/Drawer';
 import { createStore, produce } from 'solid-js/store';
@@ -246,39 +245,40 @@ export const Results = (props: {
 
   const applyChanges = async () => {
     try {
-      const _table = table.name;
-      const queries = [];
-      if (Object.keys(changes['add']).length) {
-        const inserts = Object.keys(changes['add']).map((rowIndex) => {
-          const row = changes['add'];
-          return insert(_table).values(row[rowIndex].changes).toString();
-        });
-        queries.push(...inserts);
-      }
-      if (Object.keys(changes['update']).length) {
-        const updates = Object.keys(changes['update']).map((rowIndex) => {
-          const row = changes['update']?.[rowIndex];
-          return update(_table, { ...row.changes })
-            .where(row.condition)
-            .toString();
-        });
-        queries.push(...updates);
-      }
-      if (Object.keys(changes['delete']).length) {
-        const deletes = Object.keys(changes['delete']).map((rowIndex) => {
-          const row = changes['delete'];
-          return deleteFrom(_table).where(row[rowIndex].condition).toString();
+      const edits: RowEdits = {
+        inserts: Object.values(changes['add']).map((row) => row.changes as Row),
+        updates: Object.values(changes['update']).map((row) => ({
+          row: row.data,
+          values: row.changes,
+        })),
+        deletes: Object.values(changes['delete']).map((row) => ({
+          row: row.data,
+        })),
+        verify: true,
+      };
+      const count =
+        edits.inserts.length + edits.updates.length + edits.deletes.length;
+      if (count === 0) return;
+      const conn = getConnection();
+      const result = await invoke<RowEditsResult>('apply_row_edits', {
+        connId: conn.id,
+        table: table.name,
+        edits,
+      });
+      if (!result.applied) {
+        toast.error(t('console.table.edit_conflicts'), {
+          description: t('console.table.edit_conflicts_description', {
+            count: result.conflicts.length,
+          }),
         });
-        queries.push(...deletes);
+        return false;
       }
-      if (queries.length === 0) return;
-      const conn = getConnection();
-      await invoke('execute_tx', { queries, connId: conn.id });
       await invoke('invalidate_query', { path: data()?.path });
       const result_sets = await selectAllFrom(
         props.table!,
         conn.id,
-        getConnection().idx
+        getConnection().idx,
+        conn.connection.dialect
       );
       updateDataContentTab(
         'result_sets',
@@ -123,8 +123,8 @@ export const getColumnDefs = ({
             pk,
             fk: fk
               ? {
-                  table: getAnyCase(fk, 'table_name'),
-                  column: getAnyCase(fk, 'column_name'),
+                  table: getAnyCase(fk, 'referenced_table_name'),
+                  column: getAnyCase(fk, 'referenced_column_name'),
                 }
               : undefined,
           }),
@@ -2,11 +2,11 @@ import { createEffect, createMemo, createSignal, For } from 'solid-js';
 import {
   JSONValue,
   TableEntity,
+  TableStructure,
   TableStrucureEntities,
   TableStrucureEntityType,
 } from 'interfaces';
 import { t } from 'utils/i18n';
-import { useAppSelector } from 'services/Context';
 import {
   Tabs,
   TabsContent,
@@ -25,10 +25,7 @@ import {
 } from 'components/ui/table';
 import { TextField, TextFieldRoot } from 'components/ui/textfield';
 
-export const TableStructureTab = (props: { tabIdx: number }) => {
-  const {
-    connections: { getContentData },
-  } = useAppSelector();
+export const TableStructureTab = (props: { structure: TableStructure }) => {
   const [tab, setTab] = createSignal<TableStrucureEntityType>(
     TableEntity.columns
   );
@@ -37,12 +34,14 @@ export const TableStructureTab = (props: { tabIdx: number }) => {
   const [search, setSearch] = createSignal('');
 
   createEffect(() => {
-    const data = getContentData('TableStructure')[tab()];
-    const _columns = Object.keys(data?.[0] ?? {});
-    const _rows = data.map((row) => _columns.map((column) => row[column]));
+    const data: Record<string, unknown>[] = props.structure[tab()] ?? [];
+    const _columns = Object.keys(data[0] ?? {});
+    const _rows = data.map((row) =>
+      _columns.map((column) => row[column] as JSONValue)
+    );
     setColumns(_columns);
     setRows(_rows);
-  }, [props.tabIdx]);
+  });
 
   const filteredRows = createMemo(() => {
     const searchTerm = search().toLowerCase();
@@ -46,6 +46,7 @@ export const SchemaSelect = () => {
       updateSchemaDefinition,
       refreshEntities,
     },
+    backend: { quoteIdentifier },
   } = useAppSelector();
   const conn = getConnection();
   const [loading, setLoading] = createSignal(false);
@@ -57,7 +58,11 @@ export const SchemaSelect = () => {
       dialect: conn.connection.dialect,
     });
     const { triggers, routines, tables, schemas, columns, views } =
-      await fetchSchemaEntities(conn.id, conn.connection.dialect);
+      await fetchSchemaEntities(
+        conn.id,
+        conn.connection.dialect,
+        conn.selectedSchema
+      );
     updateSchemaDefinition({
       triggers,
       routines,
@@ -110,10 +115,12 @@ export const SchemaSelect = () => {
 
   const dropDatabase = async (schema: string) => {
     try {
-      const selectedSchema = getConnection().selectedSchema;
-      const query = 'DROP SCHEMA ' + schema;
+      const conn = getConnection();
+      const selectedSchema = conn.selectedSchema;
+      const name = await quoteIdentifier(conn.connection.dialect, schema);
+      const query = 'DROP SCHEMA ' + name;
       await invoke<ResultSet>('execute_query', {
-        connId: getConnection().id,
+        connId: conn.id,
         query,
       });
       if (selectedSchema === schema) {
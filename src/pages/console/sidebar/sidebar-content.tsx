@@ -51,6 +51,7 @@ export const DbSidebarContent = () => {
       getSchemaEntity,
       refreshEntities,
     },
+    backend: { quoteIdentifier, quoteLiteral },
   } = useAppSelector();
   const [itemCollapseState, setItemCollapseState] = createStore<
     Record<string, boolean>
@@ -60,11 +61,13 @@ export const DbSidebarContent = () => {
   const routines = createMemo(() => getSchemaEntity('routines'));
   const triggers = createMemo(() => getSchemaEntity('triggers'));
 
-  const showRoutine = async (routine: string) => {
+  const showRoutine = async (routine: string, type: string) => {
     try {
-      const query = 'SHOW CREATE PROCEDURE ' + routine;
+      const conn = getConnection();
+      const name = await quoteIdentifier(conn.connection.dialect, routine);
+      const query = `SHOW CREATE ${type.toUpperCase()} ${name}`;
       const res = await invoke<ResultSet>('execute_query', {
-        connId: getConnection().id,
+        connId: conn.id,
         query,
       });
       const data = { query, result_sets: [res], id: randomId() };
@@ -83,10 +86,13 @@ export const DbSidebarContent = () => {
 
   const showTrigger = async (trigger: string) => {
     try {
-      const schema = getConnection().selectedSchema;
-      const query = `SELECT * FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = '${schema}' and TRIGGER_NAME = '${trigger}'`;
+      const conn = getConnection();
+      const { dialect } = conn.connection;
+      const schema = await quoteLiteral(dialect, conn.selectedSchema);
+      const name = await quoteLiteral(dialect, trigger);
+      const query = `SELECT * FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = ${schema} and TRIGGER_NAME = ${name}`;
       const res = await invoke<ResultSet>('execute_query', {
-        connId: getConnection().id,
+        connId: conn.id,
         query,
       });
       const data = { query, result_sets: [res], id: randomId() };
@@ -118,7 +124,11 @@ export const DbSidebarContent = () => {
           rows: routines(),
           icon: () => <Function class="size-4 text-emerald-500" />,
           name: (row: Row) => getAnyCase(row, 'routine_name'),
-          show: (row: Row) => showRoutine(getAnyCase(row, 'routine_name')),
+          show: (row: Row) =>
+            showRoutine(
+              getAnyCase(row, 'routine_name'),
+              getAnyCase(row, 'routine_type')
+            ),
           statement: (row: Row) =>
             showCreateStatement(
               getAnyCase(row, 'routine_name'),
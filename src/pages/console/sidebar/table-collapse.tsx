@@ -8,7 +8,7 @@ import {
   newContentTab,
   TableStructureContentTabData,
 } from 'services/Connections';
-import { Column, ResultSet } from 'interfaces';
+import { Column, ConfirmationRequired, ResultSet } from 'interfaces';
 import { getAnyCase } from 'utils/utils';
 import {
   Collapsible,
@@ -50,6 +50,10 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
   const [dialogAction, setDialogAction] = createSignal<
     'drop' | 'truncate' | ''
   >('');
+  const [confirmation, setConfirmation] = createSignal<{
+    required: ConfirmationRequired;
+    action: 'drop' | 'truncate';
+  }>();
 
   const {
     connections: {
@@ -58,7 +62,7 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
       getConnection,
       updateDataContentTab,
     },
-    backend: { selectAllFrom },
+    backend: { selectAllFrom, quoteIdentifier },
   } = useAppSelector();
 
   const addTableStructureTab = async (table: string) => {
@@ -68,6 +72,7 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
         {
           connId: getConnection().id,
           table,
+          schema: getConnection().selectedSchema,
         }
       );
       addContentTab(newContentTab(table, 'TableStructure', data));
@@ -83,7 +88,12 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
       const conn = getConnection();
       const data = { result_sets: [], table, autoLimit: true };
       addContentTab(newContentTab(table, 'Data', data));
-      const result_sets = await selectAllFrom(table, conn.id, conn.idx);
+      const result_sets = await selectAllFrom(
+        table,
+        conn.id,
+        conn.idx,
+        conn.connection.dialect
+      );
       updateDataContentTab(
         'result_sets',
         result_sets.map((id) => ({ id, loading: true }))
@@ -95,13 +105,37 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
     }
   };
 
-  const dropTable = async () => {
+  // sensitive connections ask to confirm the statements before they run,
+  // returns false when the confirmation dialog was opened instead
+  const executeAction = async (
+    connId: string,
+    query: string,
+    action: 'drop' | 'truncate',
+    token?: string
+  ) => {
     try {
-      const query = `DROP ${props.entity === 'views' ? 'VIEW' : 'TABLE'} ${table}`;
       await invoke<ResultSet>('execute_query', {
-        connId: getConnection().id,
+        connId,
         query,
+        confirmation: token,
       });
+      return true;
+    } catch (error) {
+      const required = error as ConfirmationRequired;
+      if (required.kind === 'confirmation_required' && !token) {
+        setConfirmation({ required, action });
+        return false;
+      }
+      throw error;
+    }
+  };
+
+  const dropTable = async (token?: string) => {
+    try {
+      const conn = getConnection();
+      const name = await quoteIdentifier(conn.connection.dialect, table);
+      const query = `DROP ${props.entity === 'views' ? 'VIEW' : 'TABLE'} ${name}`;
+      if (!(await executeAction(conn.id, query, 'drop', token))) return;
       toast.success(t('sidebar.table_was_dropped', { table }));
       await props.refresh();
     } catch (error) {
@@ -111,13 +145,12 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
     }
   };
 
-  const truncateTable = async () => {
+  const truncateTable = async (token?: string) => {
     try {
-      const query = 'TRUNCATE TABLE ' + table;
-      await invoke<ResultSet>('execute_query', {
-        connId: getConnection().id,
-        query,
-      });
+      const conn = getConnection();
+      const name = await quoteIdentifier(conn.connection.dialect, table);
+      const query = 'TRUNCATE TABLE ' + name;
+      if (!(await executeAction(conn.id, query, 'truncate', token))) return;
       toast.success(t('sidebar.table_was_truncated', { table }));
     } catch (error) {
       toast.error('Could not truncate table', {
@@ -240,6 +273,49 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
           </AlertDialogFooter>
         </AlertDialogContent>
       </AlertDialog>
+      <AlertDialog
+        open={!!confirmation()}
+        onOpenChange={(open) => !open && setConfirmation(undefined)}
+      >
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle>Are you absolutely sure?</AlertDialogTitle>
+            <AlertDialogDescription>
+              This {getConnection().connection.name} database is marked as
+              sensitive and you are making a sensitive query type. This behavior
+              can be changed in the options on the settings screen.
+              <br />
+              Please confirm your action.
+              <Show when={confirmation()}>
+                {(pending) => (
+                  <pre class="mt-2 max-h-40 overflow-auto text-xs">
+                    {pending().required.statements.join(';\n')}
+                  </pre>
+                )}
+              </Show>
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogClose>Cancel</AlertDialogClose>
+            <AlertDialogAction
+              onClick={() => {
+                const pending = confirmation();
+                const token = pending?.required.token;
+                setConfirmation(undefined);
+                if (!pending || !token) return;
+                if (pending.action === 'drop') {
+                  dropTable(token);
+                } else {
+                  truncateTable(token);
+                }
+              }}
+              class="bg-destructive text-destructive-foreground"
+            >
+              Continue
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
     </>
   );
 };
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import {
+  DialectType,
   QueryMetadataResult,
   QueryTaskEnqueueResult,
   RawQueryResult,
@@ -29,10 +30,17 @@ export const BackendService = () => {
     return JSON.parse(res) as unknown as QueryMetadataResult;
   };
 
+  const quoteIdentifier = (dialect: DialectType, ...parts: string[]) =>
+    invoke<string>('quote_identifier', { dialect, parts });
+
+  const quoteLiteral = (dialect: DialectType, value: string) =>
+    invoke<string>('quote_literal', { dialect, value });
+
   const selectAllFrom = async (
     table: string,
     connId: string,
-    tabIdx: number
+    tabIdx: number,
+    dialect: DialectType
   ) => {
     let pk = await invoke<RawQueryResult>('get_primary_key', {
       connId,
@@ -45,9 +53,12 @@ export const BackendService = () => {
       });
       pk = [columns[0]];
     }
+    const columns = await Promise.all(
+      pk.map((c) => quoteIdentifier(dialect, getAnyCase(c, 'column_name')))
+    );
     const sql = select()
-      .from(table)
-      .orderBy(...pk.map((c) => getAnyCase(c, 'column_name')))
+      .from(await quoteIdentifier(dialect, table))
+      .orderBy(...columns)
       .toString();
     const { result_sets } = await invoke<QueryTaskEnqueueResult>(
       'enqueue_query',
@@ -80,5 +91,7 @@ export const BackendService = () => {
     downloadCsv,
     downloadJSON,
     selectAllFrom,
+    quoteIdentifier,
+    quoteLiteral,
   };
 };
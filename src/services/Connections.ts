@@ -8,8 +8,9 @@ import {
   RawQueryResult,
   ResultSet,
   Row,
+  SchemaSnapshot,
   Table,
-  TableEntity,
+  TableStructure,
 } from '../interfaces';
 import { columnsToTables, randomId } from 'utils/utils';
 import { invoke } from '@tauri-apps/api/core';
@@ -63,8 +64,9 @@ export const newContentTab = <T extends ContentComponentKeys>(
           table: label,
           columns: [],
           indices: [],
-          foreign_keys: [],
           primary_key: [],
+          foreign_keys: [],
+          constraints: [],
           triggers: [],
         },
         key,
@@ -84,13 +86,7 @@ export type QueryContentTabData = {
   id: string;
 };
 
-export type TableStructureContentTabData = {
-  table: string;
-  [TableEntity.columns]: Row[];
-  [TableEntity.indices]: Row[];
-  [TableEntity.triggers]: Row[];
-  [TableEntity.foreign_keys]: Row[];
-};
+export type TableStructureContentTabData = TableStructure;
 
 export type DataContentTabData = {
   result_sets: ResultSet[];
@@ -485,14 +481,33 @@ export const ConnectionsService = () => {
       setQueryIdx((s) => (s + 1) % getContentData('Query').result_sets.length);
   };
 
-  const fetchSchemaEntities = async (connId: string, dialect: DialectType) => {
-    const [_schemas, columns, routines, triggers, _views] = await Promise.all([
+  const fetchSchemaSnapshot = async (connId: string, schema: string) => {
+    const pages: SchemaSnapshot[] = [];
+    let after: string | null = null;
+    do {
+      const page: SchemaSnapshot = await invoke<SchemaSnapshot>(
+        'get_schema_snapshot',
+        { connId, schema, after }
+      );
+      pages.push(page);
+      after = page.next;
+    } while (after);
+    return pages;
+  };
+
+  const fetchSchemaEntities = async (
+    connId: string,
+    dialect: DialectType,
+    schema: string
+  ) => {
+    const [_schemas, pages] = await Promise.all([
       invoke<RawQueryResult>('get_schemas', { connId }),
-      invoke<RawQueryResult>('get_columns', { connId }),
-      invoke<RawQueryResult>('get_procedures', { connId }),
-      invoke<RawQueryResult>('get_triggers', { connId }),
-      invoke<RawQueryResult>('get_views', { connId }),
+      fetchSchemaSnapshot(connId, schema),
     ]);
+    const columns: Row[] = pages.flatMap((p) => p.columns);
+    const routines: Row[] = pages.flatMap((p) => p.routines);
+    const triggers: Row[] = pages.flatMap((p) => p.triggers);
+    const _views: Row[] = pages.flatMap((p) => p.views);
 
     const { views, tables } = columnsToTables(columns, _views, dialect) ?? [];
     const schemas = _schemas.map((d) => String(d['schema'])).sort();
@@ -502,9 +517,13 @@ export const ConnectionsService = () => {
   const refreshEntities = async () => {
     const config = getConnection().connection;
     setLoading(true);
-    await invoke('init_connection', { config });
+    await invoke('refresh_metadata', { connId: config.id });
     const { triggers, routines, tables, schemas, columns, views } =
-      await fetchSchemaEntities(config.id, config.dialect);
+      await fetchSchemaEntities(
+        config.id,
+        config.dialect,
+        getConnection().selectedSchema
+      );
     updateConnectionTab('schemas', schemas);
     updateSchemaDefinition({
       triggers,